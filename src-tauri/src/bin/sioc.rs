@@ -0,0 +1,414 @@
+//! Headless command-line client that reuses the desktop app's saved connections.
+//!
+//! All output is newline-delimited JSON so it can be piped into `jq` or other tools.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use socket_io_client_lib::event_sink::{BroadcastSink, SocketNotification};
+use socket_io_client_lib::load_test::{LoadTest, LoadTestConfig};
+use socket_io_client_lib::socket_client::SocketManager;
+use socket_io_client_lib::{db, history_writer, mcp_server, redaction, secrets};
+use tokio::sync::broadcast;
+
+const USAGE: &str = "\
+Usage: sioc [--db <path>] <command> [args]
+
+Commands:
+  list                                   List saved connections
+  listen <id> [--event <name>]... [--duration <secs>]
+                                         Connect and print events until Ctrl-C
+  emit <id> <event> [payload] [--timeout <secs>]
+                                         Connect, emit one event and disconnect
+  auto-send <id> [--timeout <secs>]      Connect and send the connection's auto-send messages
+  load-test <id> --clients <n> --duration <secs> [--ramp-up <secs>] [--think-time <ms>] [--ack] [--ack-timeout <ms>] [--workers <n>]
+                                         Run the auto-send messages from many virtual clients
+                                         and print a latency report
+  mcp                                    Serve MCP tools over stdin/stdout for AI assistants
+
+Options:
+  --db <path>    SQLite database to use (default: the desktop app's database, or $SIOC_DB)
+                 Secrets protected by a passphrase are unlocked with $SIOC_PASSPHRASE
+  -h, --help     Show this help";
+
+const APP_IDENTIFIER: &str = "com.socketio.client";
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+/// Upper bound on draining notifications still held by socket callbacks at exit
+const PRINTER_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let result = run(std::env::args().skip(1).collect()).await;
+    // Write out queued event history before exiting
+    history_writer::shutdown();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("sioc: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: Vec<String>) -> Result<(), String> {
+    let mut args = Args::new(args);
+    if args.flag("-h") || args.flag("--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let db_path = match args.option("--db")? {
+        Some(path) => PathBuf::from(path),
+        None => default_db_path()?,
+    };
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create database dir: {}", e))?;
+    }
+    db::init_db(&db_path).map_err(|e| format!("Failed to initialize database: {}", e))?;
+    let key_dir = db_path.parent().unwrap_or(Path::new("."));
+    secrets::init(key_dir).map_err(|e| format!("Failed to load secrets key: {}", e))?;
+    if let Ok(passphrase) = std::env::var("SIOC_PASSPHRASE") {
+        secrets::unlock(&passphrase)?;
+    }
+
+    let command = args
+        .positional()
+        .ok_or_else(|| format!("missing command\n\n{}", USAGE))?;
+
+    match command.as_str() {
+        "list" => {
+            args.finish()?;
+            list()
+        }
+        "listen" => {
+            let events = args.options("--event")?;
+            let duration = args.secs("--duration")?;
+            let connection_id = args.connection_id()?;
+            args.finish()?;
+            listen(connection_id, events, duration).await
+        }
+        "emit" => {
+            let timeout = args.secs("--timeout")?.unwrap_or(DEFAULT_TIMEOUT_SECS);
+            let connection_id = args.connection_id()?;
+            let event_name = args.positional().ok_or("missing event name")?;
+            let payload = args.positional().unwrap_or_else(|| "{}".to_string());
+            args.finish()?;
+            emit(connection_id, &event_name, &payload, timeout).await
+        }
+        "auto-send" => {
+            let timeout = args.secs("--timeout")?.unwrap_or(DEFAULT_TIMEOUT_SECS);
+            let connection_id = args.connection_id()?;
+            args.finish()?;
+            auto_send(connection_id, timeout).await
+        }
+        "load-test" => {
+            let config = LoadTestConfig {
+                clients: args.number("--clients")?.ok_or("--clients is required")?,
+                duration_ms: args.secs("--duration")?.ok_or("--duration is required")? * 1000,
+                ramp_up_ms: args.secs("--ramp-up")?.unwrap_or(0) * 1000,
+                think_time_ms: args.number("--think-time")?.unwrap_or(0),
+                ack: args.flag("--ack"),
+                ack_timeout_ms: args.number("--ack-timeout")?,
+                workers: args.number("--workers")?,
+                scenario: None,
+                connection_id: args.connection_id()?,
+            };
+            args.finish()?;
+            load_test(config).await
+        }
+        "mcp" => {
+            args.finish()?;
+            mcp().await
+        }
+        other => Err(format!("unknown command: {}\n\n{}", other, USAGE)),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+fn list() -> Result<(), String> {
+    let rows = db::list_connections().map_err(|e| e.to_string())?;
+    for c in rows {
+        println!(
+            "{}",
+            json!({ "id": c.id, "name": c.name, "url": c.url, "namespace": c.namespace })
+        );
+    }
+    Ok(())
+}
+
+async fn listen(
+    connection_id: i64,
+    events: Vec<String>,
+    duration: Option<u64>,
+) -> Result<(), String> {
+    let (manager, rx) = new_manager();
+    let printer = tokio::spawn(print_notifications(rx));
+
+    connect(&manager, connection_id).await?;
+    for event_name in &events {
+        manager.add_listener(connection_id, event_name)?;
+    }
+
+    match duration {
+        Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+        None => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+
+    let result = manager.disconnect(connection_id, "cli");
+    finish_printer(manager, printer).await;
+    result
+}
+
+async fn emit(
+    connection_id: i64,
+    event_name: &str,
+    payload: &str,
+    timeout: u64,
+) -> Result<(), String> {
+    let (manager, rx) = new_manager();
+    let printer = tokio::spawn(print_notifications(rx));
+
+    connect(&manager, connection_id).await?;
+    wait_until_connected(&manager, connection_id, timeout).await?;
+
+    let result = manager
+        .emit_message_async(connection_id, event_name.to_string(), payload.to_string())
+        .await;
+    let (stored, redacted) = redaction::redact_payload(connection_id, payload);
+    if result.is_ok()
+        && let Err(e) = db::add_emit_log(connection_id, event_name, &stored, redacted)
+    {
+        log::warn!("Failed to save emit log: {}", e);
+    }
+
+    let _ = manager.disconnect(connection_id, "cli");
+    finish_printer(manager, printer).await;
+    result
+}
+
+async fn auto_send(connection_id: i64, timeout: u64) -> Result<(), String> {
+    let (manager, rx) = new_manager();
+    let printer = tokio::spawn(print_notifications(rx));
+
+    connect(&manager, connection_id).await?;
+    wait_until_connected(&manager, connection_id, timeout).await?;
+
+    let auto_send_manager = manager.clone();
+    tokio::task::spawn_blocking(move || auto_send_manager.do_auto_send(connection_id))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?;
+
+    let result = manager.disconnect(connection_id, "cli");
+    finish_printer(manager, printer).await;
+    result
+}
+
+async fn load_test(config: LoadTestConfig) -> Result<(), String> {
+    let test = Arc::new(LoadTest::new(config)?);
+
+    let cancel_test = test.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancel_test.cancel();
+        }
+    });
+
+    let report = tokio::task::spawn_blocking(move || test.run())
+        .await
+        .map_err(|e| format!("Task error: {}", e))?;
+    let json = serde_json::to_string(&report).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Connections opened by the assistant belong to this process, not the desktop app
+async fn mcp() -> Result<(), String> {
+    let (manager, _rx) = new_manager();
+    let input = tokio::io::BufReader::new(tokio::io::stdin());
+    let result = mcp_server::serve_stdio(manager.clone(), input, tokio::io::stdout())
+        .await
+        .map_err(|e| format!("MCP stdio transport failed: {}", e));
+
+    for connection_id in manager.get_all_statuses().into_keys() {
+        let _ = manager.disconnect(connection_id, "cli");
+    }
+    result
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn new_manager() -> (SocketManager, broadcast::Receiver<SocketNotification>) {
+    let sink = BroadcastSink::new(1024);
+    let rx = sink.subscribe();
+    (SocketManager::new(Arc::new(sink)), rx)
+}
+
+async fn connect(manager: &SocketManager, connection_id: i64) -> Result<(), String> {
+    let manager = manager.clone();
+    tokio::task::spawn_blocking(move || manager.connect(connection_id))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+async fn wait_until_connected(
+    manager: &SocketManager,
+    connection_id: i64,
+    timeout: u64,
+) -> Result<(), String> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout);
+    loop {
+        match manager.get_status_for_connection(connection_id).as_str() {
+            "connected" => return Ok(()),
+            "error" => return Err("Connection failed".to_string()),
+            _ => {}
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err("Connection timeout".to_string());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+async fn print_notifications(mut rx: broadcast::Receiver<SocketNotification>) {
+    loop {
+        match rx.recv().await {
+            Ok(notification) => match serde_json::to_string(&notification) {
+                Ok(line) => println!("{}", line),
+                Err(e) => log::warn!("Failed to serialize notification: {}", e),
+            },
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                eprintln!("sioc: dropped {} notifications", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Drop the manager, closing the notification channel, and wait for the printer to drain it.
+///
+/// Socket callbacks may hold the sink briefly after disconnecting, so the wait is bounded.
+async fn finish_printer(manager: SocketManager, printer: tokio::task::JoinHandle<()>) {
+    drop(manager);
+    let abort = printer.abort_handle();
+    let drained = tokio::time::timeout(PRINTER_DRAIN_TIMEOUT, printer).await;
+    if drained.is_err() {
+        abort.abort();
+    }
+}
+
+/// Same location Tauri resolves `app_data_dir()` to for the desktop app
+fn default_db_path() -> Result<PathBuf, String> {
+    if let Ok(path) = std::env::var("SIOC_DB") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE"));
+
+    #[cfg(target_os = "macos")]
+    let data_dir = home
+        .map(|home| PathBuf::from(home).join("Library/Application Support"))
+        .map_err(|_| "Failed to get home directory".to_string())?;
+
+    #[cfg(target_os = "windows")]
+    let data_dir = std::env::var("APPDATA")
+        .map(PathBuf::from)
+        .or_else(|_| home.map(|home| PathBuf::from(home).join("AppData").join("Roaming")))
+        .map_err(|_| "Failed to get APPDATA directory".to_string())?;
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let data_dir = match std::env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home
+            .map(|home| PathBuf::from(home).join(".local/share"))
+            .map_err(|_| "Failed to get home directory".to_string())?,
+    };
+
+    Ok(data_dir.join(APP_IDENTIFIER).join("socket-io-client.db"))
+}
+
+/// Minimal argument parser: options may appear anywhere, and are consumed before positionals
+struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    fn new(args: Vec<String>) -> Self {
+        Self { args }
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        match self.args.iter().position(|a| a == name) {
+            Some(index) => {
+                self.args.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn option(&mut self, name: &str) -> Result<Option<String>, String> {
+        let Some(index) = self.args.iter().position(|a| a == name) else {
+            return Ok(None);
+        };
+        if index + 1 >= self.args.len() {
+            return Err(format!("{} requires a value", name));
+        }
+        let value = self.args.remove(index + 1);
+        self.args.remove(index);
+        Ok(Some(value))
+    }
+
+    fn options(&mut self, name: &str) -> Result<Vec<String>, String> {
+        let mut values = Vec::new();
+        while let Some(value) = self.option(name)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    fn secs(&mut self, name: &str) -> Result<Option<u64>, String> {
+        self.option(name)?
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| format!("{} expects a number of seconds", name))
+            })
+            .transpose()
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        self.option(name)?
+            .map(|v| v.parse().map_err(|_| format!("{} expects a number", name)))
+            .transpose()
+    }
+
+    fn positional(&mut self) -> Option<String> {
+        let index = self.args.iter().position(|a| !a.starts_with("--"))?;
+        Some(self.args.remove(index))
+    }
+
+    fn connection_id(&mut self) -> Result<i64, String> {
+        self.positional()
+            .ok_or("missing connection id")?
+            .parse()
+            .map_err(|_| "connection id must be a number".to_string())
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.args.first() {
+            Some(arg) => Err(format!("unexpected argument: {}", arg)),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::profiles::{
+    self, ConnectionProfile, ImportOptions, ImportedProfile, ProfileEvent, ProfilePinnedMessage,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionFormat {
+    /// Postman collection (v2.x) with Socket.IO requests
+    Postman,
+    /// Firecamp collection export
+    Firecamp,
+}
+
+impl CollectionFormat {
+    /// Recognize a collection file from its top-level layout
+    pub fn detect(collection: &Value) -> Option<Self> {
+        let info = collection.get("info");
+        let postman_schema = info
+            .and_then(|info| info.get("schema"))
+            .and_then(Value::as_str)
+            .is_some_and(|schema| schema.contains("getpostman.com"));
+        if postman_schema || info.and_then(|info| info.get("_postman_id")).is_some() {
+            return Some(Self::Postman);
+        }
+        if collection.get("requests").is_some_and(Value::is_array) {
+            return Some(Self::Firecamp);
+        }
+        None
+    }
+}
+
+/// A collection item that was not imported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedItem {
+    /// Folder path and name of the item, e.g. `Chat / Send message`
+    pub item: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedCollection {
+    pub profiles: Vec<ConnectionProfile>,
+    pub unmapped: Vec<UnmappedItem>,
+}
+
+impl ParsedCollection {
+    fn skip(&mut self, item: &str, reason: impl Into<String>) {
+        self.unmapped.push(UnmappedItem {
+            item: item.to_string(),
+            reason: reason.into(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionImportReport {
+    pub format: CollectionFormat,
+    pub imported: Vec<ImportedProfile>,
+    pub unmapped: Vec<UnmappedItem>,
+}
+
+// ============================================================================
+// Shared Mapping
+// ============================================================================
+
+fn str_field<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(Value::as_str))
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// A URL given as a string or as `{ "raw": ... }`
+fn url_field(value: &Value) -> Option<String> {
+    match value.get("url")? {
+        Value::String(url) => Some(url.clone()),
+        url => url.get("raw").and_then(Value::as_str).map(str::to_string),
+    }
+    .filter(|url| !url.trim().is_empty())
+}
+
+/// Payloads are stored as text; structured values are serialized
+fn payload_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "{}".to_string(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Enabled `{ key, value }` headers as an object
+fn headers(list: Option<&Value>) -> Map<String, Value> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|header| {
+            !header
+                .get("disabled")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        })
+        .filter_map(|header| {
+            let key = str_field(header, &["key", "name"])?;
+            let value = header.get("value").cloned().unwrap_or(Value::Null);
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Listeners given as names or as `{ name|event, enabled|listen }`
+fn listeners(list: Option<&Value>) -> Vec<ProfileEvent> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|listener| match listener {
+            Value::String(name) if !name.trim().is_empty() => Some(ProfileEvent {
+                event_name: name.clone(),
+                is_listening: true,
+            }),
+            Value::Object(_) => Some(ProfileEvent {
+                event_name: str_field(listener, &["name", "event"])?.to_string(),
+                is_listening: ["enabled", "listen"]
+                    .iter()
+                    .find_map(|key| listener.get(*key).and_then(Value::as_bool))
+                    .unwrap_or(true),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn options_json(headers: Map<String, Value>) -> String {
+    if headers.is_empty() {
+        "{}".to_string()
+    } else {
+        let mut options = Map::new();
+        options.insert("extraHeaders".to_string(), Value::Object(headers));
+        Value::Object(options).to_string()
+    }
+}
+
+/// Replace `{{name}}` placeholders with collection variables
+fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = text.to_string();
+    for (name, value) in variables {
+        result = result.replace(&format!("{{{{{}}}}}", name), value);
+    }
+    result
+}
+
+fn item_path(folder: &str, name: &str) -> String {
+    if folder.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} / {}", folder, name)
+    }
+}
+
+// ============================================================================
+// Postman
+// ============================================================================
+
+fn is_postman_socketio(item: &Value, request: &Value) -> bool {
+    let socketio_type = |value: &Value| {
+        value.get("type").and_then(Value::as_str).is_some_and(|t| {
+            t.eq_ignore_ascii_case("socketio") || t.eq_ignore_ascii_case("socket.io")
+        })
+    };
+    socketio_type(item) || socketio_type(request)
+}
+
+fn postman_bearer_token(request: &Value) -> Option<String> {
+    let auth = request.get("auth")?;
+    if auth.get("type").and_then(Value::as_str) != Some("bearer") {
+        return None;
+    }
+    auth.get("bearer")?
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("key").and_then(Value::as_str) == Some("token"))?
+        .get("value")?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn parse_postman_items(
+    items: &[Value],
+    folder: &str,
+    variables: &HashMap<String, String>,
+    parsed: &mut ParsedCollection,
+) {
+    for item in items {
+        let name = str_field(item, &["name"]).unwrap_or("Untitled");
+        let path = item_path(folder, name);
+
+        if let Some(children) = item.get("item").and_then(Value::as_array) {
+            parse_postman_items(children, &path, variables, parsed);
+            continue;
+        }
+        let Some(request) = item.get("request") else {
+            parsed.skip(&path, "Item has no request");
+            continue;
+        };
+        if !is_postman_socketio(item, request) {
+            parsed.skip(&path, "Not a Socket.IO request");
+            continue;
+        }
+        let Some(url) = url_field(request) else {
+            parsed.skip(&path, "Request has no URL");
+            continue;
+        };
+        let url = substitute(&url, variables);
+        if url.contains("{{") {
+            parsed.skip(&path, format!("URL uses an undefined variable: {}", url));
+            continue;
+        }
+
+        let namespace = str_field(request, &["namespace"])
+            .or_else(|| {
+                request
+                    .get("settings")
+                    .and_then(|s| str_field(s, &["namespace"]))
+            })
+            .unwrap_or("/");
+        let events = listeners(request.get("events").or_else(|| request.get("listeners")));
+
+        let mut pinned_messages = Vec::new();
+        let messages = request.get("messages").or_else(|| item.get("messages"));
+        for message in messages.and_then(Value::as_array).into_iter().flatten() {
+            let label = str_field(message, &["name"]).map(str::to_string);
+            let Some(event) = str_field(message, &["event"]) else {
+                let message_name = label.as_deref().unwrap_or("Untitled message");
+                parsed.skip(&item_path(&path, message_name), "Message has no event name");
+                continue;
+            };
+            let payload = payload_text(
+                ["payload", "data", "message", "content"]
+                    .iter()
+                    .find_map(|key| message.get(*key)),
+            );
+            pinned_messages.push(ProfilePinnedMessage {
+                event_name: event.to_string(),
+                payload: substitute(&payload, variables),
+                label,
+                auto_send: false,
+            });
+        }
+
+        parsed.profiles.push(ConnectionProfile {
+            name: name.to_string(),
+            url,
+            namespace: namespace.to_string(),
+            auth_token: postman_bearer_token(request).map(|token| substitute(&token, variables)),
+            options: options_json(headers(request.get("header"))),
+            auto_send_on_connect: false,
+            auto_send_on_reconnect: false,
+            events,
+            pinned_messages,
+        });
+    }
+}
+
+pub fn parse_postman(collection: &Value) -> ParsedCollection {
+    let variables = collection
+        .get("variable")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|variable| {
+            let key = variable.get("key")?.as_str()?;
+            let value = variable.get("value")?;
+            Some((key.to_string(), payload_text(Some(value))))
+        })
+        .collect();
+
+    let mut parsed = ParsedCollection::default();
+    let items = collection.get("item").and_then(Value::as_array);
+    parse_postman_items(
+        items.map_or(&[], Vec::as_slice),
+        "",
+        &variables,
+        &mut parsed,
+    );
+    parsed
+}
+
+// ============================================================================
+// Firecamp
+// ============================================================================
+
+/// The `__ref.id` of a Firecamp entity
+fn firecamp_id(value: &Value) -> Option<&str> {
+    value.get("__ref")?.get("id")?.as_str()
+}
+
+fn firecamp_name(value: &Value) -> Option<&str> {
+    value
+        .get("__meta")
+        .and_then(|meta| str_field(meta, &["name"]))
+        .or_else(|| str_field(value, &["name"]))
+}
+
+/// Folder path of a Firecamp request, following parent folder references
+fn firecamp_folder(request: &Value, folders: &HashMap<&str, &Value>) -> String {
+    let mut names = Vec::new();
+    let mut parent = request
+        .get("__ref")
+        .and_then(|r| r.get("folderId"))
+        .and_then(Value::as_str);
+    while let Some(folder) = parent.and_then(|id| folders.get(id)) {
+        names.push(firecamp_name(folder).unwrap_or("Untitled"));
+        parent = folder
+            .get("__ref")
+            .and_then(|r| r.get("folderId"))
+            .and_then(Value::as_str);
+        if names.len() > folders.len() {
+            break;
+        }
+    }
+    names.reverse();
+    names.join(" / ")
+}
+
+pub fn parse_firecamp(collection: &Value) -> ParsedCollection {
+    let folders: HashMap<&str, &Value> = collection
+        .get("folders")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|folder| Some((firecamp_id(folder)?, folder)))
+        .collect();
+    let request_items: Vec<&Value> = collection
+        .get("requestItems")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let mut parsed = ParsedCollection::default();
+    let requests = collection.get("requests").and_then(Value::as_array);
+    for request in requests.into_iter().flatten() {
+        let name = firecamp_name(request).unwrap_or("Untitled");
+        let path = item_path(&firecamp_folder(request, &folders), name);
+
+        let request_type = request
+            .get("__meta")
+            .and_then(|meta| meta.get("type"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !request_type.eq_ignore_ascii_case("socketio") {
+            parsed.skip(&path, format!("Not a Socket.IO request ({})", request_type));
+            continue;
+        }
+        let Some(url) = url_field(request) else {
+            parsed.skip(&path, "Request has no URL");
+            continue;
+        };
+
+        let connections = request.get("connections").and_then(Value::as_array);
+        let connection = connections.and_then(|c| c.first()).unwrap_or(&Value::Null);
+        for extra in connections.into_iter().flatten().skip(1) {
+            let extra_name = str_field(extra, &["name", "namespace"]).unwrap_or("connection");
+            parsed.skip(
+                &item_path(&path, extra_name),
+                "Only the first connection of a request is imported",
+            );
+        }
+
+        let mut pinned_messages = Vec::new();
+        let request_id = firecamp_id(request);
+        let messages = request_items.iter().filter(|item| {
+            request_id.is_some()
+                && item
+                    .get("__ref")
+                    .and_then(|r| r.get("requestId"))
+                    .and_then(Value::as_str)
+                    == request_id
+        });
+        for message in messages {
+            let label = str_field(message, &["name"]).map(str::to_string);
+            let event = message
+                .get("__meta")
+                .and_then(|meta| str_field(meta, &["event"]))
+                .or_else(|| str_field(message, &["event"]));
+            let Some(event) = event else {
+                let message_name = label.as_deref().unwrap_or("Untitled message");
+                parsed.skip(&item_path(&path, message_name), "Message has no event name");
+                continue;
+            };
+            // Firecamp keeps the body under `value.value` (or a list of arguments)
+            let body = match message.get("value") {
+                Some(Value::Object(value)) => value.get("value"),
+                Some(Value::Array(args)) => args.first().and_then(|arg| arg.get("value")),
+                other => other,
+            };
+            pinned_messages.push(ProfilePinnedMessage {
+                event_name: event.to_string(),
+                payload: payload_text(body),
+                label,
+                auto_send: false,
+            });
+        }
+
+        parsed.profiles.push(ConnectionProfile {
+            name: name.to_string(),
+            url,
+            namespace: str_field(connection, &["namespace"])
+                .unwrap_or("/")
+                .to_string(),
+            auth_token: None,
+            options: options_json(headers(connection.get("headers"))),
+            auto_send_on_connect: false,
+            auto_send_on_reconnect: false,
+            events: listeners(connection.get("listeners")),
+            pinned_messages,
+        });
+    }
+    parsed
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+pub fn parse_collection(
+    collection: &Value,
+    format: Option<CollectionFormat>,
+) -> Result<(CollectionFormat, ParsedCollection), String> {
+    let format = format
+        .or_else(|| CollectionFormat::detect(collection))
+        .ok_or("Unrecognized collection format (expected Postman or Firecamp)")?;
+    let parsed = match format {
+        CollectionFormat::Postman => parse_postman(collection),
+        CollectionFormat::Firecamp => parse_firecamp(collection),
+    };
+    Ok((format, parsed))
+}
+
+/// Import the Socket.IO requests of a collection as connections.
+///
+/// Requests are saved as connection profiles, so name conflicts are handled as
+/// in `profiles::import_profiles`; items that cannot be mapped are reported
+/// rather than failing the import.
+pub fn import_collection(
+    collection: &Value,
+    format: Option<CollectionFormat>,
+    options: &ImportOptions,
+) -> Result<CollectionImportReport, String> {
+    let (format, parsed) = parse_collection(collection, format)?;
+    let imported = profiles::import_profiles(parsed.profiles, options)?;
+    Ok(CollectionImportReport {
+        format,
+        imported,
+        unmapped: parsed.unmapped,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Import a collection file read by the frontend; the format is detected when omitted
+#[tauri::command]
+pub fn import_socketio_collection(
+    collection: Value,
+    format: Option<CollectionFormat>,
+    options: Option<ImportOptions>,
+) -> Result<CollectionImportReport, String> {
+    import_collection(&collection, format, &options.unwrap_or_default())
+}
@@ -1,26 +1,8 @@
 use crate::db;
+use crate::secrets;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Connection {
-    pub id: i64,
-    pub name: String,
-    pub url: String,
-    pub namespace: String,
-    pub auth_token: Option<String>,
-    pub options: String,
-    pub created_at: String,
-    pub updated_at: String,
-    pub auto_send_on_connect: bool,
-    pub auto_send_on_reconnect: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConnectionEvent {
-    pub id: i64,
-    pub event_name: String,
-    pub is_listening: bool,
-}
+pub use crate::db::{ConnectionEventRecord as ConnectionEvent, ConnectionRecord as Connection};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateConnectionInput {
@@ -45,12 +27,14 @@ pub struct UpdateConnectionInput {
 pub fn create_connection(input: CreateConnectionInput) -> Result<i64, String> {
     let namespace = input.namespace.unwrap_or_else(|| "/".to_string());
     let options = input.options.unwrap_or_else(|| "{}".to_string());
+    let auth_token = secrets::seal_auth_token(input.auth_token.as_deref(), None)?;
+    let options = secrets::seal_options(&options, None)?;
 
     db::create_connection(
         &input.name,
         &input.url,
         &namespace,
-        input.auth_token.as_deref(),
+        auth_token.as_deref(),
         &options,
     )
     .map_err(|e| e.to_string())
@@ -61,12 +45,20 @@ pub fn update_connection(input: UpdateConnectionInput) -> Result<(), String> {
     let namespace = input.namespace.unwrap_or_else(|| "/".to_string());
     let options = input.options.unwrap_or_else(|| "{}".to_string());
 
+    // Masked secrets sent back unchanged keep their stored values
+    let existing = db::get_connection_by_id(input.id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Connection not found".to_string())?;
+    let auth_token =
+        secrets::seal_auth_token(input.auth_token.as_deref(), existing.auth_token.as_deref())?;
+    let options = secrets::seal_options(&options, Some(&existing.options))?;
+
     db::update_connection(
         input.id,
         &input.name,
         &input.url,
         &namespace,
-        input.auth_token.as_deref(),
+        auth_token.as_deref(),
         &options,
     )
     .map_err(|e| e.to_string())
@@ -79,45 +71,17 @@ pub fn delete_connection(id: i64) -> Result<(), String> {
 
 #[tauri::command]
 pub fn list_connections() -> Result<Vec<Connection>, String> {
-    let rows = db::list_connections().map_err(|e| e.to_string())?;
-
-    Ok(rows
+    let connections = db::list_connections().map_err(|e| e.to_string())?;
+    Ok(connections
         .into_iter()
-        .map(
-            |(id, name, url, namespace, auth_token, options, created_at, updated_at, auto_send_on_connect, auto_send_on_reconnect)| Connection {
-                id,
-                name,
-                url,
-                namespace,
-                auth_token,
-                options,
-                created_at,
-                updated_at,
-                auto_send_on_connect,
-                auto_send_on_reconnect,
-            },
-        )
+        .map(secrets::mask_connection)
         .collect())
 }
 
 #[tauri::command]
 pub fn get_connection(id: i64) -> Result<Option<Connection>, String> {
-    let row = db::get_connection_by_id(id).map_err(|e| e.to_string())?;
-
-    Ok(row.map(
-        |(id, name, url, namespace, auth_token, options, created_at, updated_at, auto_send_on_connect, auto_send_on_reconnect)| Connection {
-            id,
-            name,
-            url,
-            namespace,
-            auth_token,
-            options,
-            created_at,
-            updated_at,
-            auto_send_on_connect,
-            auto_send_on_reconnect,
-        },
-    ))
+    let connection = db::get_connection_by_id(id).map_err(|e| e.to_string())?;
+    Ok(connection.map(secrets::mask_connection))
 }
 
 #[tauri::command]
@@ -126,8 +90,7 @@ pub fn set_connection_auto_send(
     on_connect: bool,
     on_reconnect: bool,
 ) -> Result<(), String> {
-    db::set_connection_auto_send(connection_id, on_connect, on_reconnect)
-        .map_err(|e| e.to_string())
+    db::set_connection_auto_send(connection_id, on_connect, on_reconnect).map_err(|e| e.to_string())
 }
 
 // Connection events commands
@@ -148,16 +111,7 @@ pub fn toggle_connection_event(id: i64, is_listening: bool) -> Result<(), String
 
 #[tauri::command]
 pub fn list_connection_events(connection_id: i64) -> Result<Vec<ConnectionEvent>, String> {
-    let rows = db::list_connection_events(connection_id).map_err(|e| e.to_string())?;
-
-    Ok(rows
-        .into_iter()
-        .map(|(id, event_name, is_listening)| ConnectionEvent {
-            id,
-            event_name,
-            is_listening,
-        })
-        .collect())
+    db::list_connection_events(connection_id).map_err(|e| e.to_string())
 }
 
 // App state commands
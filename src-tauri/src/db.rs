@@ -1,34 +1,389 @@
-use rusqlite::{Connection, Result, params};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, Result, Row, params, params_from_iter};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
 
-static DB_PATH: OnceLock<Mutex<PathBuf>> = OnceLock::new();
-
-// Type aliases to reduce complexity warnings
-/// (id, name, url, namespace, auth_token, options, created_at, updated_at, auto_send_on_connect, auto_send_on_reconnect)
-pub type ConnectionRow = (
-    i64,
-    String,
-    String,
-    String,
-    Option<String>,
-    String,
-    String,
-    String,
-    bool,
-    bool,
-);
-/// (id, event_name, payload, label, sort_order, auto_send)
-pub type PinnedMessageRow = (i64, String, String, Option<String>, i64, bool);
-/// (id, event_name, payload, timestamp, direction)
-pub type EventHistoryRow = (i64, String, String, String, String);
+static POOL: OnceLock<Pool> = OnceLock::new();
+
+/// Idle connections kept open for reuse
+const MAX_IDLE_CONNECTIONS: usize = 8;
+/// Prepared statements cached per connection
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+// ============================================================================
+// Row Types
+// ============================================================================
+
+/// A saved connection profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionRecord {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub namespace: String,
+    pub auth_token: Option<String>,
+    pub options: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub auto_send_on_connect: bool,
+    pub auto_send_on_reconnect: bool,
+    /// Imported sessions can be viewed and replayed but not connected
+    pub read_only: bool,
+}
+
+impl ConnectionRecord {
+    const COLUMNS: &'static str =
+        "id, name, url, namespace, auth_token, options, created_at, updated_at,
+        COALESCE(auto_send_on_connect, 0) AS auto_send_on_connect,
+        COALESCE(auto_send_on_reconnect, 0) AS auto_send_on_reconnect,
+        COALESCE(read_only, 0) AS read_only";
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            url: row.get("url")?,
+            namespace: row.get("namespace")?,
+            auth_token: row.get("auth_token")?,
+            options: row.get("options")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+            auto_send_on_connect: row.get("auto_send_on_connect")?,
+            auto_send_on_reconnect: row.get("auto_send_on_reconnect")?,
+            read_only: row.get("read_only")?,
+        })
+    }
+}
+
+/// An event a connection listens for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionEventRecord {
+    pub id: i64,
+    pub event_name: String,
+    pub is_listening: bool,
+}
+
+impl ConnectionEventRecord {
+    const COLUMNS: &'static str = "id, event_name, is_listening";
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            event_name: row.get("event_name")?,
+            is_listening: row.get("is_listening")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedMessageRecord {
+    pub id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub label: Option<String>,
+    pub sort_order: i64,
+    pub auto_send: bool,
+}
+
+impl PinnedMessageRecord {
+    const COLUMNS: &'static str = "id, event_name, payload, label, sort_order, auto_send";
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            event_name: row.get("event_name")?,
+            payload: row.get("payload")?,
+            label: row.get("label")?,
+            sort_order: row.get("sort_order")?,
+            auto_send: row.get("auto_send")?,
+        })
+    }
+}
+
+/// A recorded incoming or outgoing event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventHistoryRecord {
+    pub id: i64,
+    pub connection_id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub timestamp: String,
+    /// `in` or `out`
+    pub direction: String,
+    /// Secrets in the payload were replaced before it was stored
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl EventHistoryRecord {
+    const COLUMNS: &'static str =
+        "id, connection_id, event_name, payload, timestamp, direction, redacted";
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            connection_id: row.get("connection_id")?,
+            event_name: row.get("event_name")?,
+            payload: row.get("payload")?,
+            timestamp: row.get("timestamp")?,
+            direction: row.get("direction")?,
+            redacted: row.get("redacted")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmitLogRecord {
+    pub id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub sent_at: String,
+    /// Secrets in the payload were replaced before it was stored
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl EmitLogRecord {
+    const COLUMNS: &'static str = "id, event_name, payload, sent_at, redacted";
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            event_name: row.get("event_name")?,
+            payload: row.get("payload")?,
+            sent_at: row.get("sent_at")?,
+            redacted: row.get("redacted")?,
+        })
+    }
+}
+
+/// Approximate storage used by one connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStorageRecord {
+    pub connection_id: i64,
+    pub event_count: i64,
+    /// Bytes of event names and payloads (excluding index overhead)
+    pub event_bytes: i64,
+    pub emit_log_count: i64,
+    pub oldest_event: Option<String>,
+    pub newest_event: Option<String>,
+}
+
+impl ConnectionStorageRecord {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            connection_id: row.get("connection_id")?,
+            event_count: row.get("event_count")?,
+            event_bytes: row.get("event_bytes")?,
+            emit_log_count: row.get("emit_log_count")?,
+            oldest_event: row.get("oldest_event")?,
+            newest_event: row.get("newest_event")?,
+        })
+    }
+}
+
+/// One `tools/call` handled by the MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpAuditRecord {
+    pub id: i64,
+    /// RFC 3339 time the call started
+    pub timestamp: String,
+    /// `clientInfo` sent by the client in `initialize`
+    pub client_name: Option<String>,
+    pub client_version: Option<String>,
+    pub tool: String,
+    /// Redacted JSON arguments
+    pub arguments: String,
+    /// Redacted JSON result; unset when the call failed
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+}
+
+impl McpAuditRecord {
+    const COLUMNS: &'static str =
+        "id, timestamp, client_name, client_version, tool, arguments, result, error, duration_ms";
+
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            timestamp: row.get("timestamp")?,
+            client_name: row.get("client_name")?,
+            client_version: row.get("client_version")?,
+            tool: row.get("tool")?,
+            arguments: row.get("arguments")?,
+            result: row.get("result")?,
+            error: row.get("error")?,
+            duration_ms: row.get("duration_ms")?,
+        })
+    }
+}
+
+/// Stored timestamps are RFC 3339 UTC with milliseconds (`2025-01-01T12:00:00.000Z`),
+/// so they sort and compare correctly as text
+pub fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Rewrite an RFC 3339 or SQLite (`2025-01-01 12:00:00`) timestamp in the stored format.
+///
+/// Anything else is returned unchanged.
+pub fn normalize_timestamp(value: &str) -> String {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return format_timestamp(time.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|time| format_timestamp(time.and_utc()))
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Filters and cursor for `event_history` and `emit_logs` queries; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub connection_id: Option<i64>,
+    pub event_name: Option<String>,
+    /// Match any of these event names (ignored when empty)
+    pub event_names: Vec<String>,
+    /// Only applies to event history (emit logs are always outgoing)
+    pub direction: Option<String>,
+    /// RFC 3339 lower bound (inclusive) on the event timestamp, any offset
+    pub since: Option<String>,
+    /// RFC 3339 upper bound (inclusive) on the event timestamp, any offset
+    pub until: Option<String>,
+    /// Only rows with a smaller id (older page)
+    pub before_id: Option<i64>,
+    /// Only rows with a larger id (newer page)
+    pub after_id: Option<i64>,
+}
+
+impl HistoryFilter {
+    /// Paging forward from `after_id` reads oldest first so no rows are skipped
+    pub fn is_forward(&self) -> bool {
+        self.after_id.is_some() && self.before_id.is_none()
+    }
+
+    /// Append the filter's conditions, ordering and limit to `sql`
+    fn push_sql(
+        &self,
+        sql: &mut String,
+        values: &mut Vec<rusqlite::types::Value>,
+        time_column: &str,
+        limit: i64,
+    ) {
+        if let Some(connection_id) = self.connection_id {
+            sql.push_str(" AND connection_id = ?");
+            values.push(connection_id.into());
+        }
+        if let Some(event_name) = &self.event_name {
+            sql.push_str(" AND event_name = ?");
+            values.push(event_name.clone().into());
+        }
+        if !self.event_names.is_empty() {
+            let placeholders = vec!["?"; self.event_names.len()].join(", ");
+            sql.push_str(&format!(" AND event_name IN ({})", placeholders));
+            values.extend(self.event_names.iter().map(|name| name.clone().into()));
+        }
+        if let Some(direction) = &self.direction {
+            sql.push_str(" AND direction = ?");
+            values.push(direction.clone().into());
+        }
+        if let Some(since) = &self.since {
+            sql.push_str(&format!(" AND {} >= ?", time_column));
+            values.push(normalize_timestamp(since).into());
+        }
+        if let Some(until) = &self.until {
+            sql.push_str(&format!(" AND {} <= ?", time_column));
+            values.push(normalize_timestamp(until).into());
+        }
+        if let Some(before_id) = self.before_id {
+            sql.push_str(" AND id < ?");
+            values.push(before_id.into());
+        }
+        if let Some(after_id) = self.after_id {
+            sql.push_str(" AND id > ?");
+            values.push(after_id.into());
+        }
+        sql.push_str(if self.is_forward() {
+            " ORDER BY id ASC LIMIT ?"
+        } else {
+            " ORDER BY id DESC LIMIT ?"
+        });
+        values.push(limit.into());
+    }
+}
+
+/// (description, apply)
+type Migration = (&'static str, fn(&Connection) -> Result<()>);
+
+/// Schema migrations in order; a database's `user_version` is the number applied.
+///
+/// Migrations must be idempotent: databases created before versioning already
+/// contain some of these objects.
+const MIGRATIONS: &[Migration] = &[
+    ("base schema", migrate_base_schema),
+    ("event history full-text index", migrate_event_history_fts),
+    ("read-only imported sessions", migrate_read_only_connections),
+    ("MCP audit log", migrate_mcp_audit_log),
+    ("normalized timestamps", migrate_normalized_timestamps),
+    ("redacted payload flags", migrate_redacted_flags),
+];
+
+/// Schema version this build of the app creates and understands
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
 
 pub fn init_db(path: &PathBuf) -> Result<()> {
-    // Initialize DB_PATH with OnceLock - this can only be set once
-    let _ = DB_PATH.get_or_init(|| Mutex::new(path.clone()));
+    // The pool can only be created once per process
+    let _ = POOL.get_or_init(|| Pool::new(path.clone()));
 
-    let conn = get_connection()?;
+    let mut conn = get_connection()?;
+
+    // WAL lets the history writer commit without blocking readers
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+
+    migrate(&mut conn)?;
+
+    log::info!("Database initialized at {:?}", path);
+    Ok(())
+}
+
+pub fn schema_version(conn: &Connection) -> Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Apply pending migrations, each in its own transaction together with its version bump.
+///
+/// Refuses databases written by a newer version of the app.
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+            Some(format!(
+                "Database was created by a newer version of the app (schema version {}, \
+                 supported {}); please update the app",
+                current, SCHEMA_VERSION
+            )),
+        ));
+    }
+
+    for (index, (description, apply)) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = index as i64 + 1;
+        let tx = conn.transaction()?;
+        apply(&tx)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        log::info!("Applied database migration {}: {}", version, description);
+    }
+    Ok(())
+}
 
+fn migrate_base_schema(conn: &Connection) -> Result<()> {
     // Create connections table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS connections (
@@ -87,7 +442,7 @@ pub fn init_db(path: &PathBuf) -> Result<()> {
     )?;
 
     // Migration: add auto_send column if missing (older databases)
-    if !column_exists(&conn, "pinned_messages", "auto_send")? {
+    if !column_exists(conn, "pinned_messages", "auto_send")? {
         conn.execute(
             "ALTER TABLE pinned_messages ADD COLUMN auto_send INTEGER DEFAULT 0",
             [],
@@ -95,7 +450,7 @@ pub fn init_db(path: &PathBuf) -> Result<()> {
     }
 
     // Migration: add auto_send_on_connect column if missing
-    if !column_exists(&conn, "connections", "auto_send_on_connect")? {
+    if !column_exists(conn, "connections", "auto_send_on_connect")? {
         conn.execute(
             "ALTER TABLE connections ADD COLUMN auto_send_on_connect INTEGER DEFAULT 0",
             [],
@@ -103,7 +458,7 @@ pub fn init_db(path: &PathBuf) -> Result<()> {
     }
 
     // Migration: add auto_send_on_reconnect column if missing
-    if !column_exists(&conn, "connections", "auto_send_on_reconnect")? {
+    if !column_exists(conn, "connections", "auto_send_on_reconnect")? {
         conn.execute(
             "ALTER TABLE connections ADD COLUMN auto_send_on_reconnect INTEGER DEFAULT 0",
             [],
@@ -141,18 +496,200 @@ pub fn init_db(path: &PathBuf) -> Result<()> {
         [],
     )?;
 
-    log::info!("Database initialized at {:?}", path);
     Ok(())
 }
 
-pub fn get_connection() -> Result<Connection> {
-    let db_path_mutex = DB_PATH
+fn migrate_event_history_fts(conn: &Connection) -> Result<()> {
+    // Full-text index over event_history, kept in sync by triggers
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS event_history_fts USING fts5(
+            event_name, payload, content='event_history', content_rowid='id'
+        );
+        CREATE TRIGGER IF NOT EXISTS event_history_fts_insert AFTER INSERT ON event_history BEGIN
+            INSERT INTO event_history_fts(rowid, event_name, payload)
+            VALUES (new.id, new.event_name, new.payload);
+        END;
+        CREATE TRIGGER IF NOT EXISTS event_history_fts_delete AFTER DELETE ON event_history BEGIN
+            INSERT INTO event_history_fts(event_history_fts, rowid, event_name, payload)
+            VALUES ('delete', old.id, old.event_name, old.payload);
+        END;
+        CREATE TRIGGER IF NOT EXISTS event_history_fts_update AFTER UPDATE ON event_history BEGIN
+            INSERT INTO event_history_fts(event_history_fts, rowid, event_name, payload)
+            VALUES ('delete', old.id, old.event_name, old.payload);
+            INSERT INTO event_history_fts(rowid, event_name, payload)
+            VALUES (new.id, new.event_name, new.payload);
+        END;",
+    )?;
+
+    // Index history recorded before the FTS table existed
+    conn.execute(
+        "INSERT INTO event_history_fts(event_history_fts) VALUES ('rebuild')",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migrate_read_only_connections(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "connections", "read_only")? {
+        conn.execute(
+            "ALTER TABLE connections ADD COLUMN read_only INTEGER DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_mcp_audit_log(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mcp_audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp TEXT NOT NULL,
+            client_name TEXT,
+            client_version TEXT,
+            tool TEXT NOT NULL,
+            arguments TEXT NOT NULL DEFAULT '{}',
+            result TEXT,
+            error TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Rewrite stored timestamps in the `format_timestamp` format and index them
+fn migrate_normalized_timestamps(conn: &Connection) -> Result<()> {
+    for (table, column) in [
+        ("event_history", "timestamp"),
+        ("emit_logs", "sent_at"),
+        ("mcp_audit_log", "timestamp"),
+    ] {
+        // strftime understands both RFC 3339 offsets and CURRENT_TIMESTAMP values
+        conn.execute(
+            &format!(
+                "UPDATE {table} SET {column} = strftime('%Y-%m-%dT%H:%M:%fZ', {column})
+                 WHERE strftime('%Y-%m-%dT%H:%M:%fZ', {column}) IS NOT {column}"
+            ),
+            [],
+        )?;
+    }
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_event_history_connection_time
+             ON event_history(connection_id, timestamp);
+         CREATE INDEX IF NOT EXISTS idx_emit_logs_connection_sent_at
+             ON emit_logs(connection_id, sent_at);
+         CREATE INDEX IF NOT EXISTS idx_mcp_audit_log_timestamp ON mcp_audit_log(timestamp);",
+    )?;
+    Ok(())
+}
+
+fn migrate_redacted_flags(conn: &Connection) -> Result<()> {
+    for table in ["event_history", "emit_logs"] {
+        if !column_exists(conn, table, "redacted")? {
+            conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN redacted INTEGER NOT NULL DEFAULT 0"),
+                [],
+            )?;
+            // Rows stored before the flag existed can only be recognised by the placeholder
+            conn.execute(
+                &format!("UPDATE {table} SET redacted = 1 WHERE instr(payload, '[REDACTED]') > 0"),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Connection Pool
+// ============================================================================
+
+/// Open connections to the database file, reused so their statement caches survive
+struct Pool {
+    path: PathBuf,
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl Pool {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn get(&'static self) -> Result<PooledConnection> {
+        // The idle list is only pushed and popped, so it is intact even if a holder panicked
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => {
+                let conn = Connection::open(&self.path)?;
+                conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+                conn
+            }
+        };
+        Ok(PooledConnection {
+            conn: Some(conn),
+            pool: self,
+        })
+    }
+
+    fn release(&self, conn: Connection) {
+        // A connection left inside a transaction is closed rather than reused
+        if !conn.is_autocommit() {
+            return;
+        }
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(conn);
+        }
+    }
+}
+
+/// A connection borrowed from the pool, returned to it when dropped
+pub struct PooledConnection {
+    conn: Option<Connection>,
+    pool: &'static Pool,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("pooled connection already released")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn
+            .as_mut()
+            .expect("pooled connection already released")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.release(conn);
+        }
+    }
+}
+
+/// Borrow a connection from the pool, opening a new one if none is idle
+pub fn get_connection() -> Result<PooledConnection> {
+    POOL.get()
+        .ok_or_else(|| rusqlite::Error::InvalidParameterName("Database not initialized".into()))?
         .get()
-        .ok_or_else(|| rusqlite::Error::InvalidParameterName("Database not initialized".into()))?;
-    let path = db_path_mutex
-        .lock()
-        .map_err(|_| rusqlite::Error::InvalidParameterName("Database lock poisoned".into()))?;
-    Connection::open(&*path)
+}
+
+/// Path of the initialized database
+pub fn db_path() -> Option<PathBuf> {
+    POOL.get().map(|pool| pool.path.clone())
 }
 
 fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
@@ -199,17 +736,44 @@ pub fn update_connection(
     Ok(())
 }
 
+/// Store re-encrypted secrets without touching `updated_at`
+pub fn update_connection_secrets(id: i64, auth_token: Option<&str>, options: &str) -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute(
+        "UPDATE connections SET auth_token = ?1, options = ?2 WHERE id = ?3",
+        params![auth_token, options, id],
+    )?;
+    Ok(())
+}
+
+/// Store every connection's re-encrypted secrets and the new key settings in one transaction
+pub fn replace_connection_secrets(
+    updates: &[(i64, Option<String>, String)],
+    settings_key: &str,
+    settings: &str,
+) -> Result<()> {
+    let mut conn = get_connection()?;
+    let tx = conn.transaction()?;
+    for (id, auth_token, options) in updates {
+        tx.execute(
+            "UPDATE connections SET auth_token = ?1, options = ?2 WHERE id = ?3",
+            params![auth_token, options, id],
+        )?;
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO app_state (key, value) VALUES (?1, ?2)",
+        params![settings_key, settings],
+    )?;
+    tx.commit()
+}
+
 pub fn delete_connection(id: i64) -> Result<()> {
     let conn = get_connection()?;
     conn.execute("DELETE FROM connections WHERE id = ?1", params![id])?;
     Ok(())
 }
 
-pub fn set_connection_auto_send(
-    id: i64,
-    on_connect: bool,
-    on_reconnect: bool,
-) -> Result<()> {
+pub fn set_connection_auto_send(id: i64, on_connect: bool, on_reconnect: bool) -> Result<()> {
     let conn = get_connection()?;
     conn.execute(
         "UPDATE connections SET auto_send_on_connect = ?1, auto_send_on_reconnect = ?2 WHERE id = ?3",
@@ -218,57 +782,24 @@ pub fn set_connection_auto_send(
     Ok(())
 }
 
-pub fn list_connections() -> Result<Vec<ConnectionRow>> {
+pub fn list_connections() -> Result<Vec<ConnectionRecord>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, name, url, namespace, auth_token, options, created_at, updated_at, COALESCE(auto_send_on_connect, 0), COALESCE(auto_send_on_reconnect, 0) FROM connections ORDER BY updated_at DESC"
-    )?;
-
-    let rows = stmt.query_map([], |row| {
-        Ok((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-            row.get(4)?,
-            row.get(5)?,
-            row.get(6)?,
-            row.get(7)?,
-            row.get::<_, i64>(8)? != 0,
-            row.get::<_, i64>(9)? != 0,
-        ))
-    })?;
-
-    let mut results = Vec::new();
-    for row in rows {
-        results.push(row?);
-    }
-    Ok(results)
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM connections ORDER BY updated_at DESC",
+        ConnectionRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map([], ConnectionRecord::from_row)?;
+    rows.collect()
 }
 
-pub fn get_connection_by_id(id: i64) -> Result<Option<ConnectionRow>> {
+pub fn get_connection_by_id(id: i64) -> Result<Option<ConnectionRecord>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, name, url, namespace, auth_token, options, created_at, updated_at, COALESCE(auto_send_on_connect, 0), COALESCE(auto_send_on_reconnect, 0) FROM connections WHERE id = ?1"
-    )?;
-
-    let mut rows = stmt.query(params![id])?;
-    if let Some(row) = rows.next()? {
-        Ok(Some((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-            row.get(4)?,
-            row.get(5)?,
-            row.get(6)?,
-            row.get(7)?,
-            row.get::<_, i64>(8)? != 0,
-            row.get::<_, i64>(9)? != 0,
-        )))
-    } else {
-        Ok(None)
-    }
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM connections WHERE id = ?1",
+        ConnectionRecord::COLUMNS
+    ))?;
+    let mut rows = stmt.query_map(params![id], ConnectionRecord::from_row)?;
+    rows.next().transpose()
 }
 
 // Connection events operations
@@ -296,51 +827,143 @@ pub fn toggle_connection_event(id: i64, is_listening: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn list_connection_events(connection_id: i64) -> Result<Vec<(i64, String, bool)>> {
+pub fn list_connection_events(connection_id: i64) -> Result<Vec<ConnectionEventRecord>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, event_name, is_listening FROM connection_events WHERE connection_id = ?1 ORDER BY created_at"
-    )?;
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM connection_events WHERE connection_id = ?1 ORDER BY created_at",
+        ConnectionEventRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![connection_id], ConnectionEventRecord::from_row)?;
+    rows.collect()
+}
 
-    let rows = stmt.query_map(params![connection_id], |row| {
-        Ok((row.get(0)?, row.get(1)?, row.get::<_, i32>(2)? != 0))
-    })?;
+// Profile operations
 
-    let mut results = Vec::new();
-    for row in rows {
-        results.push(row?);
+/// Save an imported connection with its listeners and pinned messages in one transaction.
+///
+/// With `replace_id` the existing connection's settings are overwritten and its
+/// listeners and pinned messages replaced; otherwise a new connection is created.
+/// Ids and timestamps in the records are ignored.
+pub fn save_connection_profile(
+    replace_id: Option<i64>,
+    connection: &ConnectionRecord,
+    events: &[ConnectionEventRecord],
+    pinned_messages: &[PinnedMessageRecord],
+) -> Result<i64> {
+    let mut conn = get_connection()?;
+    let tx = conn.transaction()?;
+
+    let connection_id = match replace_id {
+        Some(id) => {
+            tx.execute(
+                "UPDATE connections SET name = ?1, url = ?2, namespace = ?3, auth_token = ?4, options = ?5, auto_send_on_connect = ?6, auto_send_on_reconnect = ?7, updated_at = CURRENT_TIMESTAMP WHERE id = ?8",
+                params![
+                    connection.name,
+                    connection.url,
+                    connection.namespace,
+                    connection.auth_token,
+                    connection.options,
+                    connection.auto_send_on_connect as i32,
+                    connection.auto_send_on_reconnect as i32,
+                    id
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM connection_events WHERE connection_id = ?1",
+                params![id],
+            )?;
+            tx.execute(
+                "DELETE FROM pinned_messages WHERE connection_id = ?1",
+                params![id],
+            )?;
+            id
+        }
+        None => {
+            tx.execute(
+                "INSERT INTO connections (name, url, namespace, auth_token, options, auto_send_on_connect, auto_send_on_reconnect) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    connection.name,
+                    connection.url,
+                    connection.namespace,
+                    connection.auth_token,
+                    connection.options,
+                    connection.auto_send_on_connect as i32,
+                    connection.auto_send_on_reconnect as i32
+                ],
+            )?;
+            tx.last_insert_rowid()
+        }
+    };
+
+    for event in events {
+        tx.execute(
+            "INSERT INTO connection_events (connection_id, event_name, is_listening) VALUES (?1, ?2, ?3)",
+            params![connection_id, event.event_name, event.is_listening as i32],
+        )?;
     }
-    Ok(results)
+    for message in pinned_messages {
+        tx.execute(
+            "INSERT INTO pinned_messages (connection_id, event_name, payload, label, sort_order, auto_send) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                connection_id,
+                message.event_name,
+                message.payload,
+                message.label,
+                message.sort_order,
+                message.auto_send as i32
+            ],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(connection_id)
 }
 
 // Emit log operations
-pub fn add_emit_log(connection_id: i64, event_name: &str, payload: &str) -> Result<i64> {
+/// `redacted` records that `payload` had secrets replaced before it was passed in
+pub fn add_emit_log(
+    connection_id: i64,
+    event_name: &str,
+    payload: &str,
+    redacted: bool,
+) -> Result<i64> {
     let conn = get_connection()?;
-    conn.execute(
-        "INSERT INTO emit_logs (connection_id, event_name, payload) VALUES (?1, ?2, ?3)",
-        params![connection_id, event_name, payload],
-    )?;
+    conn.prepare_cached(
+        "INSERT INTO emit_logs (connection_id, event_name, payload, sent_at, redacted) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?
+    .execute(params![
+        connection_id,
+        event_name,
+        payload,
+        format_timestamp(Utc::now()),
+        redacted
+    ])?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn list_emit_logs(
-    connection_id: i64,
-    limit: i64,
-) -> Result<Vec<(i64, String, String, String)>> {
+pub fn list_emit_logs(connection_id: i64, limit: i64) -> Result<Vec<EmitLogRecord>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, event_name, payload, sent_at FROM emit_logs WHERE connection_id = ?1 ORDER BY sent_at DESC LIMIT ?2"
-    )?;
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM emit_logs WHERE connection_id = ?1 ORDER BY sent_at DESC LIMIT ?2",
+        EmitLogRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![connection_id, limit], EmitLogRecord::from_row)?;
+    rows.collect()
+}
 
-    let rows = stmt.query_map(params![connection_id, limit], |row| {
-        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
-    })?;
+/// Emit logs matching `filter`, newest first (oldest first when `filter.is_forward()`)
+pub fn list_emit_logs_filtered(filter: &HistoryFilter, limit: i64) -> Result<Vec<EmitLogRecord>> {
+    let mut sql = format!(
+        "SELECT {} FROM emit_logs WHERE 1 = 1",
+        EmitLogRecord::COLUMNS
+    );
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    filter.push_sql(&mut sql, &mut values, "sent_at", limit);
 
-    let mut results = Vec::new();
-    for row in rows {
-        results.push(row?);
-    }
-    Ok(results)
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), EmitLogRecord::from_row)?;
+    rows.collect()
 }
 
 pub fn clear_emit_logs(connection_id: i64) -> Result<()> {
@@ -418,54 +1041,24 @@ pub fn reorder_pinned_messages(ids: &[i64]) -> Result<()> {
     tx.commit()
 }
 
-pub fn list_pinned_messages(connection_id: i64) -> Result<Vec<PinnedMessageRow>> {
+pub fn list_pinned_messages(connection_id: i64) -> Result<Vec<PinnedMessageRecord>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, event_name, payload, label, sort_order, auto_send FROM pinned_messages WHERE connection_id = ?1 ORDER BY sort_order"
-    )?;
-
-    let rows = stmt.query_map(params![connection_id], |row| {
-        let auto_send_value: i64 = row.get(5)?;
-        Ok((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-            row.get(4)?,
-            auto_send_value != 0,
-        ))
-    })?;
-
-    let mut results = Vec::new();
-    for row in rows {
-        results.push(row?);
-    }
-    Ok(results)
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM pinned_messages WHERE connection_id = ?1 ORDER BY sort_order",
+        PinnedMessageRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![connection_id], PinnedMessageRecord::from_row)?;
+    rows.collect()
 }
 
-pub fn list_auto_send_messages(connection_id: i64) -> Result<Vec<PinnedMessageRow>> {
+pub fn list_auto_send_messages(connection_id: i64) -> Result<Vec<PinnedMessageRecord>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, event_name, payload, label, sort_order, auto_send FROM pinned_messages WHERE connection_id = ?1 AND auto_send = 1 ORDER BY sort_order"
-    )?;
-
-    let rows = stmt.query_map(params![connection_id], |row| {
-        let auto_send_value: i64 = row.get(5)?;
-        Ok((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-            row.get(4)?,
-            auto_send_value != 0,
-        ))
-    })?;
-
-    let mut results = Vec::new();
-    for row in rows {
-        results.push(row?);
-    }
-    Ok(results)
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM pinned_messages WHERE connection_id = ?1 AND auto_send = 1 ORDER BY sort_order",
+        PinnedMessageRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![connection_id], PinnedMessageRecord::from_row)?;
+    rows.collect()
 }
 
 pub fn find_duplicate_pinned_message(
@@ -474,55 +1067,151 @@ pub fn find_duplicate_pinned_message(
     payload: &str,
 ) -> Result<Option<i64>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         "SELECT id FROM pinned_messages WHERE connection_id = ?1 AND event_name = ?2 AND payload = ?3 LIMIT 1"
     )?;
-
-    let mut rows = stmt.query(params![connection_id, event_name, payload])?;
-    if let Some(row) = rows.next()? {
-        Ok(Some(row.get(0)?))
-    } else {
-        Ok(None)
-    }
+    let mut rows = stmt.query_map(params![connection_id, event_name, payload], |row| {
+        row.get(0)
+    })?;
+    rows.next().transpose()
 }
 
 // Event history operations
+/// `redacted` records that `payload` had secrets replaced before it was passed in
 pub fn add_event_history(
     connection_id: i64,
     event_name: &str,
     payload: &str,
     timestamp: &str,
     direction: &str,
+    redacted: bool,
 ) -> Result<i64> {
     let conn = get_connection()?;
-    conn.execute(
-        "INSERT INTO event_history (connection_id, event_name, payload, timestamp, direction) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![connection_id, event_name, payload, timestamp, direction],
-    )?;
+    conn.prepare_cached(
+        "INSERT INTO event_history (connection_id, event_name, payload, timestamp, direction, redacted) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?
+    .execute(params![
+        connection_id,
+        event_name,
+        payload,
+        normalize_timestamp(timestamp),
+        direction,
+        redacted
+    ])?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn list_event_history(connection_id: i64, limit: i64) -> Result<Vec<EventHistoryRow>> {
+pub fn list_event_history(connection_id: i64, limit: i64) -> Result<Vec<EventHistoryRecord>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM event_history WHERE connection_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+        EventHistoryRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![connection_id, limit], EventHistoryRecord::from_row)?;
+    rows.collect()
+}
+
+/// Events of a connection with ids in `from_id..=to_id` (either bound optional), oldest first
+pub fn list_event_history_between(
+    connection_id: i64,
+    from_id: Option<i64>,
+    to_id: Option<i64>,
+) -> Result<Vec<EventHistoryRecord>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM event_history WHERE connection_id = ?1 AND id >= COALESCE(?2, id) AND id <= COALESCE(?3, id) ORDER BY id",
+        EventHistoryRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(
+        params![connection_id, from_id, to_id],
+        EventHistoryRecord::from_row,
+    )?;
+    rows.collect()
+}
+
+/// Save an imported capture as a read-only connection with its events, in one transaction
+pub fn create_imported_session(
+    name: &str,
+    url: &str,
+    namespace: &str,
+    events: &[EventHistoryRecord],
+) -> Result<i64> {
+    let mut conn = get_connection()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO connections (name, url, namespace, read_only) VALUES (?1, ?2, ?3, 1)",
+        params![name, url, namespace],
+    )?;
+    let connection_id = tx.last_insert_rowid();
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO event_history (connection_id, event_name, payload, timestamp, direction, redacted) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for event in events {
+            stmt.execute(params![
+                connection_id,
+                event.event_name,
+                event.payload,
+                normalize_timestamp(&event.timestamp),
+                event.direction,
+                event.redacted
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(connection_id)
+}
+
+/// Id of the most recent `connect` event, i.e. the start of the latest session
+pub fn find_last_session_start(connection_id: i64) -> Result<Option<i64>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, event_name, payload, timestamp, direction FROM event_history WHERE connection_id = ?1 ORDER BY created_at DESC LIMIT ?2"
+    let mut stmt = conn.prepare_cached(
+        "SELECT MAX(id) FROM event_history WHERE connection_id = ?1 AND event_name = 'connect' AND direction = 'in'",
     )?;
+    stmt.query_row(params![connection_id], |row| row.get(0))
+}
 
-    let rows = stmt.query_map(params![connection_id, limit], |row| {
-        Ok((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-            row.get(4)?,
-        ))
-    })?;
+/// Turn free text into an FTS5 query: every word must match, `word*` matches a prefix
+fn fts_query(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| match word.strip_suffix('*') {
+            Some(prefix) if !prefix.is_empty() => {
+                format!("\"{}\"*", prefix.replace('"', "\"\""))
+            }
+            _ => format!("\"{}\"", word.replace('"', "\"\"")),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Search event history by text in the event name or payload.
+///
+/// An empty `text` only applies the filters. Rows are newest first, or oldest
+/// first when `filter.is_forward()`.
+pub fn search_event_history(
+    text: &str,
+    filter: &HistoryFilter,
+    limit: i64,
+) -> Result<Vec<EventHistoryRecord>> {
+    let mut sql = format!(
+        "SELECT {} FROM event_history WHERE 1 = 1",
+        EventHistoryRecord::COLUMNS
+    );
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
 
-    let mut results = Vec::new();
-    for row in rows {
-        results.push(row?);
+    let query = fts_query(text);
+    if !query.is_empty() {
+        sql.push_str(
+            " AND id IN (SELECT rowid FROM event_history_fts WHERE event_history_fts MATCH ?)",
+        );
+        values.push(query.into());
     }
-    Ok(results)
+    filter.push_sql(&mut sql, &mut values, "timestamp", limit);
+
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), EventHistoryRecord::from_row)?;
+    rows.collect()
 }
 
 pub fn clear_event_history(connection_id: i64) -> Result<()> {
@@ -534,6 +1223,150 @@ pub fn clear_event_history(connection_id: i64) -> Result<()> {
     Ok(())
 }
 
+// Retention operations
+/// Delete all but the newest `keep` events of a connection; returns the rows deleted
+pub fn prune_event_history_to_rows(connection_id: i64, keep: i64) -> Result<usize> {
+    let conn = get_connection()?;
+    conn.execute(
+        "DELETE FROM event_history WHERE connection_id = ?1 AND id <= (
+            SELECT id FROM event_history WHERE connection_id = ?1 ORDER BY id DESC LIMIT 1 OFFSET ?2
+        )",
+        params![connection_id, keep],
+    )
+}
+
+/// Delete events of a connection recorded before the RFC 3339 `cutoff`
+pub fn prune_event_history_before(connection_id: i64, cutoff: &str) -> Result<usize> {
+    let conn = get_connection()?;
+    conn.execute(
+        "DELETE FROM event_history WHERE connection_id = ?1 AND timestamp < ?2",
+        params![connection_id, normalize_timestamp(cutoff)],
+    )
+}
+
+/// Delete the oldest `count` recorded events across all connections, keeping imported sessions
+pub fn prune_oldest_event_history(count: i64) -> Result<usize> {
+    let conn = get_connection()?;
+    conn.execute(
+        "DELETE FROM event_history WHERE id IN (
+            SELECT h.id FROM event_history h JOIN connections c ON c.id = h.connection_id
+            WHERE c.read_only = 0 ORDER BY h.id LIMIT ?1
+        )",
+        params![count],
+    )
+}
+
+/// Connections that have recorded events; imported sessions are left out so retention keeps them
+pub fn list_event_history_connection_ids() -> Result<Vec<i64>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(
+        "SELECT DISTINCT h.connection_id FROM event_history h
+         JOIN connections c ON c.id = h.connection_id WHERE c.read_only = 0",
+    )?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// (file bytes, bytes in use); free pages are only returned to the OS by VACUUM
+pub fn database_size() -> Result<(i64, i64)> {
+    let conn = get_connection()?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let freelist_count: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    Ok((
+        page_count * page_size,
+        (page_count - freelist_count) * page_size,
+    ))
+}
+
+/// Approximate storage used per connection (payload and event name bytes)
+pub fn list_connection_storage() -> Result<Vec<ConnectionStorageRecord>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(
+        "SELECT c.id AS connection_id,
+            (SELECT COUNT(*) FROM event_history h WHERE h.connection_id = c.id) AS event_count,
+            (SELECT COALESCE(SUM(LENGTH(h.payload) + LENGTH(h.event_name)), 0) FROM event_history h WHERE h.connection_id = c.id) AS event_bytes,
+            (SELECT COUNT(*) FROM emit_logs l WHERE l.connection_id = c.id) AS emit_log_count,
+            (SELECT MIN(h.timestamp) FROM event_history h WHERE h.connection_id = c.id) AS oldest_event,
+            (SELECT MAX(h.timestamp) FROM event_history h WHERE h.connection_id = c.id) AS newest_event
+         FROM connections c ORDER BY c.id",
+    )?;
+    let rows = stmt.query_map([], ConnectionStorageRecord::from_row)?;
+    rows.collect()
+}
+
+/// Compact the full-text index and rebuild the database file to release free pages
+pub fn vacuum() -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute(
+        "INSERT INTO event_history_fts(event_history_fts) VALUES ('optimize')",
+        [],
+    )?;
+    conn.execute_batch("VACUUM")
+}
+
+// MCP audit log operations
+/// Insert an audit entry; `record.id` is ignored
+pub fn add_mcp_audit(record: &McpAuditRecord) -> Result<i64> {
+    let conn = get_connection()?;
+    conn.prepare_cached(
+        "INSERT INTO mcp_audit_log (timestamp, client_name, client_version, tool, arguments, result, error, duration_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?
+    .execute(params![
+        normalize_timestamp(&record.timestamp),
+        record.client_name,
+        record.client_version,
+        record.tool,
+        record.arguments,
+        record.result,
+        record.error,
+        record.duration_ms
+    ])?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Audit entries newest first, optionally for one tool and older than `before_id`
+pub fn list_mcp_audit(
+    tool: Option<&str>,
+    before_id: Option<i64>,
+    limit: i64,
+) -> Result<Vec<McpAuditRecord>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM mcp_audit_log WHERE tool = COALESCE(?1, tool) AND id < COALESCE(?2, id + 1)
+         ORDER BY id DESC LIMIT ?3",
+        McpAuditRecord::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![tool, before_id, limit], McpAuditRecord::from_row)?;
+    rows.collect()
+}
+
+pub fn clear_mcp_audit() -> Result<usize> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM mcp_audit_log", [])
+}
+
+/// Delete all but the newest `keep` audit entries; returns the rows deleted
+pub fn prune_mcp_audit_to_rows(keep: i64) -> Result<usize> {
+    let conn = get_connection()?;
+    conn.execute(
+        "DELETE FROM mcp_audit_log WHERE id <= (
+            SELECT id FROM mcp_audit_log ORDER BY id DESC LIMIT 1 OFFSET ?1
+        )",
+        params![keep],
+    )
+}
+
+/// Delete audit entries recorded before the RFC 3339 `cutoff`
+pub fn prune_mcp_audit_before(cutoff: &str) -> Result<usize> {
+    let conn = get_connection()?;
+    conn.execute(
+        "DELETE FROM mcp_audit_log WHERE timestamp < ?1",
+        params![normalize_timestamp(cutoff)],
+    )
+}
+
 // App state operations
 pub fn set_app_state(key: &str, value: &str) -> Result<()> {
     let conn = get_connection()?;
@@ -546,12 +1379,7 @@ pub fn set_app_state(key: &str, value: &str) -> Result<()> {
 
 pub fn get_app_state(key: &str) -> Result<Option<String>> {
     let conn = get_connection()?;
-    let mut stmt = conn.prepare("SELECT value FROM app_state WHERE key = ?1")?;
-    let mut rows = stmt.query(params![key])?;
-
-    if let Some(row) = rows.next()? {
-        Ok(Some(row.get(0)?))
-    } else {
-        Ok(None)
-    }
+    let mut stmt = conn.prepare_cached("SELECT value FROM app_state WHERE key = ?1")?;
+    let mut rows = stmt.query_map(params![key], |row| row.get(0))?;
+    rows.next().transpose()
 }
@@ -1,21 +1,160 @@
 use crate::db;
+use crate::redaction;
+use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 
+pub use crate::db::{
+    EmitLogRecord as EmitLog, EventHistoryRecord as EventHistoryItem,
+    EventHistoryRecord as EventSearchResult,
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 1000;
+
+/// One page of history, newest first
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EmitLog {
-    pub id: i64,
-    pub event_name: String,
-    pub payload: String,
-    pub sent_at: String,
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage<T> {
+    pub items: Vec<T>,
+    /// More rows exist past this page in the direction being paged
+    pub has_more: bool,
+    /// Pass as `beforeId` to fetch the next older page
+    pub older_cursor: Option<i64>,
+    /// Pass as `afterId` to fetch the next newer page
+    pub newer_cursor: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EventHistoryItem {
-    pub id: i64,
-    pub event_name: String,
-    pub payload: String,
-    pub timestamp: String,
-    pub direction: String,
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPageInput {
+    pub connection_id: i64,
+    pub event_name: Option<String>,
+    /// `in` or `out`
+    pub direction: Option<String>,
+    /// RFC 3339 timestamps bounding the page (inclusive)
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub before_id: Option<i64>,
+    pub after_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSearchInput {
+    /// Words to find in the event name or payload; `word*` matches a prefix
+    #[serde(default)]
+    pub query: String,
+    /// Searches every connection when omitted
+    pub connection_id: Option<i64>,
+    pub event_name: Option<String>,
+    /// `in` or `out`
+    pub direction: Option<String>,
+    /// RFC 3339 timestamps bounding the search (inclusive)
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub before_id: Option<i64>,
+    pub after_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+pub(crate) fn validate_direction(direction: Option<String>) -> Result<Option<String>, String> {
+    match direction.as_deref() {
+        None | Some("in") | Some("out") => Ok(direction),
+        Some(other) => Err(format!("Invalid direction: {} (expected in or out)", other)),
+    }
+}
+
+pub(crate) fn validate_timestamp(timestamp: Option<String>) -> Result<Option<String>, String> {
+    if let Some(value) = &timestamp {
+        DateTime::parse_from_rfc3339(value)
+            .map_err(|e| format!("Invalid timestamp {}: {}", value, e))?;
+    }
+    Ok(timestamp)
+}
+
+fn page_size(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Build a page from rows fetched with `limit + 1` so the extra row signals `has_more`
+fn into_page<T>(
+    mut items: Vec<T>,
+    limit: i64,
+    forward: bool,
+    id: impl Fn(&T) -> i64,
+) -> HistoryPage<T> {
+    let has_more = items.len() as i64 > limit;
+    items.truncate(limit as usize);
+    if forward {
+        items.reverse();
+    }
+
+    HistoryPage {
+        older_cursor: items.last().map(&id),
+        newer_cursor: items.first().map(&id),
+        has_more,
+        items,
+    }
+}
+
+/// Full-text search over event history (shared by the Tauri command and MCP tool)
+pub fn search_events(input: EventSearchInput) -> Result<HistoryPage<EventSearchResult>, String> {
+    let filter = db::HistoryFilter {
+        connection_id: input.connection_id,
+        event_name: input.event_name,
+        event_names: Vec::new(),
+        direction: validate_direction(input.direction)?,
+        since: validate_timestamp(input.since)?,
+        until: validate_timestamp(input.until)?,
+        before_id: input.before_id,
+        after_id: input.after_id,
+    };
+    let limit = page_size(input.limit);
+    let items =
+        db::search_event_history(&input.query, &filter, limit + 1).map_err(|e| e.to_string())?;
+    Ok(into_page(items, limit, filter.is_forward(), |e| e.id))
+}
+
+/// A page of a connection's event history
+pub fn query_event_history(
+    input: HistoryPageInput,
+) -> Result<HistoryPage<EventHistoryItem>, String> {
+    let filter = db::HistoryFilter {
+        connection_id: Some(input.connection_id),
+        event_name: input.event_name,
+        event_names: Vec::new(),
+        direction: validate_direction(input.direction)?,
+        since: validate_timestamp(input.since)?,
+        until: validate_timestamp(input.until)?,
+        before_id: input.before_id,
+        after_id: input.after_id,
+    };
+    let limit = page_size(input.limit);
+    let items = db::search_event_history("", &filter, limit + 1).map_err(|e| e.to_string())?;
+    Ok(into_page(items, limit, filter.is_forward(), |e| e.id))
+}
+
+/// A page of a connection's emit logs
+pub fn query_emit_logs(input: HistoryPageInput) -> Result<HistoryPage<EmitLog>, String> {
+    // Emit logs only hold outgoing messages
+    if validate_direction(input.direction)?.as_deref() == Some("in") {
+        return Ok(into_page(Vec::new(), 0, false, |e: &EmitLog| e.id));
+    }
+
+    let filter = db::HistoryFilter {
+        connection_id: Some(input.connection_id),
+        event_name: input.event_name,
+        event_names: Vec::new(),
+        direction: None,
+        since: validate_timestamp(input.since)?,
+        until: validate_timestamp(input.until)?,
+        before_id: input.before_id,
+        after_id: input.after_id,
+    };
+    let limit = page_size(input.limit);
+    let items = db::list_emit_logs_filtered(&filter, limit + 1).map_err(|e| e.to_string())?;
+    Ok(into_page(items, limit, filter.is_forward(), |e| e.id))
 }
 
 #[tauri::command]
@@ -24,23 +163,19 @@ pub fn add_emit_log(
     event_name: String,
     payload: String,
 ) -> Result<i64, String> {
-    db::add_emit_log(connection_id, &event_name, &payload).map_err(|e| e.to_string())
+    let (payload, redacted) = redaction::redact_payload(connection_id, &payload);
+    db::add_emit_log(connection_id, &event_name, &payload, redacted).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn list_emit_logs(connection_id: i64, limit: Option<i64>) -> Result<Vec<EmitLog>, String> {
     let limit = limit.unwrap_or(100);
-    let rows = db::list_emit_logs(connection_id, limit).map_err(|e| e.to_string())?;
+    db::list_emit_logs(connection_id, limit).map_err(|e| e.to_string())
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|(id, event_name, payload, sent_at)| EmitLog {
-            id,
-            event_name,
-            payload,
-            sent_at,
-        })
-        .collect())
+#[tauri::command]
+pub fn list_emit_logs_page(input: HistoryPageInput) -> Result<HistoryPage<EmitLog>, String> {
+    query_emit_logs(input)
 }
 
 #[tauri::command]
@@ -55,18 +190,21 @@ pub fn list_event_history(
     limit: Option<i64>,
 ) -> Result<Vec<EventHistoryItem>, String> {
     let limit = limit.unwrap_or(1000);
-    let rows = db::list_event_history(connection_id, limit).map_err(|e| e.to_string())?;
-
-    Ok(rows
-        .into_iter()
-        .map(|(id, event_name, payload, timestamp, direction)| EventHistoryItem {
-            id,
-            event_name,
-            payload,
-            timestamp,
-            direction,
-        })
-        .collect())
+    db::list_event_history(connection_id, limit).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_event_history_page(
+    input: HistoryPageInput,
+) -> Result<HistoryPage<EventHistoryItem>, String> {
+    query_event_history(input)
+}
+
+#[tauri::command]
+pub fn search_event_history(
+    input: EventSearchInput,
+) -> Result<HistoryPage<EventSearchResult>, String> {
+    search_events(input)
 }
 
 #[tauri::command]
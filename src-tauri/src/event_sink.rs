@@ -0,0 +1,188 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+const SOCKET_STATUS_EVENT: &str = "socket:status";
+const SOCKET_EVENT_EVENT: &str = "socket:event";
+const SOCKET_ERROR_EVENT: &str = "socket:error";
+const PROXY_BREAKPOINT_EVENT: &str = "proxy:breakpoint";
+const MCP_CONFIRMATION_EVENT: &str = "mcp:confirmation";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketStatusPayload {
+    pub connection_id: i64,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketErrorPayload {
+    pub connection_id: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketEventPayload {
+    pub connection_id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub timestamp: String,
+    pub direction: String,
+}
+
+/// A proxied message held until the user forwards or drops it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyBreakpointPayload {
+    pub connection_id: i64,
+    pub breakpoint_id: u64,
+    pub event_name: String,
+    pub payload: String,
+    pub direction: String,
+}
+
+/// An MCP tool call waiting for the user to allow or deny it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConfirmationPayload {
+    pub request_id: u64,
+    pub tool: String,
+    /// Tool arguments as JSON, with secrets redacted
+    pub arguments: String,
+    pub connection_id: Option<i64>,
+}
+
+/// A status, error or event notification produced by `SocketManager` (or the proxy)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SocketNotification {
+    Status(SocketStatusPayload),
+    Error(SocketErrorPayload),
+    Event(SocketEventPayload),
+    Breakpoint(ProxyBreakpointPayload),
+    McpConfirmation(McpConfirmationPayload),
+}
+
+/// Receives notifications from `SocketManager`.
+///
+/// Called from socket callback threads, so implementations must not block.
+pub trait EventSink: Send + Sync {
+    fn send(&self, notification: SocketNotification);
+
+    /// Whether someone answers `McpConfirmation` notifications sent to this sink
+    fn handles_confirmations(&self) -> bool {
+        false
+    }
+}
+
+/// Forwards notifications to the Tauri frontend as `socket:*` events
+pub struct TauriSink {
+    app_handle: AppHandle,
+}
+
+impl TauriSink {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl EventSink for TauriSink {
+    fn send(&self, notification: SocketNotification) {
+        let _ = match notification {
+            SocketNotification::Status(payload) => {
+                self.app_handle.emit(SOCKET_STATUS_EVENT, payload)
+            }
+            SocketNotification::Error(payload) => self.app_handle.emit(SOCKET_ERROR_EVENT, payload),
+            SocketNotification::Event(payload) => self.app_handle.emit(SOCKET_EVENT_EVENT, payload),
+            SocketNotification::Breakpoint(payload) => {
+                self.app_handle.emit(PROXY_BREAKPOINT_EVENT, payload)
+            }
+            SocketNotification::McpConfirmation(payload) => {
+                self.app_handle.emit(MCP_CONFIRMATION_EVENT, payload)
+            }
+        };
+    }
+
+    fn handles_confirmations(&self) -> bool {
+        true
+    }
+}
+
+/// Publishes notifications on a broadcast channel (MCP subscribers, CLI)
+#[derive(Clone)]
+pub struct BroadcastSink {
+    tx: broadcast::Sender<SocketNotification>,
+}
+
+impl BroadcastSink {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SocketNotification> {
+        self.tx.subscribe()
+    }
+}
+
+impl EventSink for BroadcastSink {
+    fn send(&self, notification: SocketNotification) {
+        // No subscribers is not an error
+        let _ = self.tx.send(notification);
+    }
+}
+
+/// Collects notifications in memory so tests can assert on them
+#[derive(Default)]
+pub struct MemorySink {
+    notifications: Mutex<Vec<SocketNotification>>,
+    confirmer: bool,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A sink whose test answers MCP confirmations with `resolve_confirmation`
+    pub fn with_confirmer() -> Self {
+        Self {
+            confirmer: true,
+            ..Self::default()
+        }
+    }
+
+    /// All notifications received so far
+    pub fn notifications(&self) -> Vec<SocketNotification> {
+        self.notifications
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
+    /// Remove and return all notifications received so far
+    pub fn take(&self) -> Vec<SocketNotification> {
+        self.notifications
+            .lock()
+            .map(|mut guard| std::mem::take(&mut *guard))
+            .unwrap_or_default()
+    }
+}
+
+impl EventSink for MemorySink {
+    fn send(&self, notification: SocketNotification) {
+        if let Ok(mut guard) = self.notifications.lock() {
+            guard.push(notification);
+        }
+    }
+
+    fn handles_confirmations(&self) -> bool {
+        self.confirmer
+    }
+}
@@ -0,0 +1,553 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::db::{self, ConnectionRecord, EventHistoryRecord};
+use crate::emit_log::{validate_direction, validate_timestamp};
+use crate::history_writer;
+
+/// Rows read from the database per query while exporting
+const EXPORT_PAGE_SIZE: i64 = 1000;
+const CSV_HEADER: &str = "id,connection_id,timestamp,direction,event_name,payload";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryFormat {
+    /// One JSON event per line
+    Ndjson,
+    Csv,
+    /// HAR 1.2 file with a single WebSocket entry, as browsers export
+    Har,
+}
+
+impl HistoryFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "csv" => Some(Self::Csv),
+            "har" | "json" => Some(Self::Har),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Which events of a connection to export; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportFilter {
+    pub connection_id: i64,
+    #[serde(default)]
+    pub event_names: Vec<String>,
+    /// `in` or `out`
+    pub direction: Option<String>,
+    /// RFC 3339 timestamps bounding the export (inclusive)
+    pub since: Option<String>,
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportReport {
+    pub path: String,
+    pub format: HistoryFormat,
+    pub events: usize,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn epoch_seconds(timestamp: &str) -> f64 {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|time| time.timestamp_millis() as f64 / 1000.0)
+        .unwrap_or_default()
+}
+
+fn har_header(connection: &ConnectionRecord, started: &str) -> Value {
+    json!({
+        "startedDateTime": started,
+        "time": 0,
+        "request": {
+            "method": "GET",
+            "url": connection.url,
+            "httpVersion": "HTTP/1.1",
+            "headers": [],
+            "queryString": [],
+            "cookies": [],
+            "headersSize": -1,
+            "bodySize": 0
+        },
+        "response": {
+            "status": 101,
+            "statusText": "Switching Protocols",
+            "httpVersion": "HTTP/1.1",
+            "headers": [],
+            "cookies": [],
+            "content": { "size": 0, "mimeType": "" },
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": 0
+        },
+        "cache": {},
+        "timings": { "send": 0, "wait": 0, "receive": 0 },
+        "_resourceType": "websocket",
+        "_connection": {
+            "id": connection.id,
+            "name": connection.name,
+            "url": connection.url,
+            "namespace": connection.namespace
+        }
+    })
+}
+
+fn har_message(event: &EventHistoryRecord) -> Value {
+    json!({
+        "type": if event.direction == "out" { "send" } else { "receive" },
+        "time": epoch_seconds(&event.timestamp),
+        "opcode": 1,
+        "data": event.payload,
+        "_id": event.id,
+        "_event": event.event_name,
+        "_timestamp": event.timestamp
+    })
+}
+
+/// Write the events matching `filter` to `out`, oldest first, a page at a time
+pub fn export_history(
+    filter: &HistoryExportFilter,
+    format: HistoryFormat,
+    out: &mut impl Write,
+) -> Result<usize, String> {
+    let connection = db::get_connection_by_id(filter.connection_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Connection not found".to_string())?;
+    let mut page_filter = db::HistoryFilter {
+        connection_id: Some(filter.connection_id),
+        event_names: filter.event_names.clone(),
+        direction: validate_direction(filter.direction.clone())?,
+        since: validate_timestamp(filter.since.clone())?,
+        until: validate_timestamp(filter.until.clone())?,
+        // Paging forward from the start reads oldest first
+        after_id: Some(0),
+        ..Default::default()
+    };
+    let write_error = |e: std::io::Error| format!("Failed to write export: {}", e);
+
+    let mut page =
+        db::search_event_history("", &page_filter, EXPORT_PAGE_SIZE).map_err(|e| e.to_string())?;
+    match format {
+        HistoryFormat::Ndjson => {}
+        HistoryFormat::Csv => writeln!(out, "{}", CSV_HEADER).map_err(write_error)?,
+        HistoryFormat::Har => {
+            let started = page
+                .first()
+                .map(|event| event.timestamp.clone())
+                .unwrap_or_else(|| Utc::now().to_rfc3339());
+            let mut entry = har_header(&connection, &started).to_string();
+            // Leave the entry open so messages can be streamed into it
+            entry.pop();
+            write!(
+                out,
+                "{{\"log\":{{\"version\":\"1.2\",\"creator\":{},\"pages\":[],\"entries\":[{},\"_webSocketMessages\":[",
+                json!({ "name": "Socket.IO Client", "version": env!("CARGO_PKG_VERSION") }),
+                entry
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    let mut count = 0;
+    loop {
+        for event in &page {
+            match format {
+                HistoryFormat::Ndjson => {
+                    serde_json::to_writer(&mut *out, event).map_err(|e| e.to_string())?;
+                    writeln!(out).map_err(write_error)?;
+                }
+                HistoryFormat::Csv => writeln!(
+                    out,
+                    "{},{},{},{},{},{}",
+                    event.id,
+                    event.connection_id,
+                    csv_field(&event.timestamp),
+                    event.direction,
+                    csv_field(&event.event_name),
+                    csv_field(&event.payload)
+                )
+                .map_err(write_error)?,
+                HistoryFormat::Har => {
+                    if count > 0 {
+                        out.write_all(b",").map_err(write_error)?;
+                    }
+                    serde_json::to_writer(&mut *out, &har_message(event))
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            count += 1;
+        }
+
+        if (page.len() as i64) < EXPORT_PAGE_SIZE {
+            break;
+        }
+        page_filter.after_id = page.last().map(|event| event.id);
+        page = db::search_event_history("", &page_filter, EXPORT_PAGE_SIZE)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if format == HistoryFormat::Har {
+        out.write_all(b"]}]}}").map_err(write_error)?;
+    }
+    out.flush().map_err(write_error)?;
+    Ok(count)
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedSession {
+    /// Read-only connection holding the imported events
+    pub connection_id: i64,
+    pub name: String,
+    pub events: usize,
+}
+
+/// Connection details recovered from the file, if it has any
+#[derive(Default)]
+struct SessionSource {
+    name: Option<String>,
+    url: Option<String>,
+    namespace: Option<String>,
+}
+
+/// Build an event from imported fields, checking direction and timestamp
+fn imported_event(
+    event_name: String,
+    payload: String,
+    timestamp: String,
+    direction: String,
+) -> Result<EventHistoryRecord, String> {
+    if event_name.is_empty() {
+        return Err("Event name cannot be empty".to_string());
+    }
+    validate_direction(Some(direction.clone()))?;
+    validate_timestamp(Some(timestamp.clone()))?;
+    Ok(EventHistoryRecord {
+        id: 0,
+        connection_id: 0,
+        event_name,
+        payload,
+        timestamp,
+        direction,
+        redacted: false,
+    })
+}
+
+/// Payloads are stored as text; JSON values other than strings are serialized
+fn payload_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "{}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Result<String, String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| format!("missing {}", key))
+}
+
+fn parse_ndjson(reader: impl BufRead) -> Result<Vec<EventHistoryRecord>, String> {
+    let mut events = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parse = || -> Result<EventHistoryRecord, String> {
+            let value: Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+            let mut event = imported_event(
+                string_field(&value, "event_name")?,
+                payload_text(value.get("payload").unwrap_or(&Value::Null)),
+                string_field(&value, "timestamp")?,
+                string_field(&value, "direction")?,
+            )?;
+            // Only NDJSON keeps the flag; CSV and HAR exports have no column for it
+            event.redacted = value
+                .get("redacted")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            Ok(event)
+        };
+        events.push(parse().map_err(|e| format!("Line {}: {}", index + 1, e))?);
+    }
+    Ok(events)
+}
+
+/// Split CSV text into records, honouring quoted fields with embedded newlines
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn parse_csv(mut reader: impl BufRead) -> Result<Vec<EventHistoryRecord>, String> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|e| e.to_string())?;
+    let mut records = csv_records(&text)?.into_iter();
+
+    let header = records.next().ok_or("CSV file is empty")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| format!("CSV file has no {} column", name))
+    };
+    let (event_name, payload, timestamp, direction) = (
+        column("event_name")?,
+        column("payload")?,
+        column("timestamp")?,
+        column("direction")?,
+    );
+
+    let mut events = Vec::new();
+    for (index, record) in records.enumerate() {
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        let field = |i: usize| record.get(i).cloned().unwrap_or_default();
+        let event = imported_event(
+            field(event_name),
+            field(payload),
+            field(timestamp),
+            field(direction),
+        )
+        .map_err(|e| format!("Row {}: {}", index + 2, e))?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Split a Socket.IO EVENT packet (`42/ns,7["name",payload]`) into name and payload
+fn socketio_event(frame: &str) -> Option<(String, String)> {
+    let packet = frame.strip_prefix("42")?;
+    let packet = match packet.strip_prefix('/') {
+        Some(rest) => rest.split_once(',')?.1,
+        None => packet,
+    };
+    let packet = packet.trim_start_matches(|c: char| c.is_ascii_digit());
+    let mut args = serde_json::from_str::<Vec<Value>>(packet).ok()?.into_iter();
+    let name = args.next()?.as_str()?.to_string();
+    Some((name, payload_text(&args.next().unwrap_or(Value::Null))))
+}
+
+fn parse_har(reader: impl BufRead) -> Result<(SessionSource, Vec<EventHistoryRecord>), String> {
+    let har: Value =
+        serde_json::from_reader(reader).map_err(|e| format!("Invalid HAR file: {}", e))?;
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(Value::as_array)
+        .ok_or("HAR file has no log entries")?;
+
+    let mut source = SessionSource::default();
+    let mut events = Vec::new();
+    for entry in entries {
+        let Some(messages) = entry.get("_webSocketMessages").and_then(Value::as_array) else {
+            continue;
+        };
+        if source.url.is_none() {
+            let connection = entry.get("_connection");
+            let field = |key: &str| {
+                connection
+                    .and_then(|c| c.get(key))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            source = SessionSource {
+                name: field("name"),
+                url: field("url").or_else(|| {
+                    entry
+                        .pointer("/request/url")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                }),
+                namespace: field("namespace"),
+            };
+        }
+
+        for message in messages {
+            let direction = match message.get("type").and_then(Value::as_str) {
+                Some("send") => "out",
+                Some("receive") => "in",
+                _ => continue,
+            };
+            let data = payload_text(message.get("data").unwrap_or(&Value::Null));
+            // Browser captures carry raw frames; skip Engine.IO pings and other packets
+            let (event_name, payload) = match message.get("_event").and_then(Value::as_str) {
+                Some(name) => (name.to_string(), data),
+                None => match socketio_event(&data) {
+                    Some(event) => event,
+                    None => continue,
+                },
+            };
+            let timestamp = match message.get("_timestamp").and_then(Value::as_str) {
+                Some(timestamp) => timestamp.to_string(),
+                None => {
+                    let seconds = message.get("time").and_then(Value::as_f64).unwrap_or(0.0);
+                    Utc.timestamp_millis_opt((seconds * 1000.0) as i64)
+                        .single()
+                        .ok_or("Invalid message time")?
+                        .to_rfc3339()
+                }
+            };
+            events.push(imported_event(
+                event_name,
+                payload,
+                timestamp,
+                direction.to_string(),
+            )?);
+        }
+    }
+    Ok((source, events))
+}
+
+/// Load an exported capture as a new read-only connection
+pub fn import_history(
+    reader: impl BufRead,
+    format: HistoryFormat,
+    name: Option<String>,
+) -> Result<ImportedSession, String> {
+    let (source, events) = match format {
+        HistoryFormat::Ndjson => (SessionSource::default(), parse_ndjson(reader)?),
+        HistoryFormat::Csv => (SessionSource::default(), parse_csv(reader)?),
+        HistoryFormat::Har => parse_har(reader)?,
+    };
+    if events.is_empty() {
+        return Err("The file contains no events".to_string());
+    }
+
+    let name = name.unwrap_or_else(|| match &source.name {
+        Some(original) => format!("{} (imported)", original),
+        None => "Imported session".to_string(),
+    });
+    let connection_id = db::create_imported_session(
+        &name,
+        source.url.as_deref().unwrap_or("imported://session"),
+        source.namespace.as_deref().unwrap_or("/"),
+        &events,
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(ImportedSession {
+        connection_id,
+        name,
+        events: events.len(),
+    })
+}
+
+fn resolve_format(path: &Path, format: Option<HistoryFormat>) -> Result<HistoryFormat, String> {
+    format
+        .or_else(|| HistoryFormat::from_path(path))
+        .ok_or_else(|| format!("Cannot tell the format of {}", path.display()))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportInput {
+    #[serde(flatten)]
+    pub filter: HistoryExportFilter,
+    pub path: String,
+    /// Guessed from the file extension when omitted
+    pub format: Option<HistoryFormat>,
+}
+
+#[tauri::command]
+pub async fn export_event_history(
+    input: HistoryExportInput,
+) -> Result<HistoryExportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        // Include events still queued on the history writer
+        history_writer::flush();
+
+        let path = Path::new(&input.path);
+        let format = resolve_format(path, input.format)?;
+        let file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+        let events = export_history(&input.filter, format, &mut BufWriter::new(file))?;
+        Ok(HistoryExportReport {
+            path: input.path,
+            format,
+            events,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Import an exported capture as a read-only connection for viewing and replay
+#[tauri::command]
+pub async fn import_event_history(
+    path: String,
+    format: Option<HistoryFormat>,
+    name: Option<String>,
+) -> Result<ImportedSession, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&path);
+        let format = resolve_format(path, format)?;
+        let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+        import_history(BufReader::new(file), format, name)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,328 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use rusqlite::{Connection, params};
+use serde::Serialize;
+
+use crate::db;
+use crate::redaction;
+
+/// Events that can wait in the queue before new ones are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+/// Events written per transaction at most
+const MAX_BATCH: usize = 500;
+/// How long the writer waits for more events before committing a partial batch
+const BATCH_WINDOW: Duration = Duration::from_millis(20);
+
+static WRITER: OnceLock<Option<HistoryWriter>> = OnceLock::new();
+
+struct HistoryRecord {
+    connection_id: i64,
+    event_name: String,
+    payload: String,
+    timestamp: String,
+    direction: String,
+    redacted: bool,
+}
+
+enum WriterCommand {
+    Record(HistoryRecord),
+    /// Reply once everything queued before it is written
+    Flush(mpsc::Sender<()>),
+    Shutdown,
+}
+
+#[derive(Default)]
+struct WriterCounters {
+    accepted: AtomicU64,
+    written: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+    batches: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryWriterStats {
+    /// Events waiting to be written
+    pub pending: u64,
+    pub written: u64,
+    /// Events discarded because the queue was full
+    pub dropped: u64,
+    /// Events lost to failed transactions
+    pub failed: u64,
+    pub batches: u64,
+}
+
+/// Writes event history on a dedicated thread, batching inserts into transactions.
+///
+/// Socket callbacks only enqueue; when the queue is full new events are dropped
+/// and counted rather than blocking the callback.
+pub struct HistoryWriter {
+    tx: SyncSender<WriterCommand>,
+    counters: Arc<WriterCounters>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl HistoryWriter {
+    pub fn start(path: &Path) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+
+        let (tx, rx) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let counters = Arc::new(WriterCounters::default());
+        let thread_counters = counters.clone();
+        let handle = thread::Builder::new()
+            .name("history-writer".to_string())
+            .spawn(move || run(conn, rx, &thread_counters))
+            .map_err(|e| {
+                rusqlite::Error::SqliteFailure(
+                    rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+                    Some(format!("Failed to start the history writer thread: {}", e)),
+                )
+            })?;
+
+        Ok(Self {
+            tx,
+            counters,
+            handle: Mutex::new(Some(handle)),
+        })
+    }
+
+    /// Queue an event; returns false if the writer has been shut down
+    pub fn record(
+        &self,
+        connection_id: i64,
+        event_name: &str,
+        payload: &str,
+        timestamp: &str,
+        direction: &str,
+        redacted: bool,
+    ) -> bool {
+        let record = HistoryRecord {
+            connection_id,
+            event_name: event_name.to_string(),
+            payload: payload.to_string(),
+            timestamp: timestamp.to_string(),
+            direction: direction.to_string(),
+            redacted,
+        };
+        match self.tx.try_send(WriterCommand::Record(record)) {
+            Ok(()) => {
+                self.counters.accepted.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.counters.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped.is_power_of_two() {
+                    log::warn!("[HistoryWriter] Queue full, {} events dropped", dropped);
+                }
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+
+    /// Block until every event queued so far is written
+    pub fn flush(&self) {
+        let (done_tx, done_rx) = mpsc::channel();
+        if self.tx.send(WriterCommand::Flush(done_tx)).is_ok() {
+            let _ = done_rx.recv();
+        }
+    }
+
+    /// Write what is queued and stop the writer thread
+    pub fn shutdown(&self) {
+        let handle = self.handle.lock().ok().and_then(|mut guard| guard.take());
+        if let Some(handle) = handle {
+            let _ = self.tx.send(WriterCommand::Shutdown);
+            let _ = handle.join();
+        }
+    }
+
+    pub fn stats(&self) -> HistoryWriterStats {
+        let counters = &self.counters;
+        let accepted = counters.accepted.load(Ordering::Relaxed);
+        let written = counters.written.load(Ordering::Relaxed);
+        let failed = counters.failed.load(Ordering::Relaxed);
+        HistoryWriterStats {
+            pending: accepted.saturating_sub(written + failed),
+            written,
+            dropped: counters.dropped.load(Ordering::Relaxed),
+            failed,
+            batches: counters.batches.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn run(mut conn: Connection, rx: Receiver<WriterCommand>, counters: &WriterCounters) {
+    let mut batch = Vec::with_capacity(MAX_BATCH);
+    let mut waiting = Vec::new();
+
+    while let Ok(command) = rx.recv() {
+        let mut shutdown = false;
+        let mut next = Some(command);
+
+        // Collect a batch: whatever arrives within the batch window, up to MAX_BATCH
+        while let Some(command) = next.take() {
+            match command {
+                WriterCommand::Record(record) => batch.push(record),
+                WriterCommand::Flush(done) => waiting.push(done),
+                WriterCommand::Shutdown => {
+                    shutdown = true;
+                    break;
+                }
+            }
+            if batch.len() >= MAX_BATCH || !waiting.is_empty() {
+                break;
+            }
+            match rx.recv_timeout(BATCH_WINDOW) {
+                Ok(command) => next = Some(command),
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {}
+            }
+        }
+
+        if shutdown {
+            // Drain anything queued behind the shutdown request
+            while let Ok(command) = rx.try_recv() {
+                match command {
+                    WriterCommand::Record(record) => batch.push(record),
+                    WriterCommand::Flush(done) => waiting.push(done),
+                    WriterCommand::Shutdown => {}
+                }
+            }
+        }
+
+        write_batch(&mut conn, &mut batch, counters);
+        for done in waiting.drain(..) {
+            let _ = done.send(());
+        }
+        if shutdown {
+            break;
+        }
+    }
+}
+
+fn write_batch(conn: &mut Connection, batch: &mut Vec<HistoryRecord>, counters: &WriterCounters) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let result = (|| -> rusqlite::Result<()> {
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO event_history (connection_id, event_name, payload, timestamp, direction, redacted) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for record in batch.iter() {
+                stmt.execute(params![
+                    record.connection_id,
+                    record.event_name,
+                    record.payload,
+                    db::normalize_timestamp(&record.timestamp),
+                    record.direction,
+                    record.redacted
+                ])?;
+            }
+        }
+        tx.commit()
+    })();
+
+    let count = batch.len() as u64;
+    match result {
+        Ok(()) => {
+            counters.written.fetch_add(count, Ordering::Relaxed);
+            counters.batches.fetch_add(1, Ordering::Relaxed);
+        }
+        Err(e) => {
+            log::warn!("[HistoryWriter] Failed to write {} events: {}", count, e);
+            counters.failed.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+    batch.clear();
+}
+
+// ============================================================================
+// Global Writer
+// ============================================================================
+
+/// The writer for the initialized database, started on first use
+fn writer() -> Option<&'static HistoryWriter> {
+    WRITER
+        .get_or_init(|| {
+            let path = db::db_path()?;
+            match HistoryWriter::start(&path) {
+                Ok(writer) => Some(writer),
+                Err(e) => {
+                    log::error!("[HistoryWriter] Failed to start: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Queue an event for the history table (written synchronously if the writer is unavailable)
+pub fn record(
+    connection_id: i64,
+    event_name: &str,
+    payload: &str,
+    timestamp: &str,
+    direction: &str,
+) {
+    let (payload, redacted) = redaction::redact_payload(connection_id, payload);
+    let queued = writer().is_some_and(|writer| {
+        writer.record(
+            connection_id,
+            event_name,
+            &payload,
+            timestamp,
+            direction,
+            redacted,
+        )
+    });
+    if !queued
+        && let Err(e) = db::add_event_history(
+            connection_id,
+            event_name,
+            &payload,
+            timestamp,
+            direction,
+            redacted,
+        )
+    {
+        log::warn!("Failed to persist event to DB: {}", e);
+    }
+}
+
+/// Block until queued events are written
+pub fn flush() {
+    if let Some(writer) = WRITER.get().and_then(Option::as_ref) {
+        writer.flush();
+    }
+}
+
+/// Flush and stop the writer; later events are written synchronously
+pub fn shutdown() {
+    if let Some(writer) = WRITER.get().and_then(Option::as_ref) {
+        writer.shutdown();
+    }
+}
+
+pub fn stats() -> Option<HistoryWriterStats> {
+    WRITER
+        .get()
+        .and_then(Option::as_ref)
+        .map(HistoryWriter::stats)
+}
+
+#[tauri::command]
+pub fn get_history_writer_stats() -> Result<Option<HistoryWriterStats>, String> {
+    Ok(stats())
+}
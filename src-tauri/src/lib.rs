@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::{
     Manager,
     menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
@@ -6,12 +7,27 @@ use tauri::{
 };
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 
+pub mod collection_import;
 mod connection;
-mod db;
+pub mod db;
 mod emit_log;
-mod mcp_server;
+pub mod event_sink;
+pub mod history_export;
+pub mod history_writer;
+pub mod load_test;
+pub mod local_server;
+pub mod mcp_audit;
+pub mod mcp_policy;
+pub mod mcp_server;
+pub mod mock_server;
 mod pinned;
-mod socket_client;
+pub mod profiles;
+pub mod proxy;
+pub mod redaction;
+pub mod replay;
+pub mod retention;
+pub mod secrets;
+pub mod socket_client;
 
 const APP_NAME: &str = "Socket.IO Client";
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -50,9 +66,18 @@ pub fn run() {
 
             let db_path: PathBuf = app_data_dir.join("socket-io-client.db");
             db::init_db(&db_path).map_err(|e| format!("Failed to initialize database: {}", e))?;
+            secrets::init(&app_data_dir)
+                .map_err(|e| format!("Failed to load secrets key: {}", e))?;
+            tauri::async_runtime::spawn(retention::run_pruner());
 
-            app.manage(socket_client::SocketManager::new(app.handle().clone()));
+            app.manage(socket_client::SocketManager::new(Arc::new(
+                event_sink::TauriSink::new(app.handle().clone()),
+            )));
             app.manage(mcp_server::McpServerState::new());
+            app.manage(mock_server::MockServerState::new());
+            app.manage(load_test::LoadTestState::new());
+            app.manage(proxy::ProxyState::new());
+            app.manage(replay::ReplayState::new());
 
             // Setup custom application menu (macOS menu bar)
             #[cfg(target_os = "macos")]
@@ -173,13 +198,30 @@ pub fn run() {
             connection::set_current_connection,
             connection::get_current_connection,
             connection::set_connection_auto_send,
+            // Profile commands
+            profiles::export_connection_profiles,
+            profiles::import_connection_profiles,
+            collection_import::import_socketio_collection,
+            // Redaction commands
+            redaction::get_redaction_settings,
+            redaction::save_redaction_settings,
+            redaction::set_connection_redaction,
+            // Secret commands
+            secrets::get_secret_status,
+            secrets::unlock_secrets,
+            secrets::set_secret_passphrase,
             // Emit log commands
             emit_log::add_emit_log,
             emit_log::list_emit_logs,
+            emit_log::list_emit_logs_page,
             emit_log::clear_emit_logs,
             // Event history commands
             emit_log::list_event_history,
+            emit_log::list_event_history_page,
+            emit_log::search_event_history,
             emit_log::clear_event_history,
+            history_export::export_event_history,
+            history_export::import_event_history,
             // Pinned message commands
             pinned::add_pinned_message,
             pinned::update_pinned_message,
@@ -202,9 +244,49 @@ pub fn run() {
             mcp_server::start_mcp_server,
             mcp_server::stop_mcp_server,
             mcp_server::get_mcp_status,
+            mcp_server::get_mcp_auth_token,
+            mcp_server::rotate_mcp_auth_token,
+            mcp_audit::list_mcp_audit_log,
+            mcp_audit::clear_mcp_audit_log,
+            mcp_policy::get_mcp_policy,
+            mcp_policy::save_mcp_policy,
+            mcp_policy::resolve_mcp_confirmation,
             mcp_server::check_claude_cli,
             mcp_server::run_claude_mcp_add,
+            // Mock server commands
+            mock_server::start_mock_server,
+            mock_server::stop_mock_server,
+            mock_server::get_mock_server_status,
+            // History writer commands
+            history_writer::get_history_writer_stats,
+            // Load test commands
+            load_test::start_load_test,
+            load_test::stop_load_test,
+            load_test::get_load_test_status,
+            // Proxy commands
+            proxy::start_proxy,
+            proxy::stop_proxy,
+            proxy::get_proxy_status,
+            proxy::set_proxy_rules,
+            proxy::resolve_proxy_breakpoint,
+            // Retention commands
+            retention::get_retention_settings,
+            retention::set_retention_settings,
+            retention::prune_event_history_now,
+            retention::get_storage_report,
+            retention::vacuum_database,
+            // Replay commands
+            replay::replay_session_as_client,
+            replay::stop_replay,
+            replay::build_replay_script,
+            replay::start_replay_server,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Write out queued event history before the process ends
+                history_writer::shutdown();
+            }
+        });
 }
@@ -0,0 +1,628 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rust_socketio::client::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db;
+use crate::redaction;
+use crate::secrets;
+use crate::socket_client::client_builder;
+
+const DEFAULT_ACK_TIMEOUT_MS: u64 = 5000;
+/// Worker threads used when the config does not say
+const DEFAULT_WORKERS: u32 = 32;
+/// How often a finished client checks whether its outstanding acks arrived
+const ACK_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadTestConfig {
+    /// Connection profile every virtual client connects with
+    pub connection_id: i64,
+    /// Number of virtual clients
+    pub clients: u32,
+    /// Time over which client start-up is spread evenly
+    #[serde(default)]
+    pub ramp_up_ms: u64,
+    /// How long each client keeps running its scenario after connecting
+    pub duration_ms: u64,
+    /// Pause after each step
+    #[serde(default)]
+    pub think_time_ms: u64,
+    /// Steps each client repeats; defaults to the profile's auto-send messages
+    pub scenario: Option<Vec<LoadStep>>,
+    /// Wait for an ack on the default scenario's messages
+    #[serde(default)]
+    pub ack: bool,
+    /// How long to wait for each ack, and for the last ones after the run (default 5 s)
+    pub ack_timeout_ms: Option<u64>,
+    /// Threads driving the clients, each taking an equal share (default 32)
+    #[serde(default)]
+    pub workers: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadStep {
+    pub event: String,
+    /// JSON payload (sent as a plain string if it does not parse)
+    pub payload: String,
+    /// Measure the ack latency of this emit
+    #[serde(default)]
+    pub ack: bool,
+}
+
+// ============================================================================
+// Report
+// ============================================================================
+
+/// Latency distribution in milliseconds
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencySummary {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+
+        Self {
+            count: sorted.len(),
+            min: sorted[0],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadTestReport {
+    pub clients: u32,
+    pub elapsed_ms: u64,
+    pub connected: u64,
+    pub connect_failures: u64,
+    pub messages_sent: u64,
+    pub emit_errors: u64,
+    pub acks_expected: u64,
+    pub acks_received: u64,
+    /// Unexpected disconnects (closed before the client finished its run)
+    pub disconnects: u64,
+    /// Socket errors reported by the clients
+    pub errors: u64,
+    pub messages_per_sec: f64,
+    pub connect_time: LatencySummary,
+    pub ack_latency: LatencySummary,
+}
+
+/// Counters shared by every virtual client; nothing is written to SQLite
+#[derive(Default)]
+struct LoadStats {
+    connected: AtomicU64,
+    connect_failures: AtomicU64,
+    messages_sent: AtomicU64,
+    emit_errors: AtomicU64,
+    acks_expected: AtomicU64,
+    acks_received: AtomicU64,
+    disconnects: AtomicU64,
+    errors: AtomicU64,
+    connect_times: Mutex<Vec<f64>>,
+    ack_latencies: Mutex<Vec<f64>>,
+}
+
+impl LoadStats {
+    fn add_sample(samples: &Mutex<Vec<f64>>, elapsed: Duration) {
+        if let Ok(mut guard) = samples.lock() {
+            guard.push(elapsed.as_secs_f64() * 1000.0);
+        }
+    }
+
+    fn summary(samples: &Mutex<Vec<f64>>) -> LatencySummary {
+        samples
+            .lock()
+            .map(|guard| LatencySummary::from_samples(&guard))
+            .unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Runner
+// ============================================================================
+
+/// A load test against one connection profile.
+///
+/// Clients are driven by a fixed pool of worker threads, each stepping its share of
+/// clients in turn, so thousands of clients do not need thousands of runner threads.
+/// rust_socketio still reads each connection on a thread of its own.
+pub struct LoadTest {
+    config: LoadTestConfig,
+    url: String,
+    namespace: String,
+    auth_token: Option<String>,
+    options_value: Value,
+    scenario: Vec<LoadStep>,
+    stats: Arc<LoadStats>,
+    cancel: AtomicBool,
+    started_at: Mutex<Option<Instant>>,
+    finished_at: Mutex<Option<Instant>>,
+}
+
+impl LoadTest {
+    /// Load the connection profile and scenario
+    pub fn new(config: LoadTestConfig) -> Result<Self, String> {
+        if config.clients == 0 {
+            return Err("At least one client is required".to_string());
+        }
+
+        let db::ConnectionRecord {
+            url,
+            namespace,
+            auth_token,
+            options,
+            ..
+        } = db::get_connection_by_id(config.connection_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Connection not found".to_string())
+            .and_then(secrets::reveal_connection)?;
+        let options_value: Value = serde_json::from_str(&options).unwrap_or(Value::Null);
+
+        let scenario = match &config.scenario {
+            Some(steps) => steps.clone(),
+            None => db::list_auto_send_messages(config.connection_id)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|message| LoadStep {
+                    event: message.event_name,
+                    payload: message.payload,
+                    ack: config.ack,
+                })
+                .collect(),
+        };
+
+        Ok(Self {
+            config,
+            url,
+            namespace,
+            auth_token,
+            options_value,
+            scenario,
+            stats: Arc::new(LoadStats::default()),
+            cancel: AtomicBool::new(false),
+            started_at: Mutex::new(None),
+            finished_at: Mutex::new(None),
+        })
+    }
+
+    /// Run every virtual client to completion and return the final report
+    pub fn run(self: &Arc<Self>) -> LoadTestReport {
+        if let Ok(mut started_at) = self.started_at.lock() {
+            *started_at = Some(Instant::now());
+        }
+        log::info!(
+            "[LoadTest] Starting {} clients against connection {}",
+            self.config.clients,
+            self.config.connection_id
+        );
+
+        // Client start times are spread over the ramp-up and dealt out round-robin
+        let started = Instant::now();
+        let spacing = Duration::from_millis(self.config.ramp_up_ms) / self.config.clients;
+        let workers = self
+            .config
+            .workers
+            .unwrap_or(DEFAULT_WORKERS)
+            .clamp(1, self.config.clients);
+        let mut shares: Vec<Vec<Instant>> = vec![Vec::new(); workers as usize];
+        for index in 0..self.config.clients {
+            shares[(index % workers) as usize].push(started + spacing * index);
+        }
+
+        let mut handles = Vec::new();
+        for (index, share) in shares.into_iter().enumerate() {
+            let clients = share.len() as u64;
+            let test = self.clone();
+            let handle = thread::Builder::new()
+                .name(format!("load-worker-{}", index))
+                .spawn(move || test.run_worker(share));
+            match handle {
+                Ok(handle) => handles.push(handle),
+                Err(e) => {
+                    log::error!("[LoadTest] Failed to spawn worker thread: {}", e);
+                    self.stats
+                        .connect_failures
+                        .fetch_add(clients, Ordering::Relaxed);
+                }
+            }
+        }
+        for handle in handles {
+            let _ = handle.join();
+        }
+        if let Ok(mut finished_at) = self.finished_at.lock() {
+            *finished_at = Some(Instant::now());
+        }
+
+        let report = self.report();
+        log::info!(
+            "[LoadTest] Finished: {} connected, {} messages sent",
+            report.connected,
+            report.messages_sent
+        );
+        report
+    }
+
+    /// Ask every client to stop after its current step
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the results so far
+    pub fn report(&self) -> LoadTestReport {
+        let stats = &self.stats;
+        let started_at = self.started_at.lock().ok().and_then(|guard| *guard);
+        let finished_at = self.finished_at.lock().ok().and_then(|guard| *guard);
+        let elapsed = match (started_at, finished_at) {
+            (Some(started_at), Some(finished_at)) => finished_at - started_at,
+            (Some(started_at), None) => started_at.elapsed(),
+            _ => Duration::ZERO,
+        };
+        let messages_sent = stats.messages_sent.load(Ordering::Relaxed);
+        let secs = elapsed.as_secs_f64();
+
+        LoadTestReport {
+            clients: self.config.clients,
+            elapsed_ms: elapsed.as_millis() as u64,
+            connected: stats.connected.load(Ordering::Relaxed),
+            connect_failures: stats.connect_failures.load(Ordering::Relaxed),
+            messages_sent,
+            emit_errors: stats.emit_errors.load(Ordering::Relaxed),
+            acks_expected: stats.acks_expected.load(Ordering::Relaxed),
+            acks_received: stats.acks_received.load(Ordering::Relaxed),
+            disconnects: stats.disconnects.load(Ordering::Relaxed),
+            errors: stats.errors.load(Ordering::Relaxed),
+            messages_per_sec: if secs > 0.0 {
+                messages_sent as f64 / secs
+            } else {
+                0.0
+            },
+            connect_time: LoadStats::summary(&stats.connect_times),
+            ack_latency: LoadStats::summary(&stats.ack_latencies),
+        }
+    }
+
+    /// Sleep in short slices; returns true if the test was cancelled
+    fn sleep(&self, duration: Duration) -> bool {
+        let mut remaining = duration;
+        while !remaining.is_zero() && !self.cancel.load(Ordering::Relaxed) {
+            let slice = remaining.min(Duration::from_millis(100));
+            thread::sleep(slice);
+            remaining -= slice;
+        }
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn connect_client(&self, finished: Arc<AtomicBool>) -> Result<Client, String> {
+        let close_stats = self.stats.clone();
+        let error_stats = self.stats.clone();
+
+        client_builder(
+            self.url.clone(),
+            self.namespace.clone(),
+            self.auth_token.as_deref(),
+            &self.options_value,
+        )
+        // A dropped virtual client counts as a disconnect instead of reconnecting
+        .reconnect_on_disconnect(false)
+        .on(rust_socketio::Event::Close, move |_, _| {
+            if !finished.load(Ordering::Relaxed) {
+                close_stats.disconnects.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .on(rust_socketio::Event::Error, move |_, _| {
+            error_stats.errors.fetch_add(1, Ordering::Relaxed);
+        })
+        .connect()
+        .map_err(|e| e.to_string())
+    }
+
+    /// Step every client of this worker, earliest due first, until all have finished
+    fn run_worker(self: Arc<Self>, starts: Vec<Instant>) {
+        let mut clients: Vec<VirtualClient> =
+            starts.into_iter().map(VirtualClient::Waiting).collect();
+
+        while let Some((index, due)) = clients
+            .iter()
+            .enumerate()
+            .filter_map(|(index, client)| client.due().map(|due| (index, due)))
+            .min_by_key(|(_, due)| *due)
+        {
+            if self.sleep(due.saturating_duration_since(Instant::now())) {
+                break;
+            }
+            let client = std::mem::replace(&mut clients[index], VirtualClient::Done);
+            clients[index] = self.advance(client);
+        }
+
+        // Cancelled: close whatever is still connected
+        for client in clients {
+            if let VirtualClient::Running(running) = client {
+                running.close();
+            }
+        }
+    }
+
+    /// Take one client one step further: connect, emit, or wait for its last acks
+    fn advance(&self, client: VirtualClient) -> VirtualClient {
+        let now = Instant::now();
+        let mut running = match client {
+            VirtualClient::Waiting(_) => return self.start_client(),
+            VirtualClient::Running(running) => running,
+            VirtualClient::Done => return VirtualClient::Done,
+        };
+
+        match running.ack_deadline {
+            // Wait up to the ack timeout for outstanding acks before closing
+            Some(ack_deadline) => {
+                if running.outstanding.load(Ordering::Relaxed) == 0 || now >= ack_deadline {
+                    running.close();
+                    return VirtualClient::Done;
+                }
+                running.next_at = now + ACK_POLL_INTERVAL;
+            }
+            None if now >= running.deadline => {
+                running.ack_deadline = Some(now + running.ack_timeout);
+                running.next_at = now;
+            }
+            None => {
+                let step = &self.scenario[running.step % self.scenario.len()];
+                running.step += 1;
+                self.run_step(
+                    &running.client,
+                    step,
+                    running.ack_timeout,
+                    &running.outstanding,
+                );
+                let think_time = Duration::from_millis(self.config.think_time_ms);
+                running.next_at = (now + think_time).min(running.deadline);
+            }
+        }
+        VirtualClient::Running(running)
+    }
+
+    fn start_client(&self) -> VirtualClient {
+        let finished = Arc::new(AtomicBool::new(false));
+        let connect_started = Instant::now();
+        let client = match self.connect_client(finished.clone()) {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!(
+                    "[LoadTest] Client failed to connect: {}",
+                    redaction::redact_text(Some(self.config.connection_id), &e)
+                );
+                self.stats.connect_failures.fetch_add(1, Ordering::Relaxed);
+                return VirtualClient::Done;
+            }
+        };
+        LoadStats::add_sample(&self.stats.connect_times, connect_started.elapsed());
+        self.stats.connected.fetch_add(1, Ordering::Relaxed);
+
+        let now = Instant::now();
+        let deadline = now + Duration::from_millis(self.config.duration_ms);
+        VirtualClient::Running(RunningClient {
+            client,
+            finished,
+            outstanding: Arc::new(AtomicU64::new(0)),
+            ack_timeout: Duration::from_millis(
+                self.config.ack_timeout_ms.unwrap_or(DEFAULT_ACK_TIMEOUT_MS),
+            ),
+            deadline,
+            ack_deadline: None,
+            step: 0,
+            // Without a scenario clients just hold their connection open
+            next_at: if self.scenario.is_empty() {
+                deadline
+            } else {
+                now
+            },
+        })
+    }
+
+    fn run_step(
+        &self,
+        client: &Client,
+        step: &LoadStep,
+        ack_timeout: Duration,
+        outstanding: &Arc<AtomicU64>,
+    ) {
+        let payload = serde_json::from_str::<Value>(&step.payload)
+            .unwrap_or_else(|_| Value::String(step.payload.clone()));
+
+        let result = if step.ack {
+            let stats = self.stats.clone();
+            let sent_at = Instant::now();
+            let pending = outstanding.clone();
+            self.stats.acks_expected.fetch_add(1, Ordering::Relaxed);
+            outstanding.fetch_add(1, Ordering::Relaxed);
+            client.emit_with_ack(step.event.as_str(), payload, ack_timeout, move |_, _| {
+                pending.fetch_sub(1, Ordering::Relaxed);
+                stats.acks_received.fetch_add(1, Ordering::Relaxed);
+                LoadStats::add_sample(&stats.ack_latencies, sent_at.elapsed());
+            })
+        } else {
+            client.emit(step.event.as_str(), payload)
+        };
+
+        match result {
+            Ok(()) => {
+                self.stats.messages_sent.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                log::warn!(
+                    "[LoadTest] Failed to emit {}: {}",
+                    step.event,
+                    redaction::redact_text(Some(self.config.connection_id), &e.to_string())
+                );
+                self.stats.emit_errors.fetch_add(1, Ordering::Relaxed);
+                if step.ack {
+                    outstanding.fetch_sub(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
+
+/// A virtual client as scheduled by its worker
+enum VirtualClient {
+    /// Connects at the given time
+    Waiting(Instant),
+    Running(RunningClient),
+    Done,
+}
+
+impl VirtualClient {
+    /// When the client next needs its worker
+    fn due(&self) -> Option<Instant> {
+        match self {
+            Self::Waiting(start_at) => Some(*start_at),
+            Self::Running(running) => Some(running.next_at),
+            Self::Done => None,
+        }
+    }
+}
+
+struct RunningClient {
+    client: Client,
+    /// Set before closing, so the close is not counted as a disconnect
+    finished: Arc<AtomicBool>,
+    outstanding: Arc<AtomicU64>,
+    ack_timeout: Duration,
+    deadline: Instant,
+    /// Set once the scenario is over and only acks are awaited
+    ack_deadline: Option<Instant>,
+    step: usize,
+    next_at: Instant,
+}
+
+impl RunningClient {
+    fn close(self) {
+        self.finished.store(true, Ordering::Relaxed);
+        let _ = self.client.disconnect();
+    }
+}
+
+// ============================================================================
+// Tauri State & Commands
+// ============================================================================
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadTestStatus {
+    pub status: String,
+    pub report: Option<LoadTestReport>,
+}
+
+#[derive(Default)]
+pub struct LoadTestState {
+    current: Mutex<Option<Arc<LoadTest>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl LoadTestState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Start a load test in the background; poll `get_load_test_status` for results
+#[tauri::command]
+pub fn start_load_test(
+    config: LoadTestConfig,
+    load_test_state: tauri::State<'_, LoadTestState>,
+) -> Result<(), String> {
+    if load_test_state.running.swap(true, Ordering::SeqCst) {
+        return Err("Load test already running".to_string());
+    }
+
+    let test = match LoadTest::new(config) {
+        Ok(test) => Arc::new(test),
+        Err(e) => {
+            load_test_state.running.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+    if let Ok(mut current) = load_test_state.current.lock() {
+        *current = Some(test.clone());
+    }
+
+    let running = load_test_state.running.clone();
+    thread::spawn(move || {
+        test.run();
+        running.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_load_test(load_test_state: tauri::State<'_, LoadTestState>) -> Result<(), String> {
+    if let Ok(current) = load_test_state.current.lock()
+        && let Some(test) = current.as_ref()
+    {
+        test.cancel();
+    }
+    Ok(())
+}
+
+/// Live report of the running load test, or the final report of the last one
+#[tauri::command]
+pub fn get_load_test_status(
+    load_test_state: tauri::State<'_, LoadTestState>,
+) -> Result<LoadTestStatus, String> {
+    let report = load_test_state
+        .current
+        .lock()
+        .map_err(|_| "Failed to lock load test state".to_string())?
+        .as_ref()
+        .map(|test| test.report());
+
+    let status = if load_test_state.running.load(Ordering::SeqCst) {
+        "running"
+    } else if report.is_some() {
+        "finished"
+    } else {
+        "idle"
+    };
+
+    Ok(LoadTestStatus {
+        status: status.to_string(),
+        report,
+    })
+}
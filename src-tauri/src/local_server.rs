@@ -0,0 +1,202 @@
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use socketioxide::SocketIo;
+use socketioxide::layer::SocketIoLayer;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// How long `stop` waits for the server to shut down before aborting it
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// Socket.IO server on a local port, shared by the mock server and the proxy
+pub struct LocalServer {
+    label: &'static str,
+    addr: SocketAddr,
+    io: SocketIo,
+    shutdown_tx: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
+impl LocalServer {
+    /// Serve `layer` on `port` (0 picks a free port) until `shutdown_tx` is sent `true`.
+    ///
+    /// `label` prefixes log lines, e.g. `MockServer`.
+    pub async fn start(
+        label: &'static str,
+        port: u16,
+        io: SocketIo,
+        layer: SocketIoLayer,
+        shutdown_tx: watch::Sender<bool>,
+    ) -> Result<Self, String> {
+        let app = axum::Router::new().layer(layer);
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| e.to_string())?;
+        let addr = listener.local_addr().map_err(|e| e.to_string())?;
+
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async move {
+                    let _ = shutdown_rx.changed().await;
+                })
+                .await
+                .ok();
+        });
+
+        log::info!("[{}] Listening on {}", label, addr);
+        Ok(Self {
+            label,
+            addr,
+            io,
+            shutdown_tx,
+            handle,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Signal shutdown, disconnect every socket, then wait for the server to finish
+    pub async fn stop(self) {
+        let _ = self.shutdown_tx.send(true);
+        self.io.close().await;
+        let mut handle = self.handle;
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut handle)
+            .await
+            .is_err()
+        {
+            log::warn!("[{}] Shutdown timed out; aborting", self.label);
+            handle.abort();
+        }
+        log::info!("[{}] Stopped", self.label);
+    }
+}
+
+// ============================================================================
+// Tauri State
+// ============================================================================
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub status: String,
+    pub port: Option<u16>,
+    pub connection_id: Option<i64>,
+    pub message: Option<String>,
+}
+
+impl ServerStatus {
+    fn new(status: &str) -> Self {
+        Self {
+            status: status.to_string(),
+            port: None,
+            connection_id: None,
+            message: None,
+        }
+    }
+}
+
+/// A server kept in a [`ServerState`]
+pub trait ManagedServer: Send + Sized {
+    /// Names the server in errors, e.g. "Proxy is not running"
+    const NAME: &'static str;
+
+    fn local_addr(&self) -> SocketAddr;
+
+    fn stop(self) -> impl Future<Output = ()> + Send;
+}
+
+/// At most one running server and its status, for the Tauri commands
+pub struct ServerState<S> {
+    status: Mutex<ServerStatus>,
+    /// Async so one lock covers the running check, the start and the insert
+    server: tokio::sync::Mutex<Option<S>>,
+}
+
+impl<S: ManagedServer> ServerState<S> {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(ServerStatus::new("stopped")),
+            server: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    fn update_status(&self, status: ServerStatus) {
+        if let Ok(mut guard) = self.status.lock() {
+            *guard = status;
+        }
+    }
+
+    pub fn status(&self) -> ServerStatus {
+        self.status
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_else(|_| ServerStatus::new("unknown"))
+    }
+
+    /// Run `start` unless a server is already running, recording under `connection_id`
+    pub async fn start(
+        &self,
+        connection_id: i64,
+        start: impl Future<Output = Result<S, String>>,
+    ) -> Result<ServerStatus, String> {
+        let mut slot = self.server.lock().await;
+        if slot.is_some() {
+            return Err(format!("{} already running", S::NAME));
+        }
+
+        let server = match start.await {
+            Ok(server) => server,
+            Err(e) => {
+                self.update_status(ServerStatus {
+                    message: Some(e.clone()),
+                    ..ServerStatus::new("error")
+                });
+                return Err(e);
+            }
+        };
+        let port = server.local_addr().port();
+        *slot = Some(server);
+
+        self.update_status(ServerStatus {
+            port: Some(port),
+            connection_id: Some(connection_id),
+            ..ServerStatus::new("running")
+        });
+        Ok(self.status())
+    }
+
+    pub async fn stop(&self) -> ServerStatus {
+        let server = self.server.lock().await.take();
+        if let Some(server) = server {
+            server.stop().await;
+        }
+
+        self.update_status(ServerStatus::new("stopped"));
+        self.status()
+    }
+
+    pub async fn with_server<T>(&self, f: impl FnOnce(&S) -> T) -> Result<T, String> {
+        let guard = self.server.lock().await;
+        guard
+            .as_ref()
+            .map(f)
+            .ok_or_else(|| format!("{} is not running", S::NAME))
+    }
+}
+
+impl<S: ManagedServer> Default for ServerState<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
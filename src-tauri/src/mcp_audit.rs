@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::db::{self, McpAuditRecord};
+use crate::redaction;
+
+const DEFAULT_LIMIT: i64 = 100;
+
+/// `clientInfo` sent by an MCP client in `initialize`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ClientInfo {
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Record one `tools/call`; `outcome` is the redacted result text or the error.
+///
+/// Failures are logged rather than returned so auditing never fails a call.
+pub fn record(
+    client: Option<&ClientInfo>,
+    tool: &str,
+    arguments: &Value,
+    connection_id: Option<i64>,
+    outcome: Result<&str, &str>,
+    started_at: DateTime<Utc>,
+    duration: Duration,
+) {
+    let mut arguments = arguments.clone();
+    redaction::redact_value(connection_id, &mut arguments);
+    let (result, error) = match outcome {
+        Ok(result) => (Some(result.to_string()), None),
+        Err(error) => (None, Some(redaction::redact_text(connection_id, error))),
+    };
+
+    let record = McpAuditRecord {
+        id: 0,
+        timestamp: db::format_timestamp(started_at),
+        client_name: client.and_then(|c| c.name.clone()),
+        client_version: client.and_then(|c| c.version.clone()),
+        tool: tool.to_string(),
+        arguments: arguments.to_string(),
+        result,
+        error,
+        duration_ms: duration.as_millis() as i64,
+    };
+    if let Err(e) = db::add_mcp_audit(&record) {
+        log::warn!("[MCP] Failed to record audit entry for {}: {}", tool, e);
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Audit entries newest first; pass the last id as `before_id` for the next page
+#[tauri::command]
+pub fn list_mcp_audit_log(
+    tool: Option<String>,
+    before_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<McpAuditRecord>, String> {
+    db::list_mcp_audit(tool.as_deref(), before_id, limit.unwrap_or(DEFAULT_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clear_mcp_audit_log() -> Result<(), String> {
+    db::clear_mcp_audit().map(|_| ()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::db;
+use crate::event_sink::{McpConfirmationPayload, SocketNotification};
+use crate::redaction;
+use crate::socket_client::SocketManager;
+
+const SETTINGS_KEY: &str = "mcp_policy";
+/// Unanswered confirmations are denied after this long
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Tools that change connections or send to the server
+const WRITE_TOOLS: &[&str] = &[
+    "connect",
+    "disconnect",
+    "send_message",
+    "add_event_listener",
+    "remove_event_listener",
+];
+
+pub fn is_write_tool(name: &str) -> bool {
+    WRITE_TOOLS.contains(&name)
+}
+
+// ============================================================================
+// Policy
+// ============================================================================
+
+/// Event names `send_message` may emit on one connection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventRules {
+    /// Only these events may be sent; any event when empty
+    pub allow: Vec<String>,
+    /// Never sent, even when allowed
+    pub deny: Vec<String>,
+}
+
+impl EventRules {
+    fn permits(&self, event_name: &str) -> bool {
+        let listed = |names: &[String]| names.iter().any(|name| name == event_name);
+        !listed(&self.deny) && (self.allow.is_empty() || listed(&self.allow))
+    }
+}
+
+/// What AI assistants may do through the MCP server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpPolicy {
+    /// Only read tools may run
+    pub read_only: bool,
+    /// Connections `connect` may open; every connection when unset
+    pub allowed_connections: Option<Vec<i64>>,
+    /// Per-connection rules for `send_message`
+    pub events: HashMap<i64, EventRules>,
+    /// Ask in the desktop app before a write tool runs
+    pub confirm_writes: bool,
+}
+
+impl McpPolicy {
+    /// Check a tool call against the policy before it runs
+    pub fn check(
+        &self,
+        tool: &str,
+        connection_id: Option<i64>,
+        event_name: Option<&str>,
+    ) -> Result<(), String> {
+        if self.read_only && is_write_tool(tool) {
+            return Err(format!(
+                "{} is not allowed: the MCP server is in read-only mode",
+                tool
+            ));
+        }
+        if tool == "connect"
+            && let (Some(allowed), Some(connection_id)) = (&self.allowed_connections, connection_id)
+            && !allowed.contains(&connection_id)
+        {
+            return Err(format!(
+                "Connection {} is not in the MCP connection allowlist",
+                connection_id
+            ));
+        }
+        if tool == "send_message"
+            && let (Some(connection_id), Some(event_name)) = (connection_id, event_name)
+            && let Some(rules) = self.events.get(&connection_id)
+            && !rules.permits(event_name)
+        {
+            return Err(format!(
+                "Event {} may not be sent on connection {} by the MCP policy",
+                event_name, connection_id
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub fn load_policy() -> Result<McpPolicy, String> {
+    match db::get_app_state(SETTINGS_KEY).map_err(|e| e.to_string())? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(McpPolicy::default()),
+    }
+}
+
+pub fn save_policy(policy: &McpPolicy) -> Result<(), String> {
+    let json = serde_json::to_string(policy).map_err(|e| e.to_string())?;
+    db::set_app_state(SETTINGS_KEY, &json).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Confirmation
+// ============================================================================
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+static PENDING: LazyLock<Mutex<HashMap<u64, oneshot::Sender<bool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Ask the desktop app whether a tool call may run and wait for the answer.
+///
+/// Fails at once when nothing can answer, e.g. under `sioc mcp`.
+pub async fn confirm(
+    socket: &SocketManager,
+    tool: &str,
+    arguments: &Value,
+    connection_id: Option<i64>,
+) -> Result<(), String> {
+    if !socket.handles_confirmations() {
+        return Err(format!(
+            "{} needs confirmation in the desktop app, which is not attached; \
+             turn off Confirm writes in the MCP policy to run write tools here",
+            tool
+        ));
+    }
+
+    let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = oneshot::channel();
+    PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(request_id, tx);
+
+    let mut arguments = arguments.clone();
+    redaction::redact_value(connection_id, &mut arguments);
+    socket.notify(SocketNotification::McpConfirmation(
+        McpConfirmationPayload {
+            request_id,
+            tool: tool.to_string(),
+            arguments: arguments.to_string(),
+            connection_id,
+        },
+    ));
+
+    let answer = tokio::time::timeout(CONFIRMATION_TIMEOUT, rx).await;
+    PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&request_id);
+    match answer {
+        Ok(Ok(true)) => Ok(()),
+        Ok(_) => Err(format!("{} was denied by the user", tool)),
+        Err(_) => Err(format!(
+            "{} was not confirmed in the desktop app in time",
+            tool
+        )),
+    }
+}
+
+/// Answer a pending confirmation; returns false if it is unknown or expired
+pub fn resolve_confirmation(request_id: u64, approved: bool) -> Result<bool, String> {
+    let sender = PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&request_id);
+    Ok(sender.is_some_and(|tx| tx.send(approved).is_ok()))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_mcp_policy() -> Result<McpPolicy, String> {
+    load_policy()
+}
+
+#[tauri::command]
+pub fn save_mcp_policy(policy: McpPolicy) -> Result<(), String> {
+    save_policy(&policy)
+}
+
+#[tauri::command]
+pub fn resolve_mcp_confirmation(request_id: u64, approved: bool) -> Result<(), String> {
+    if resolve_confirmation(request_id, approved)? {
+        Ok(())
+    } else {
+        Err("Confirmation request not found".to_string())
+    }
+}
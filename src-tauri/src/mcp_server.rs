@@ -1,29 +1,54 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::net::SocketAddr;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, Weak};
+use std::time::{Duration, Instant};
 
 use axum::{
     Json, Router,
-    extract::State,
-    http::{Method, StatusCode, header},
+    body::Bytes,
+    extract::{Query, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    middleware::{self, Next},
     response::{
-        IntoResponse,
+        IntoResponse, Response,
         sse::{Event, KeepAlive, Sse},
     },
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{broadcast, watch};
 use tokio::task::JoinHandle;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::BroadcastStream;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
 use crate::db;
+use crate::emit_log::{self, EventSearchInput, HistoryPageInput};
+use crate::mcp_audit::{self, ClientInfo};
+use crate::mcp_policy;
+use crate::redaction;
+use crate::secrets;
 use crate::socket_client::SocketManager;
 
-// MCP Protocol Version
-const PROTOCOL_VERSION: &str = "2024-11-05";
+/// Protocol versions this server speaks, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const SESSION_ID_HEADER: HeaderName = HeaderName::from_static("mcp-session-id");
+const PROTOCOL_VERSION_HEADER: HeaderName = HeaderName::from_static("mcp-protocol-version");
+const SESSION_ID_BYTES: usize = 16;
+/// Messages buffered for a session's SSE stream
+const SESSION_CHANNEL_CAPACITY: usize = 100;
+/// Sessions without an open stream end after this long without a request
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Sessions one server keeps at once; `initialize` beyond this gets 503
+pub const MAX_SESSIONS: usize = 64;
+
+const AUTH_TOKEN_KEY: &str = "mcp_auth_token";
+const AUTH_TOKEN_BYTES: usize = 32;
 
 // ============================================================================
 // JSON-RPC Types
@@ -94,10 +119,355 @@ struct ToolInfo {
 // App State
 // ============================================================================
 
+/// One MCP client, from `initialize` (Streamable HTTP) or `GET /sse` (legacy SSE) until it ends
+struct Session {
+    client: Option<ClientInfo>,
+    /// Messages for the session's SSE streams
+    tx: broadcast::Sender<String>,
+    /// Resource URIs the client asked to be notified about
+    subscriptions: HashSet<String>,
+    last_seen: Instant,
+}
+
+impl Session {
+    /// Idle too long and nobody is listening on its stream
+    fn is_expired(&self) -> bool {
+        self.tx.receiver_count() == 0 && self.last_seen.elapsed() >= SESSION_IDLE_TIMEOUT
+    }
+}
+
+type Sessions = Arc<Mutex<HashMap<String, Session>>>;
+
 #[derive(Clone)]
 struct McpAppState {
     socket: SocketManager,
-    sse_tx: broadcast::Sender<String>,
+    auth_token: Arc<str>,
+    sessions: Sessions,
+    /// Client of the latest `initialize` sent without a session, recorded in the audit log
+    client: Arc<RwLock<Option<ClientInfo>>>,
+}
+
+impl McpAppState {
+    fn new(socket: SocketManager, auth_token: &str) -> Self {
+        let sessions = Arc::new(Mutex::new(HashMap::new()));
+        spawn_event_forwarder(&socket, Arc::downgrade(&sessions));
+        spawn_session_sweeper(Arc::downgrade(&sessions));
+        Self {
+            socket,
+            auth_token: auth_token.into(),
+            sessions,
+            client: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    fn sessions(&self) -> Result<MutexGuard<'_, HashMap<String, Session>>, String> {
+        self.sessions.lock().map_err(|e| e.to_string())
+    }
+
+    fn create_session(
+        &self,
+        session_id: &str,
+        client: Option<ClientInfo>,
+    ) -> Result<broadcast::Receiver<String>, TransportError> {
+        let mut sessions = self.sessions().map_err(TransportError::internal)?;
+        sessions.retain(|_, session| !session.is_expired());
+        if sessions.len() >= MAX_SESSIONS {
+            return Err(TransportError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Too many MCP sessions; end unused sessions with DELETE",
+            ));
+        }
+
+        let (tx, rx) = broadcast::channel(SESSION_CHANNEL_CAPACITY);
+        let session = Session {
+            client,
+            tx,
+            subscriptions: HashSet::new(),
+            last_seen: Instant::now(),
+        };
+        sessions.insert(session_id.to_string(), session);
+        Ok(rx)
+    }
+
+    fn subscribe(&self, session_id: &str) -> Result<Option<broadcast::Receiver<String>>, String> {
+        let sessions = self.sessions()?;
+        Ok(sessions
+            .get(session_id)
+            .map(|session| session.tx.subscribe()))
+    }
+
+    fn client(&self, session_id: Option<&str>) -> Result<Option<ClientInfo>, String> {
+        match session_id {
+            Some(session_id) => {
+                let sessions = self.sessions()?;
+                Ok(sessions.get(session_id).and_then(|s| s.client.clone()))
+            }
+            None => Ok(self.client.read().map_err(|e| e.to_string())?.clone()),
+        }
+    }
+
+    fn set_client(
+        &self,
+        session_id: Option<&str>,
+        client: Option<ClientInfo>,
+    ) -> Result<(), String> {
+        match session_id {
+            Some(session_id) => {
+                if let Some(session) = self.sessions()?.get_mut(session_id) {
+                    session.client = client;
+                }
+            }
+            None => *self.client.write().map_err(|e| e.to_string())? = client,
+        }
+        Ok(())
+    }
+}
+
+fn new_session_id() -> String {
+    secrets::generate_token(SESSION_ID_BYTES)
+}
+
+/// Subscribe or unsubscribe a session; sessionless clients have nowhere to be notified
+fn set_subscription(
+    state: &McpAppState,
+    session_id: Option<&str>,
+    params: &Value,
+    subscribe: bool,
+) -> Result<(), String> {
+    let uri = params
+        .get("uri")
+        .and_then(|v| v.as_str())
+        .ok_or("uri is required")?;
+    if !matches!(ResourceUri::parse(uri), Some(ResourceUri::History(_))) {
+        return Err(format!("Resource does not support subscriptions: {}", uri));
+    }
+    let session_id = session_id.ok_or("Subscriptions need a session")?;
+
+    let mut sessions = state.sessions()?;
+    let session = sessions.get_mut(session_id).ok_or("Session not found")?;
+    if subscribe {
+        session.subscriptions.insert(uri.to_string());
+    } else {
+        session.subscriptions.remove(uri);
+    }
+    Ok(())
+}
+
+/// Notify sessions subscribed to a connection's history of each incoming event.
+///
+/// Stops at the first event after the server's sessions are gone.
+fn spawn_event_forwarder(socket: &SocketManager, sessions: Weak<Mutex<HashMap<String, Session>>>) {
+    let mut rx = socket.subscribe_incoming();
+    tokio::spawn(async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let Some(sessions) = sessions.upgrade() else {
+                break;
+            };
+            let uri = ResourceUri::History(event.connection_id).to_string();
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": { "uri": uri }
+            })
+            .to_string();
+            let Ok(sessions) = sessions.lock() else {
+                log::warn!("[MCP] Session map is poisoned; stopping event notifications");
+                break;
+            };
+            for session in sessions.values() {
+                if session.subscriptions.contains(&uri) {
+                    let _ = session.tx.send(notification.clone());
+                }
+            }
+        }
+    });
+}
+
+/// End sessions whose clients went away without sending DELETE.
+///
+/// Stops once the server's sessions are gone.
+fn spawn_session_sweeper(sessions: Weak<Mutex<HashMap<String, Session>>>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SESSION_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(sessions) = sessions.upgrade() else {
+                break;
+            };
+            if let Ok(mut sessions) = sessions.lock() {
+                sessions.retain(|_, session| !session.is_expired());
+            }
+        }
+    });
+}
+
+/// Ends a legacy SSE session when its stream is dropped
+struct SessionGuard {
+    sessions: Sessions,
+    session_id: String,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.remove(&self.session_id);
+        }
+    }
+}
+
+// ============================================================================
+// Resources
+// ============================================================================
+
+const CONNECTIONS_URI: &str = "socketio://connections/";
+const RESOURCE_MIME_TYPE: &str = "application/json";
+/// Newest events included when the history resource is read
+const HISTORY_RESOURCE_LIMIT: i64 = 100;
+/// JSON-RPC error code for unknown resources
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// (URI suffix after the connection id, name, description)
+const RESOURCE_KINDS: &[(&str, &str, &str)] = &[
+    (
+        "",
+        "Connection",
+        "Saved connection settings (secrets masked) and its event listeners",
+    ),
+    (
+        "/history",
+        "Event history",
+        "Newest recorded events of the connection; subscribe to be notified of incoming ones",
+    ),
+    (
+        "/pinned",
+        "Pinned messages",
+        "Saved messages of the connection in display order",
+    ),
+];
+
+/// A resource under `socketio://connections/{id}`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ResourceUri {
+    Connection(i64),
+    History(i64),
+    Pinned(i64),
+}
+
+impl ResourceUri {
+    fn parse(uri: &str) -> Option<Self> {
+        let rest = uri.strip_prefix(CONNECTIONS_URI)?;
+        let (id, kind) = rest.split_once('/').unwrap_or((rest, ""));
+        let id = id.parse().ok()?;
+        match kind {
+            "" => Some(Self::Connection(id)),
+            "history" => Some(Self::History(id)),
+            "pinned" => Some(Self::Pinned(id)),
+            _ => None,
+        }
+    }
+
+    fn connection_id(self) -> i64 {
+        match self {
+            Self::Connection(id) | Self::History(id) | Self::Pinned(id) => id,
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suffix = match self {
+            Self::Connection(_) => "",
+            Self::History(_) => "/history",
+            Self::Pinned(_) => "/pinned",
+        };
+        write!(f, "{}{}{}", CONNECTIONS_URI, self.connection_id(), suffix)
+    }
+}
+
+/// Every resource of every saved connection
+fn list_resources() -> Result<Value, String> {
+    let connections = db::list_connections().map_err(|e| e.to_string())?;
+    let resources: Vec<Value> = connections
+        .iter()
+        .flat_map(|connection| {
+            RESOURCE_KINDS
+                .iter()
+                .map(move |(suffix, name, description)| {
+                    json!({
+                        "uri": format!("{}{}{}", CONNECTIONS_URI, connection.id, suffix),
+                        "name": format!("{}: {}", connection.name, name),
+                        "description": description,
+                        "mimeType": RESOURCE_MIME_TYPE
+                    })
+                })
+        })
+        .collect();
+    Ok(json!({ "resources": resources }))
+}
+
+fn list_resource_templates() -> Value {
+    let templates: Vec<Value> = RESOURCE_KINDS
+        .iter()
+        .map(|(suffix, name, description)| {
+            json!({
+                "uriTemplate": format!("{}{{id}}{}", CONNECTIONS_URI, suffix),
+                "name": name,
+                "description": description,
+                "mimeType": RESOURCE_MIME_TYPE
+            })
+        })
+        .collect();
+    json!({ "resourceTemplates": templates })
+}
+
+/// Contents of a resource; `None` when the URI or its connection is unknown
+fn read_resource(uri: &str) -> Result<Option<Value>, String> {
+    let Some(resource) = ResourceUri::parse(uri) else {
+        return Ok(None);
+    };
+    let connection_id = resource.connection_id();
+    let Some(connection) = db::get_connection_by_id(connection_id).map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+
+    let mut contents = match resource {
+        ResourceUri::Connection(_) => {
+            let events = db::list_connection_events(connection_id).map_err(|e| e.to_string())?;
+            json!({
+                "connection": secrets::mask_connection(connection),
+                "events": events
+            })
+        }
+        ResourceUri::History(_) => {
+            let page = emit_log::query_event_history(HistoryPageInput {
+                connection_id,
+                limit: Some(HISTORY_RESOURCE_LIMIT),
+                ..Default::default()
+            })?;
+            json!(page)
+        }
+        ResourceUri::Pinned(_) => {
+            let pinned = db::list_pinned_messages(connection_id).map_err(|e| e.to_string())?;
+            json!({ "pinned": pinned })
+        }
+    };
+    // Same rule as tool results: payloads may carry credentials
+    redaction::redact_value(Some(connection_id), &mut contents);
+
+    let text = serde_json::to_string_pretty(&contents).map_err(|e| e.to_string())?;
+    Ok(Some(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": RESOURCE_MIME_TYPE,
+            "text": text
+        }]
+    })))
 }
 
 // ============================================================================
@@ -197,6 +567,35 @@ fn get_tools() -> Vec<ToolInfo> {
                 "required": []
             }),
         },
+        ToolInfo {
+            name: "list_event_history".to_string(),
+            description: "Page through persisted event history, newest first. Pass older_cursor as before_id for the next page".to_string(),
+            input_schema: history_query_schema(
+                "Optional connection ID. Uses active connection when omitted.",
+                json!({}),
+            ),
+        },
+        ToolInfo {
+            name: "list_emit_logs".to_string(),
+            description: "Page through logged outgoing messages, newest first. Pass older_cursor as before_id for the next page".to_string(),
+            input_schema: history_query_schema(
+                "Optional connection ID. Uses active connection when omitted.",
+                json!({}),
+            ),
+        },
+        ToolInfo {
+            name: "search_event_history".to_string(),
+            description: "Full-text search over persisted event history (event names and payloads), newest first".to_string(),
+            input_schema: history_query_schema(
+                "Optional connection ID. Searches all connections when omitted.",
+                json!({
+                    "query": {
+                        "type": "string",
+                        "description": "Words that must all appear in the event name or payload; append * for a prefix match"
+                    }
+                }),
+            ),
+        },
         ToolInfo {
             name: "list_event_listeners".to_string(),
             description: "List all current event listeners".to_string(),
@@ -250,11 +649,75 @@ fn get_tools() -> Vec<ToolInfo> {
     ]
 }
 
+/// Input schema shared by the history tools: filters, cursors and limit plus `extra` properties
+fn history_query_schema(connection_description: &str, extra: Value) -> Value {
+    let mut properties = json!({
+        "connection_id": {
+            "type": "integer",
+            "description": connection_description
+        },
+        "event_name": {
+            "type": "string",
+            "description": "Only return events with this exact name"
+        },
+        "direction": {
+            "type": "string",
+            "enum": ["in", "out"],
+            "description": "Only return incoming or outgoing events"
+        },
+        "since": {
+            "type": "string",
+            "description": "RFC 3339 timestamp; only events at or after it"
+        },
+        "until": {
+            "type": "string",
+            "description": "RFC 3339 timestamp; only events at or before it"
+        },
+        "before_id": {
+            "type": "integer",
+            "description": "Only events older than this id (next page)"
+        },
+        "after_id": {
+            "type": "integer",
+            "description": "Only events newer than this id (previous page)"
+        },
+        "limit": {
+            "type": "integer",
+            "description": "Maximum number of events to return (default: 50, max: 1000)"
+        }
+    });
+    if let (Some(properties), Value::Object(extra)) = (properties.as_object_mut(), extra) {
+        properties.extend(extra);
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": []
+    })
+}
+
 fn resolve_connection_id(args: &Value, socket: &SocketManager) -> Result<i64, String> {
     args.get("connection_id")
         .and_then(|v| v.as_i64())
         .or_else(|| socket.get_current_connection_id())
-        .ok_or_else(|| "connection_id is required when no active connection is selected".to_string())
+        .ok_or_else(|| {
+            "connection_id is required when no active connection is selected".to_string()
+        })
+}
+
+fn history_page_input(args: &Value, socket: &SocketManager) -> Result<HistoryPageInput, String> {
+    let text = |key: &str| args.get(key).and_then(|v| v.as_str()).map(String::from);
+    let id = |key: &str| args.get(key).and_then(|v| v.as_i64());
+    Ok(HistoryPageInput {
+        connection_id: resolve_connection_id(args, socket)?,
+        event_name: text("event_name"),
+        direction: text("direction"),
+        since: text("since"),
+        until: text("until"),
+        before_id: id("before_id"),
+        after_id: id("after_id"),
+        limit: Some(id("limit").unwrap_or(50)),
+    })
 }
 
 // ============================================================================
@@ -262,17 +725,25 @@ fn resolve_connection_id(args: &Value, socket: &SocketManager) -> Result<i64, St
 // ============================================================================
 
 async fn execute_tool(socket: &SocketManager, name: &str, args: &Value) -> Result<Value, String> {
+    let policy = mcp_policy::load_policy()?;
+    let connection_id = resolve_connection_id(args, socket).ok();
+    let event_name = args.get("event_name").and_then(|v| v.as_str());
+    policy.check(name, connection_id, event_name)?;
+    if policy.confirm_writes && mcp_policy::is_write_tool(name) {
+        mcp_policy::confirm(socket, name, args, connection_id).await?;
+    }
+
     match name {
         "list_connections" => {
             let rows = db::list_connections().map_err(|e| e.to_string())?;
             let connections: Vec<Value> = rows
                 .into_iter()
-                .map(|(id, name, url, namespace, _, _, _, _, _, _)| {
+                .map(|c| {
                     json!({
-                        "id": id,
-                        "name": name,
-                        "url": url,
-                        "namespace": namespace
+                        "id": c.id,
+                        "name": c.name,
+                        "url": c.url,
+                        "namespace": c.namespace
                     })
                 })
                 .collect();
@@ -338,7 +809,8 @@ async fn execute_tool(socket: &SocketManager, name: &str, args: &Value) -> Resul
                 .emit_message_async(connection_id, event_name.to_string(), payload.to_string())
                 .await?;
 
-            if let Err(e) = db::add_emit_log(connection_id, event_name, payload) {
+            let (payload, redacted) = redaction::redact_payload(connection_id, payload);
+            if let Err(e) = db::add_emit_log(connection_id, event_name, &payload, redacted) {
                 log::warn!("Failed to save emit log: {}", e);
             }
 
@@ -363,16 +835,45 @@ async fn execute_tool(socket: &SocketManager, name: &str, args: &Value) -> Resul
             Ok(json!({ "events": events, "connection_id": connection_id }))
         }
 
+        "list_event_history" => {
+            let page = emit_log::query_event_history(history_page_input(args, socket)?)?;
+            Ok(json!(page))
+        }
+
+        "list_emit_logs" => {
+            let page = emit_log::query_emit_logs(history_page_input(args, socket)?)?;
+            Ok(json!(page))
+        }
+
+        "search_event_history" => {
+            let text = |key: &str| args.get(key).and_then(|v| v.as_str()).map(String::from);
+            let id = |key: &str| args.get(key).and_then(|v| v.as_i64());
+            let input = EventSearchInput {
+                query: text("query").unwrap_or_default(),
+                connection_id: id("connection_id"),
+                event_name: text("event_name"),
+                direction: text("direction"),
+                since: text("since"),
+                until: text("until"),
+                before_id: id("before_id"),
+                after_id: id("after_id"),
+                limit: Some(id("limit").unwrap_or(50)),
+            };
+            let page = emit_log::search_events(input)?;
+            Ok(json!(page))
+        }
+
         "list_event_listeners" => {
             let connection_id = resolve_connection_id(args, socket)?;
             let in_memory = socket.list_listeners(connection_id);
 
-            let persisted: std::collections::HashSet<String> = db::list_connection_events(connection_id)
-                .map_err(|e| e.to_string())?
-                .into_iter()
-                .filter(|(_, _, is_listening)| *is_listening)
-                .map(|(_, name, _)| name)
-                .collect();
+            let persisted: std::collections::HashSet<String> =
+                db::list_connection_events(connection_id)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .filter(|event| event.is_listening)
+                    .map(|event| event.event_name)
+                    .collect();
 
             let listeners: Vec<Value> = in_memory
                 .into_iter()
@@ -402,15 +903,15 @@ async fn execute_tool(socket: &SocketManager, name: &str, args: &Value) -> Resul
             socket.add_listener(connection_id, event_name)?;
 
             let existing = db::list_connection_events(connection_id).map_err(|e| e.to_string())?;
-            let already_exists = existing.iter().any(|(_, name, _)| name == event_name);
-
-            if !already_exists {
-                db::add_connection_event(connection_id, event_name).map_err(|e| e.to_string())?;
-            } else if let Some((id, _, is_listening)) =
-                existing.iter().find(|(_, name, _)| name == event_name)
-                && !*is_listening
-            {
-                db::toggle_connection_event(*id, true).map_err(|e| e.to_string())?;
+            match existing.iter().find(|event| event.event_name == event_name) {
+                None => {
+                    db::add_connection_event(connection_id, event_name)
+                        .map_err(|e| e.to_string())?;
+                }
+                Some(event) if !event.is_listening => {
+                    db::toggle_connection_event(event.id, true).map_err(|e| e.to_string())?;
+                }
+                Some(_) => {}
             }
 
             Ok(json!({
@@ -431,11 +932,10 @@ async fn execute_tool(socket: &SocketManager, name: &str, args: &Value) -> Resul
             socket.remove_listener(connection_id, event_name);
 
             let existing = db::list_connection_events(connection_id).map_err(|e| e.to_string())?;
-            if let Some((id, _, is_listening)) =
-                existing.iter().find(|(_, name, _)| name == event_name)
-                && *is_listening
+            if let Some(event) = existing.iter().find(|event| event.event_name == event_name)
+                && event.is_listening
             {
-                db::toggle_connection_event(*id, false).map_err(|e| e.to_string())?;
+                db::toggle_connection_event(event.id, false).map_err(|e| e.to_string())?;
             }
 
             Ok(json!({
@@ -453,49 +953,339 @@ async fn execute_tool(socket: &SocketManager, name: &str, args: &Value) -> Resul
 // HTTP Handlers
 // ============================================================================
 
-async fn handle_sse(
+/// SSE stream of a session's messages, each as a `message` event
+fn session_events(
+    rx: broadcast::Receiver<String>,
+) -> impl tokio_stream::Stream<Item = Result<Event, Infallible>> {
+    BroadcastStream::new(rx)
+        .filter_map(|result| result.ok())
+        .map(|data| Ok(Event::default().event("message").data(data)))
+}
+
+/// The session named by the `Mcp-Session-Id` header; 400 without it, 404 once it has ended
+fn session_id(state: &McpAppState, headers: &HeaderMap) -> Result<String, TransportError> {
+    let Some(session_id) = headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Err(TransportError::new(
+            StatusCode::BAD_REQUEST,
+            "Missing Mcp-Session-Id header",
+        ));
+    };
+    let mut sessions = state.sessions().map_err(TransportError::internal)?;
+    let Some(session) = sessions.get_mut(session_id) else {
+        return Err(TransportError::new(
+            StatusCode::NOT_FOUND,
+            "Session not found",
+        ));
+    };
+    session.last_seen = Instant::now();
+    Ok(session_id.to_string())
+}
+
+/// A request the transport rejects before any message is handled
+struct TransportError {
+    status: StatusCode,
+    code: i32,
+    message: String,
+}
+
+impl TransportError {
+    fn new(status: StatusCode, message: &str) -> Self {
+        Self {
+            status,
+            code: -32000,
+            message: message.to_string(),
+        }
+    }
+
+    fn internal(message: String) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: -32603,
+            message,
+        }
+    }
+}
+
+impl IntoResponse for TransportError {
+    fn into_response(self) -> Response {
+        let body = JsonRpcResponse::error(Value::Null, self.code, &self.message);
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// A POST body holds one JSON-RPC message or a batch of them
+fn parse_messages(body: &[u8]) -> Result<(Vec<Value>, bool), TransportError> {
+    let invalid = |code, message| TransportError {
+        status: StatusCode::BAD_REQUEST,
+        code,
+        message,
+    };
+    match serde_json::from_slice(body) {
+        Ok(Value::Array(messages)) if messages.is_empty() => {
+            Err(invalid(-32600, "Empty batch".to_string()))
+        }
+        Ok(Value::Array(messages)) => Ok((messages, true)),
+        Ok(message) => Ok((vec![message], false)),
+        Err(e) => Err(invalid(-32700, format!("Parse error: {}", e))),
+    }
+}
+
+/// Handle every message in order; only requests (messages with an id) get a response
+async fn process_messages(
+    state: &McpAppState,
+    messages: Vec<Value>,
+    session_id: Option<&str>,
+) -> Vec<JsonRpcResponse> {
+    let mut responses = Vec::new();
+    for message in messages {
+        // Responses from the client (to requests this server never sends) are ignored
+        if message.get("method").is_none() && message.get("id").is_some() {
+            continue;
+        }
+        match serde_json::from_value::<JsonRpcRequest>(message) {
+            Ok(request) => {
+                let is_request = request.id.is_some();
+                let response = process_request(state, request, session_id).await;
+                if is_request {
+                    responses.push(response);
+                }
+            }
+            Err(e) => responses.push(JsonRpcResponse::error(
+                Value::Null,
+                -32600,
+                &format!("Invalid request: {}", e),
+            )),
+        }
+    }
+    responses
+}
+
+/// 202 when there is nothing to answer, else the response or batch of responses
+fn responses_body(responses: Vec<JsonRpcResponse>, batch: bool) -> Response {
+    match responses.len() {
+        0 => StatusCode::ACCEPTED.into_response(),
+        _ if batch => Json(responses).into_response(),
+        _ => Json(&responses[0]).into_response(),
+    }
+}
+
+/// Streamable HTTP: `initialize` starts a session, every later message must name it
+async fn handle_streamable_post(
     State(state): State<McpAppState>,
-) -> Sse<impl tokio_stream::Stream<Item = Result<Event, std::convert::Infallible>>> {
-    let rx = state.sse_tx.subscribe();
-    let stream = BroadcastStream::new(rx).filter_map(|result| match result {
-        Ok(data) => Some(Ok(Event::default().data(data))),
-        Err(_) => None,
-    });
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let (messages, batch) = match parse_messages(&body) {
+        Ok(messages) => messages,
+        Err(error) => return error.into_response(),
+    };
+
+    let is_initialize = messages
+        .iter()
+        .any(|message| message.get("method").and_then(|v| v.as_str()) == Some("initialize"));
+    if is_initialize {
+        if batch {
+            return TransportError::new(
+                StatusCode::BAD_REQUEST,
+                "initialize must not be part of a batch",
+            )
+            .into_response();
+        }
+        // The session only exists once initialization succeeds
+        let session_id = new_session_id();
+        let client = messages[0].get("params").and_then(client_info);
+        let responses = process_messages(&state, messages, Some(&session_id)).await;
+        if !matches!(responses.as_slice(), [response] if response.error.is_none()) {
+            return responses_body(responses, false);
+        }
+        if let Err(error) = state.create_session(&session_id, client) {
+            return error.into_response();
+        }
+        let mut response = responses_body(responses, false);
+        if let Ok(value) = HeaderValue::from_str(&session_id) {
+            response.headers_mut().insert(SESSION_ID_HEADER, value);
+        }
+        return response;
+    }
+
+    let session_id = match session_id(&state, &headers) {
+        Ok(session_id) => session_id,
+        Err(error) => return error.into_response(),
+    };
+    if let Some(version) = headers.get(PROTOCOL_VERSION_HEADER)
+        && !version
+            .to_str()
+            .is_ok_and(|version| PROTOCOL_VERSIONS.contains(&version))
+    {
+        return TransportError::new(StatusCode::BAD_REQUEST, "Unsupported MCP-Protocol-Version")
+            .into_response();
+    }
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    let responses = process_messages(&state, messages, Some(&session_id)).await;
+    responses_body(responses, batch)
 }
 
+/// Streamable HTTP: SSE stream for messages the server sends to a session
+async fn handle_streamable_get(State(state): State<McpAppState>, headers: HeaderMap) -> Response {
+    let rx = match session_id(&state, &headers)
+        .and_then(|id| state.subscribe(&id).map_err(TransportError::internal))
+    {
+        Ok(Some(rx)) => rx,
+        Ok(None) => {
+            return TransportError::new(StatusCode::NOT_FOUND, "Session not found").into_response();
+        }
+        Err(error) => return error.into_response(),
+    };
+    Sse::new(session_events(rx))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Streamable HTTP: the client ends its session
+async fn handle_streamable_delete(
+    State(state): State<McpAppState>,
+    headers: HeaderMap,
+) -> Response {
+    match session_id(&state, &headers) {
+        Ok(session_id) => match state.sessions() {
+            Ok(mut sessions) => {
+                sessions.remove(&session_id);
+                StatusCode::OK.into_response()
+            }
+            Err(e) => TransportError::internal(e).into_response(),
+        },
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Legacy HTTP+SSE: the stream starts with an `endpoint` event naming the URL to POST to.
+///
+/// Requests carrying `Mcp-Session-Id` are Streamable HTTP clients configured with `/sse`.
+async fn handle_sse(State(state): State<McpAppState>, headers: HeaderMap) -> Response {
+    if headers.contains_key(SESSION_ID_HEADER) {
+        return handle_streamable_get(State(state), headers).await;
+    }
+
+    let session_id = new_session_id();
+    let rx = match state.create_session(&session_id, None) {
+        Ok(rx) => rx,
+        Err(error) => return error.into_response(),
+    };
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("/message?sessionId={}", session_id));
+    let guard = SessionGuard {
+        sessions: state.sessions.clone(),
+        session_id,
+    };
+    let stream = tokio_stream::once(Ok(endpoint))
+        .chain(session_events(rx))
+        .map(move |event| {
+            let _session = &guard;
+            event
+        });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+}
+
+/// Legacy HTTP+SSE: responses go to the session's stream and the POST gets 202.
+///
+/// Without `sessionId` the responses are returned in the body for simple clients.
 async fn handle_message(
     State(state): State<McpAppState>,
-    Json(request): Json<JsonRpcRequest>,
-) -> impl IntoResponse {
-    let response = process_request(&state, request).await;
-    let response_json = serde_json::to_string(&response).unwrap_or_default();
+    Query(query): Query<MessageQuery>,
+    body: Bytes,
+) -> Response {
+    let (messages, batch) = match parse_messages(&body) {
+        Ok(messages) => messages,
+        Err(error) => return error.into_response(),
+    };
+
+    let Some(session_id) = query.session_id else {
+        let responses = process_messages(&state, messages, None).await;
+        return responses_body(responses, batch);
+    };
+    let tx = state
+        .sessions()
+        .map(|sessions| sessions.get(&session_id).map(|session| session.tx.clone()));
+    let tx = match tx {
+        Ok(Some(tx)) => tx,
+        Ok(None) => {
+            return TransportError::new(StatusCode::NOT_FOUND, "Session not found").into_response();
+        }
+        Err(e) => return TransportError::internal(e).into_response(),
+    };
 
-    // Send response through SSE channel for SSE clients
-    let _ = state.sse_tx.send(response_json);
+    let responses = process_messages(&state, messages, Some(&session_id)).await;
+    let body = match responses.len() {
+        0 => None,
+        _ if batch => serde_json::to_string(&responses).ok(),
+        _ => serde_json::to_string(&responses[0]).ok(),
+    };
+    if let Some(body) = body {
+        let _ = tx.send(body);
+    }
+    StatusCode::ACCEPTED.into_response()
+}
+
+/// `clientInfo` from `initialize` params
+fn client_info(params: &Value) -> Option<ClientInfo> {
+    params
+        .get("clientInfo")
+        .and_then(|info| serde_json::from_value(info.clone()).ok())
+}
 
-    // Also return response directly in HTTP body for simple clients
-    (StatusCode::OK, Json(response))
+/// Pick the client's version when supported, otherwise the newest one
+fn negotiate_version(requested: Option<&str>) -> &'static str {
+    PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| Some(**version) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0])
 }
 
-async fn process_request(state: &McpAppState, request: JsonRpcRequest) -> JsonRpcResponse {
+async fn process_request(
+    state: &McpAppState,
+    request: JsonRpcRequest,
+    session_id: Option<&str>,
+) -> JsonRpcResponse {
     let id = request.id.clone().unwrap_or(Value::Null);
 
     match request.method.as_str() {
-        "initialize" => JsonRpcResponse::success(
-            id,
-            json!({
-                "protocolVersion": PROTOCOL_VERSION,
-                "capabilities": {
-                    "tools": { "listChanged": false }
-                },
-                "serverInfo": {
-                    "name": "socket-io-client-mcp",
-                    "version": "0.1.0"
-                }
-            }),
-        ),
+        "initialize" => {
+            if let Err(e) = state.set_client(session_id, client_info(&request.params)) {
+                return JsonRpcResponse::error(id, -32603, &e);
+            }
+            let requested = request
+                .params
+                .get("protocolVersion")
+                .and_then(|v| v.as_str());
+
+            JsonRpcResponse::success(
+                id,
+                json!({
+                    "protocolVersion": negotiate_version(requested),
+                    "capabilities": {
+                        "tools": { "listChanged": false },
+                        "resources": { "subscribe": true, "listChanged": false }
+                    },
+                    "serverInfo": {
+                        "name": "socket-io-client-mcp",
+                        "version": "0.1.0"
+                    }
+                }),
+            )
+        }
 
         "notifications/initialized" => {
             // Just acknowledge, no response needed for notifications
@@ -515,32 +1305,88 @@ async fn process_request(state: &McpAppState, request: JsonRpcRequest) -> JsonRp
                 .cloned()
                 .unwrap_or(json!({}));
 
-            match tool_name {
-                Some(name) => match execute_tool(&state.socket, name, &arguments).await {
-                    Ok(result) => {
-                        let result_text = serde_json::to_string_pretty(&result).unwrap_or_default();
-                        JsonRpcResponse::success(
-                            id,
-                            json!({
-                                "content": [{
-                                    "type": "text",
-                                    "text": result_text
-                                }]
-                            }),
-                        )
-                    }
-                    Err(e) => JsonRpcResponse::success(
-                        id,
-                        json!({
-                            "content": [{
-                                "type": "text",
-                                "text": e
-                            }],
-                            "isError": true
-                        }),
-                    ),
-                },
-                None => JsonRpcResponse::error(id, -32602, "Missing tool name"),
+            let Some(name) = tool_name else {
+                return JsonRpcResponse::error(id, -32602, "Missing tool name");
+            };
+
+            let client = match state.client(session_id) {
+                Ok(client) => client,
+                Err(e) => return JsonRpcResponse::error(id, -32603, &e),
+            };
+            let started_at = chrono::Utc::now();
+            let started = Instant::now();
+            let outcome = execute_tool(&state.socket, name, &arguments).await;
+            let duration = started.elapsed();
+
+            // Payloads may carry credentials; hide them from the assistant
+            let connection_id = arguments
+                .get("connection_id")
+                .and_then(|v| v.as_i64())
+                .or_else(|| state.socket.get_current_connection_id());
+            let outcome = outcome.map(|mut result| {
+                redaction::redact_value(connection_id, &mut result);
+                serde_json::to_string_pretty(&result).unwrap_or_default()
+            });
+
+            mcp_audit::record(
+                client.as_ref(),
+                name,
+                &arguments,
+                connection_id,
+                outcome.as_deref().map_err(String::as_str),
+                started_at,
+                duration,
+            );
+
+            match outcome {
+                Ok(result_text) => JsonRpcResponse::success(
+                    id,
+                    json!({
+                        "content": [{
+                            "type": "text",
+                            "text": result_text
+                        }]
+                    }),
+                ),
+                Err(e) => JsonRpcResponse::success(
+                    id,
+                    json!({
+                        "content": [{
+                            "type": "text",
+                            "text": e
+                        }],
+                        "isError": true
+                    }),
+                ),
+            }
+        }
+
+        "resources/list" => match list_resources() {
+            Ok(result) => JsonRpcResponse::success(id, result),
+            Err(e) => JsonRpcResponse::error(id, -32603, &e),
+        },
+
+        "resources/templates/list" => JsonRpcResponse::success(id, list_resource_templates()),
+
+        "resources/read" => {
+            let uri = request.params.get("uri").and_then(|v| v.as_str());
+            match uri.map(read_resource) {
+                Some(Ok(Some(result))) => JsonRpcResponse::success(id, result),
+                Some(Ok(None)) => JsonRpcResponse::error(
+                    id,
+                    RESOURCE_NOT_FOUND,
+                    &format!("Resource not found: {}", uri.unwrap_or_default()),
+                ),
+                Some(Err(e)) => JsonRpcResponse::error(id, -32603, &e),
+                None => JsonRpcResponse::error(id, -32602, "uri is required"),
+            }
+        }
+
+        "resources/subscribe" | "resources/unsubscribe" => {
+            let subscribe = request.method == "resources/subscribe";
+            match set_subscription(state, session_id, &request.params, subscribe) {
+                Ok(()) => JsonRpcResponse::success(id, json!({})),
+                Err(e) => JsonRpcResponse::error(id, -32602, &e),
             }
         }
 
@@ -557,6 +1403,181 @@ async fn process_request(state: &McpAppState, request: JsonRpcRequest) -> JsonRp
     }
 }
 
+// ============================================================================
+// Stdio Transport
+// ============================================================================
+
+/// Serve MCP over newline-delimited JSON-RPC until `input` ends.
+///
+/// Used by `sioc mcp` so clients can launch the server as a command. Each line
+/// is one message or batch; responses are written one per line.
+pub async fn serve_stdio<R, W>(
+    socket: SocketManager,
+    input: R,
+    mut output: W,
+) -> std::io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let state = McpAppState::new(socket, "");
+    // The whole stdio connection is one session, so subscriptions work
+    let session_id = new_session_id();
+    let mut notifications = state
+        .create_session(&session_id, None)
+        .map_err(|e| std::io::Error::other(e.message))?;
+    let mut lines = input.lines();
+
+    loop {
+        let reply = tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) => match process_line(&state, &session_id, &line).await {
+                    Some(reply) => reply,
+                    None => continue,
+                },
+                None => break,
+            },
+            Ok(notification) = notifications.recv() => notification,
+        };
+        output.write_all(reply.as_bytes()).await?;
+        output.write_all(b"\n").await?;
+        output.flush().await?;
+    }
+    Ok(())
+}
+
+/// The reply to one stdio line, if it held any requests
+async fn process_line(state: &McpAppState, session_id: &str, line: &str) -> Option<String> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    let reply = match parse_messages(line.as_bytes()) {
+        Ok((messages, batch)) => {
+            let responses = process_messages(state, messages, Some(session_id)).await;
+            match responses.len() {
+                0 => return None,
+                _ if batch => serde_json::to_string(&responses),
+                _ => serde_json::to_string(&responses[0]),
+            }
+        }
+        Err(error) => serde_json::to_string(&JsonRpcResponse::error(
+            Value::Null,
+            error.code,
+            &error.message,
+        )),
+    };
+    reply.ok()
+}
+
+// ============================================================================
+// Authentication
+// ============================================================================
+
+/// Bearer token required by the MCP server, generated and saved on first use
+pub fn auth_token() -> Result<String, String> {
+    if let Some(token) = db::get_app_state(AUTH_TOKEN_KEY).map_err(|e| e.to_string())? {
+        return Ok(token);
+    }
+    rotate_auth_token()
+}
+
+/// Replace the bearer token; a running server keeps the old one until restarted
+pub fn rotate_auth_token() -> Result<String, String> {
+    let token = secrets::generate_token(AUTH_TOKEN_BYTES);
+    db::set_app_state(AUTH_TOKEN_KEY, &token).map_err(|e| e.to_string())?;
+    Ok(token)
+}
+
+/// Origins of pages served from this machine; anything else is a website the user visited
+fn is_local_origin(origin: &str) -> bool {
+    let Some((_, rest)) = origin.split_once("://") else {
+        return false;
+    };
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1" | "tauri.localhost")
+}
+
+fn has_bearer_token(headers: &HeaderMap, expected: &str) -> bool {
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    // Compare every byte so timing does not reveal how much of the token matched
+    token.len() == expected.len()
+        && token
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn require_auth(State(state): State<McpAppState>, request: Request, next: Next) -> Response {
+    if let Some(origin) = request.headers().get(header::ORIGIN)
+        && !origin.to_str().is_ok_and(is_local_origin)
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            "Cross-origin requests are not allowed",
+        )
+            .into_response();
+    }
+    if !has_bearer_token(request.headers(), &state.auth_token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "Missing or invalid bearer token",
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Build the MCP HTTP router for a socket manager.
+///
+/// Streamable HTTP is served at `/mcp` (and `POST /sse`), legacy HTTP+SSE at
+/// `GET /sse` with `POST /message`. Every route requires `Authorization: Bearer <auth_token>` and rejects
+/// requests sent from non-local web pages.
+pub fn router(socket: SocketManager, auth_token: String) -> Router {
+    let app_state = McpAppState::new(socket, &auth_token);
+
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(|origin: &HeaderValue, _| {
+            origin.to_str().is_ok_and(is_local_origin)
+        }))
+        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            header::AUTHORIZATION,
+            SESSION_ID_HEADER,
+            PROTOCOL_VERSION_HEADER,
+        ])
+        .expose_headers([SESSION_ID_HEADER]);
+
+    Router::new()
+        .route(
+            "/mcp",
+            post(handle_streamable_post)
+                .get(handle_streamable_get)
+                .delete(handle_streamable_delete),
+        )
+        .route("/sse", get(handle_sse).post(handle_streamable_post))
+        .route("/message", post(handle_message))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            require_auth,
+        ))
+        .layer(cors)
+        .with_state(app_state)
+}
+
 // ============================================================================
 // Tauri State & Commands
 // ============================================================================
@@ -615,6 +1636,12 @@ impl McpServerState {
     }
 }
 
+impl Default for McpServerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[tauri::command]
 pub async fn start_mcp_server(
     port: u16,
@@ -625,25 +1652,8 @@ pub async fn start_mcp_server(
         return Err("MCP server already running".to_string());
     }
 
-    let (sse_tx, _) = broadcast::channel::<String>(100);
     let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
-
-    let app_state = McpAppState {
-        socket: socket_state.inner().clone(),
-        sse_tx,
-    };
-
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-        .allow_headers([header::CONTENT_TYPE, header::ACCEPT]);
-
-    let app = Router::new()
-        .route("/sse", get(handle_sse))
-        .route("/sse", post(handle_message))
-        .route("/message", post(handle_message))
-        .layer(cors)
-        .with_state(app_state);
+    let app = router(socket_state.inner().clone(), auth_token()?);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
     let listener = tokio::net::TcpListener::bind(addr)
@@ -699,6 +1709,18 @@ pub fn get_mcp_status(mcp_state: tauri::State<'_, McpServerState>) -> Result<Mcp
     Ok(mcp_state.get_status())
 }
 
+/// The bearer token MCP clients must send, for configuring them by hand
+#[tauri::command]
+pub fn get_mcp_auth_token() -> Result<String, String> {
+    auth_token()
+}
+
+/// Issue a new bearer token; clients configured with the old one must be updated
+#[tauri::command]
+pub fn rotate_mcp_auth_token() -> Result<String, String> {
+    rotate_auth_token()
+}
+
 // ============================================================================
 // Shell Command Execution
 // ============================================================================
@@ -769,7 +1791,8 @@ pub async fn check_claude_cli() -> Result<ClaudeCheckResult, String> {
 /// Run the Claude MCP add command to register socket-io-client as an MCP server
 #[tauri::command]
 pub async fn run_claude_mcp_add(port: u16) -> Result<ShellOutput, String> {
-    let url = format!("http://localhost:{}/sse", port);
+    let url = format!("http://localhost:{}/mcp", port);
+    let auth_header = format!("Authorization: Bearer {}", auth_token()?);
 
     // Get home directory
     let home_dir = std::env::var("HOME")
@@ -784,6 +1807,8 @@ pub async fn run_claude_mcp_add(port: u16) -> Result<ShellOutput, String> {
             "http",
             "socket-io-client",
             &url,
+            "--header",
+            &auth_header,
         ])
         .current_dir(&home_dir)
         .output()
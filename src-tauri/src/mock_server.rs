@@ -0,0 +1,421 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use socketioxide::SocketIo;
+use socketioxide::extract::{AckSender, Data, SocketRef};
+use tokio::sync::watch;
+
+use crate::db;
+use crate::event_sink::{EventSink, SocketEventPayload, SocketNotification, TauriSink};
+use crate::history_writer;
+use crate::local_server::{LocalServer, ManagedServer, ServerState, ServerStatus};
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockServerConfig {
+    /// Port to listen on (0 picks a free port)
+    pub port: u16,
+    /// Saved connection whose event history records the mock server's traffic
+    pub connection_id: i64,
+    pub namespaces: Vec<MockNamespace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockNamespace {
+    pub path: String,
+    #[serde(default)]
+    pub rules: Vec<MockRule>,
+    /// Recorded session replayed to every connecting socket
+    #[serde(default)]
+    pub script: Option<MockScript>,
+}
+
+/// Scripted behaviour of a mock namespace.
+///
+/// Only events named by a rule are handled (and recorded).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MockRule {
+    /// Acknowledge `event` with `response`
+    #[serde(rename_all = "camelCase")]
+    Ack { event: String, response: Value },
+    /// Emit `reply_event` with `payload` whenever `event` is received
+    #[serde(rename_all = "camelCase")]
+    Reply {
+        event: String,
+        reply_event: String,
+        payload: Value,
+    },
+    /// Emit `event` back to the sender with the received payload
+    #[serde(rename_all = "camelCase")]
+    Echo { event: String },
+    /// Emit `event` with `payload` to every connected socket every `interval_ms`
+    #[serde(rename_all = "camelCase")]
+    Interval {
+        event: String,
+        payload: Value,
+        interval_ms: u64,
+    },
+}
+
+/// A recorded exchange sequence, played independently for each connected socket
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockScript {
+    /// Emitted right after a socket connects
+    #[serde(default)]
+    pub on_connect: Vec<ScriptStep>,
+    /// Client events expected in order, each with the replies recorded after it
+    #[serde(default)]
+    pub exchanges: Vec<ScriptExchange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptExchange {
+    pub event: String,
+    pub replies: Vec<ScriptStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptStep {
+    pub event: String,
+    pub payload: Value,
+    /// Delay after the previous step (or the triggering client event)
+    pub delay_ms: u64,
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// Records mock server traffic into `event_history` and forwards it to the event sink.
+///
+/// Direction is from the server's point of view: events received from clients are `in`.
+#[derive(Clone)]
+struct Recorder {
+    connection_id: i64,
+    sink: Arc<dyn EventSink>,
+}
+
+impl Recorder {
+    fn record(&self, event_name: &str, payload: String, direction: &str) {
+        let timestamp = db::format_timestamp(Utc::now());
+        history_writer::record(
+            self.connection_id,
+            event_name,
+            &payload,
+            &timestamp,
+            direction,
+        );
+        self.sink
+            .send(SocketNotification::Event(SocketEventPayload {
+                connection_id: self.connection_id,
+                event_name: event_name.to_string(),
+                payload,
+                timestamp,
+                direction: direction.to_string(),
+            }));
+    }
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// A running mock Socket.IO server
+pub struct MockServer {
+    server: LocalServer,
+}
+
+impl MockServer {
+    pub async fn start(config: MockServerConfig, sink: Arc<dyn EventSink>) -> Result<Self, String> {
+        let recorder = Recorder {
+            connection_id: config.connection_id,
+            sink,
+        };
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (layer, io) = SocketIo::new_layer();
+        for namespace in config.namespaces {
+            register_namespace(&io, namespace, recorder.clone(), shutdown_rx.clone());
+        }
+
+        let server = LocalServer::start("MockServer", config.port, io, layer, shutdown_tx).await?;
+        Ok(Self { server })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.server.local_addr()
+    }
+
+    /// Stop interval and script emits, disconnect every socket, then wait for the server
+    pub async fn stop(self) {
+        self.server.stop().await;
+    }
+}
+
+impl ManagedServer for MockServer {
+    const NAME: &'static str = "Mock server";
+
+    fn local_addr(&self) -> SocketAddr {
+        self.local_addr()
+    }
+
+    async fn stop(self) {
+        self.stop().await
+    }
+}
+
+/// `shutdown` changes when the server stops; timed emits end with it
+fn register_namespace(
+    io: &SocketIo,
+    namespace: MockNamespace,
+    recorder: Recorder,
+    shutdown: watch::Receiver<bool>,
+) {
+    let mut event_rules: HashMap<String, Vec<MockRule>> = HashMap::new();
+    let mut intervals = Vec::new();
+    for rule in namespace.rules {
+        match &rule {
+            MockRule::Ack { event, .. }
+            | MockRule::Reply { event, .. }
+            | MockRule::Echo { event } => {
+                event_rules.entry(event.clone()).or_default().push(rule);
+            }
+            MockRule::Interval {
+                event,
+                payload,
+                interval_ms,
+            } => intervals.push((event.clone(), payload.clone(), *interval_ms)),
+        }
+    }
+    let event_rules = Arc::new(event_rules);
+    let intervals = Arc::new(intervals);
+    let script = namespace.script.map(Arc::new);
+
+    io.ns(namespace.path, move |socket: SocketRef| {
+        on_connect(
+            socket,
+            &event_rules,
+            &intervals,
+            script.as_ref(),
+            &recorder,
+            &shutdown,
+        );
+    });
+}
+
+fn on_connect(
+    socket: SocketRef,
+    event_rules: &Arc<HashMap<String, Vec<MockRule>>>,
+    intervals: &[(String, Value, u64)],
+    script: Option<&Arc<MockScript>>,
+    recorder: &Recorder,
+    shutdown: &watch::Receiver<bool>,
+) {
+    recorder.record(
+        "connect",
+        json!({ "socketId": socket.id.to_string() }).to_string(),
+        "in",
+    );
+
+    let mut event_names: HashSet<String> = event_rules.keys().cloned().collect();
+    if let Some(script) = script {
+        event_names.extend(script.exchanges.iter().map(|e| e.event.clone()));
+        play_steps(
+            socket.clone(),
+            script.on_connect.clone(),
+            recorder.clone(),
+            shutdown.clone(),
+        );
+    }
+    // Position of the next expected exchange, per socket
+    let cursor = Arc::new(Mutex::new(0usize));
+
+    for event_name in event_names {
+        let rules = event_rules.get(&event_name).cloned().unwrap_or_default();
+        let script = script.cloned();
+        let cursor = cursor.clone();
+        let recorder = recorder.clone();
+        let shutdown = shutdown.clone();
+        socket.on(
+            event_name.clone(),
+            move |socket: SocketRef, Data::<Value>(data), ack: AckSender| {
+                handle_event(&socket, &event_name, data, ack, &rules, &recorder);
+                if let Some(script) = &script {
+                    advance_script(&socket, &event_name, script, &cursor, &recorder, &shutdown);
+                }
+            },
+        );
+    }
+
+    for (event_name, payload, interval_ms) in intervals.iter().cloned() {
+        let socket = socket.clone();
+        let recorder = recorder.clone();
+        let mut shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms.max(1)));
+            // The first tick completes immediately; wait a full interval before emitting
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown.changed() => break,
+                }
+                if !socket.connected() {
+                    break;
+                }
+                if socket.emit(event_name.as_str(), &payload).is_ok() {
+                    recorder.record(&event_name, payload.to_string(), "out");
+                }
+            }
+        });
+    }
+
+    let disconnect_recorder = recorder.clone();
+    socket.on_disconnect(move |socket: SocketRef| {
+        disconnect_recorder.record(
+            "disconnect",
+            json!({ "socketId": socket.id.to_string() }).to_string(),
+            "in",
+        );
+    });
+}
+
+fn handle_event(
+    socket: &SocketRef,
+    event_name: &str,
+    data: Value,
+    ack: AckSender,
+    rules: &[MockRule],
+    recorder: &Recorder,
+) {
+    recorder.record(event_name, data.to_string(), "in");
+
+    let mut ack = Some(ack);
+    for rule in rules {
+        match rule {
+            MockRule::Ack { response, .. } => {
+                // A message can only be acknowledged once; the first ack rule wins
+                if let Some(ack) = ack.take()
+                    && ack.send(response).is_ok()
+                {
+                    recorder.record(event_name, response.to_string(), "out");
+                }
+            }
+            MockRule::Reply {
+                reply_event,
+                payload,
+                ..
+            } => {
+                if socket.emit(reply_event.as_str(), payload).is_ok() {
+                    recorder.record(reply_event, payload.to_string(), "out");
+                }
+            }
+            MockRule::Echo { .. } => {
+                if socket.emit(event_name, &data).is_ok() {
+                    recorder.record(event_name, data.to_string(), "out");
+                }
+            }
+            MockRule::Interval { .. } => {}
+        }
+    }
+}
+
+fn advance_script(
+    socket: &SocketRef,
+    event_name: &str,
+    script: &MockScript,
+    cursor: &Mutex<usize>,
+    recorder: &Recorder,
+    shutdown: &watch::Receiver<bool>,
+) {
+    let replies = {
+        let Ok(mut cursor) = cursor.lock() else {
+            return;
+        };
+        // Look ahead so a skipped client event does not stall the script
+        let Some(offset) = script.exchanges[*cursor..]
+            .iter()
+            .position(|exchange| exchange.event == event_name)
+        else {
+            return;
+        };
+        let index = *cursor + offset;
+        *cursor = index + 1;
+        script.exchanges[index].replies.clone()
+    };
+    play_steps(socket.clone(), replies, recorder.clone(), shutdown.clone());
+}
+
+fn play_steps(
+    socket: SocketRef,
+    steps: Vec<ScriptStep>,
+    recorder: Recorder,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    if steps.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        for step in steps {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_millis(step.delay_ms)) => {}
+                _ = shutdown.changed() => break,
+            }
+            if !socket.connected() {
+                break;
+            }
+            if socket.emit(step.event.as_str(), &step.payload).is_ok() {
+                recorder.record(&step.event, step.payload.to_string(), "out");
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Tauri State & Commands
+// ============================================================================
+
+pub type MockServerStatus = ServerStatus;
+
+pub type MockServerState = ServerState<MockServer>;
+
+#[tauri::command]
+pub async fn start_mock_server(
+    config: MockServerConfig,
+    app_handle: tauri::AppHandle,
+    mock_state: tauri::State<'_, MockServerState>,
+) -> Result<MockServerStatus, String> {
+    let connection_id = config.connection_id;
+    let sink = Arc::new(TauriSink::new(app_handle));
+    mock_state
+        .start(connection_id, MockServer::start(config, sink))
+        .await
+}
+
+#[tauri::command]
+pub async fn stop_mock_server(
+    mock_state: tauri::State<'_, MockServerState>,
+) -> Result<MockServerStatus, String> {
+    Ok(mock_state.stop().await)
+}
+
+#[tauri::command]
+pub fn get_mock_server_status(
+    mock_state: tauri::State<'_, MockServerState>,
+) -> Result<MockServerStatus, String> {
+    Ok(mock_state.status())
+}
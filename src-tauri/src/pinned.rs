@@ -1,15 +1,7 @@
 use crate::db;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PinnedMessage {
-    pub id: i64,
-    pub event_name: String,
-    pub payload: String,
-    pub label: Option<String>,
-    pub sort_order: i64,
-    pub auto_send: bool,
-}
+pub use crate::db::PinnedMessageRecord as PinnedMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreatePinnedInput {
@@ -66,40 +58,12 @@ pub fn reorder_pinned_messages(ids: Vec<i64>) -> Result<(), String> {
 
 #[tauri::command]
 pub fn list_pinned_messages(connection_id: i64) -> Result<Vec<PinnedMessage>, String> {
-    let rows = db::list_pinned_messages(connection_id).map_err(|e| e.to_string())?;
-
-    Ok(rows
-        .into_iter()
-        .map(
-            |(id, event_name, payload, label, sort_order, auto_send)| PinnedMessage {
-                id,
-                event_name,
-                payload,
-                label,
-                sort_order,
-                auto_send,
-            },
-        )
-        .collect())
+    db::list_pinned_messages(connection_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn list_auto_send_messages(connection_id: i64) -> Result<Vec<PinnedMessage>, String> {
-    let rows = db::list_auto_send_messages(connection_id).map_err(|e| e.to_string())?;
-
-    Ok(rows
-        .into_iter()
-        .map(
-            |(id, event_name, payload, label, sort_order, auto_send)| PinnedMessage {
-                id,
-                event_name,
-                payload,
-                label,
-                sort_order,
-                auto_send,
-            },
-        )
-        .collect())
+    db::list_auto_send_messages(connection_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::{self, ConnectionEventRecord, ConnectionRecord, PinnedMessageRecord};
+use crate::secrets::{self, SECRET_OPTION_KEYS};
+
+/// Identifies profile files so unrelated JSON is rejected on import
+pub const PROFILE_FORMAT: &str = "socket-io-client/connections";
+/// Bumped whenever the file layout changes incompatibly
+pub const PROFILE_FORMAT_VERSION: u32 = 1;
+
+// ============================================================================
+// File Format
+// ============================================================================
+
+/// A file of exported connection profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    /// App version that wrote the file
+    pub app_version: String,
+    pub connections: Vec<ConnectionProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default)]
+    pub auth_token: Option<String>,
+    /// JSON options string, as stored on the connection
+    #[serde(default = "default_options")]
+    pub options: String,
+    #[serde(default)]
+    pub auto_send_on_connect: bool,
+    #[serde(default)]
+    pub auto_send_on_reconnect: bool,
+    #[serde(default)]
+    pub events: Vec<ProfileEvent>,
+    #[serde(default)]
+    pub pinned_messages: Vec<ProfilePinnedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileEvent {
+    pub event_name: String,
+    #[serde(default = "default_true")]
+    pub is_listening: bool,
+}
+
+/// Pinned messages are stored in their display order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePinnedMessage {
+    pub event_name: String,
+    #[serde(default = "default_options")]
+    pub payload: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub auto_send: bool,
+}
+
+fn default_namespace() -> String {
+    "/".to_string()
+}
+
+fn default_options() -> String {
+    "{}".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ConnectionProfile {
+    /// Drop the auth token and credential-bearing options
+    pub fn strip_secrets(&mut self) {
+        self.auth_token = None;
+        if let Ok(Value::Object(mut options)) = serde_json::from_str::<Value>(&self.options) {
+            let before = options.len();
+            options.retain(|key, _| !SECRET_OPTION_KEYS.contains(&key.as_str()));
+            if options.len() != before {
+                self.options = Value::Object(options).to_string();
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Connection name cannot be empty".to_string());
+        }
+        if self.url.trim().is_empty() {
+            return Err(format!("Connection {} has no URL", self.name));
+        }
+        if self.events.iter().any(|e| e.event_name.trim().is_empty()) {
+            return Err(format!(
+                "Connection {} has an event with no name",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Export `connection_ids` (every connection when empty) with their listeners and pinned messages.
+///
+/// Secrets are written as `SECRET_MASK`, or left out entirely with `strip_secrets`.
+pub fn export_profiles(
+    connection_ids: &[i64],
+    strip_secrets: bool,
+) -> Result<ProfileBundle, String> {
+    let connections = if connection_ids.is_empty() {
+        db::list_connections().map_err(|e| e.to_string())?
+    } else {
+        connection_ids
+            .iter()
+            .map(|&id| {
+                db::get_connection_by_id(id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Connection {} not found", id))
+            })
+            .collect::<Result<Vec<_>, String>>()?
+    };
+
+    let mut profiles = Vec::with_capacity(connections.len());
+    for connection in connections {
+        let connection = secrets::mask_connection(connection);
+        let events = db::list_connection_events(connection.id).map_err(|e| e.to_string())?;
+        let pinned = db::list_pinned_messages(connection.id).map_err(|e| e.to_string())?;
+
+        let mut profile = ConnectionProfile {
+            name: connection.name,
+            url: connection.url,
+            namespace: connection.namespace,
+            auth_token: connection.auth_token,
+            options: connection.options,
+            auto_send_on_connect: connection.auto_send_on_connect,
+            auto_send_on_reconnect: connection.auto_send_on_reconnect,
+            events: events
+                .into_iter()
+                .map(|e| ProfileEvent {
+                    event_name: e.event_name,
+                    is_listening: e.is_listening,
+                })
+                .collect(),
+            pinned_messages: pinned
+                .into_iter()
+                .map(|m| ProfilePinnedMessage {
+                    event_name: m.event_name,
+                    payload: m.payload,
+                    label: m.label,
+                    auto_send: m.auto_send,
+                })
+                .collect(),
+        };
+        if strip_secrets {
+            profile.strip_secrets();
+        }
+        profiles.push(profile);
+    }
+
+    Ok(ProfileBundle {
+        format: PROFILE_FORMAT.to_string(),
+        version: PROFILE_FORMAT_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        connections: profiles,
+    })
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// What to do with a profile whose name matches an existing connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    /// Import under a new name such as `Staging (2)`
+    #[default]
+    Rename,
+    /// Replace the existing connection's settings, listeners and pinned messages
+    Overwrite,
+    /// Keep the existing connection and ignore the profile
+    Skip,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    #[serde(default)]
+    pub on_conflict: ConflictStrategy,
+    /// Import without auth tokens or credential-bearing options
+    #[serde(default)]
+    pub strip_secrets: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportAction {
+    Created,
+    Renamed,
+    Overwritten,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedProfile {
+    /// Name in the file
+    pub name: String,
+    /// Name saved in the app (differs from `name` when renamed)
+    pub saved_name: Option<String>,
+    pub connection_id: Option<i64>,
+    pub action: ImportAction,
+}
+
+/// Parse a profile file, checking its format and version before its contents
+pub fn parse_bundle(value: Value) -> Result<ProfileBundle, String> {
+    if value.get("format").and_then(Value::as_str) != Some(PROFILE_FORMAT) {
+        return Err("Not a connection profile file".to_string());
+    }
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or("Profile file has no version")?;
+    if version == 0 || version > PROFILE_FORMAT_VERSION as u64 {
+        return Err(format!(
+            "Profile file version {} is not supported (expected {} or older); please update the app",
+            version, PROFILE_FORMAT_VERSION
+        ));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid profile file: {}", e))
+}
+
+/// First free name of the form `name (n)`
+fn unique_name(name: &str, taken: &HashMap<String, i64>) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !taken.contains_key(candidate))
+        .expect("unbounded range always yields a free name")
+}
+
+/// Import `profiles`; nothing is written if any profile is invalid
+pub fn import_profiles(
+    profiles: Vec<ConnectionProfile>,
+    options: &ImportOptions,
+) -> Result<Vec<ImportedProfile>, String> {
+    for profile in &profiles {
+        profile.validate()?;
+    }
+
+    // Existing names; the newest connection wins when names are already duplicated
+    let mut taken: HashMap<String, i64> = HashMap::new();
+    for connection in db::list_connections().map_err(|e| e.to_string())? {
+        taken.entry(connection.name).or_insert(connection.id);
+    }
+
+    let mut results = Vec::with_capacity(profiles.len());
+    for mut profile in profiles {
+        if options.strip_secrets {
+            profile.strip_secrets();
+        }
+
+        let (replace_id, saved_name, action) = match taken.get(&profile.name) {
+            None => (None, profile.name.clone(), ImportAction::Created),
+            Some(_) if options.on_conflict == ConflictStrategy::Skip => {
+                results.push(ImportedProfile {
+                    name: profile.name,
+                    saved_name: None,
+                    connection_id: None,
+                    action: ImportAction::Skipped,
+                });
+                continue;
+            }
+            Some(&id) if options.on_conflict == ConflictStrategy::Overwrite => {
+                (Some(id), profile.name.clone(), ImportAction::Overwritten)
+            }
+            Some(_) => (
+                None,
+                unique_name(&profile.name, &taken),
+                ImportAction::Renamed,
+            ),
+        };
+
+        let connection_id = save_profile(replace_id, &saved_name, &profile)?;
+        taken.insert(saved_name.clone(), connection_id);
+        results.push(ImportedProfile {
+            name: profile.name,
+            saved_name: Some(saved_name),
+            connection_id: Some(connection_id),
+            action,
+        });
+    }
+
+    Ok(results)
+}
+
+fn save_profile(
+    replace_id: Option<i64>,
+    name: &str,
+    profile: &ConnectionProfile,
+) -> Result<i64, String> {
+    // Masked secrets keep the values of the connection being overwritten
+    let existing = match replace_id {
+        Some(id) => db::get_connection_by_id(id).map_err(|e| e.to_string())?,
+        None => None,
+    };
+    let existing_token = existing.as_ref().and_then(|c| c.auth_token.as_deref());
+    let existing_options = existing.as_ref().map(|c| c.options.as_str());
+
+    let connection = ConnectionRecord {
+        id: replace_id.unwrap_or_default(),
+        name: name.to_string(),
+        url: profile.url.clone(),
+        namespace: profile.namespace.clone(),
+        auth_token: secrets::seal_auth_token(profile.auth_token.as_deref(), existing_token)?,
+        options: secrets::seal_options(&profile.options, existing_options)?,
+        created_at: String::new(),
+        updated_at: String::new(),
+        auto_send_on_connect: profile.auto_send_on_connect,
+        auto_send_on_reconnect: profile.auto_send_on_reconnect,
+        read_only: false,
+    };
+    let events: Vec<ConnectionEventRecord> = profile
+        .events
+        .iter()
+        .map(|e| ConnectionEventRecord {
+            id: 0,
+            event_name: e.event_name.clone(),
+            is_listening: e.is_listening,
+        })
+        .collect();
+    let pinned: Vec<PinnedMessageRecord> = profile
+        .pinned_messages
+        .iter()
+        .enumerate()
+        .map(|(index, m)| PinnedMessageRecord {
+            id: 0,
+            event_name: m.event_name.clone(),
+            payload: m.payload.clone(),
+            label: m.label.clone(),
+            sort_order: index as i64 + 1,
+            auto_send: m.auto_send,
+        })
+        .collect();
+
+    db::save_connection_profile(replace_id, &connection, &events, &pinned)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Export connections (all when `connection_ids` is empty) as a profile file
+#[tauri::command]
+pub fn export_connection_profiles(
+    connection_ids: Vec<i64>,
+    strip_secrets: bool,
+) -> Result<ProfileBundle, String> {
+    export_profiles(&connection_ids, strip_secrets)
+}
+
+/// Import a profile file read by the frontend
+#[tauri::command]
+pub fn import_connection_profiles(
+    bundle: Value,
+    options: Option<ImportOptions>,
+) -> Result<Vec<ImportedProfile>, String> {
+    import_profiles(
+        parse_bundle(bundle)?.connections,
+        &options.unwrap_or_default(),
+    )
+}
@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::Utc;
+use rust_socketio::Payload;
+use rust_socketio::client::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use socketioxide::SocketIo;
+use socketioxide::adapter::Adapter;
+use socketioxide::extract::{AckSender, Data, Event, SocketRef};
+use socketioxide::handler::{FromMessageParts, Value as MessageValue};
+use socketioxide::socket::Socket;
+use tokio::sync::{mpsc, oneshot, watch};
+
+use crate::db;
+use crate::event_sink::{
+    EventSink, ProxyBreakpointPayload, SocketEventPayload, SocketNotification, TauriSink,
+};
+use crate::history_writer;
+use crate::local_server::{LocalServer, ManagedServer, ServerState, ServerStatus};
+use crate::redaction;
+use crate::secrets;
+use crate::socket_client::client_builder;
+
+/// How long the upstream server has to acknowledge a proxied message
+const UPSTREAM_ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    /// Local port apps connect to (0 picks a free port)
+    pub port: u16,
+    /// Saved connection to forward to
+    pub connection_id: i64,
+    /// Saved connection whose event history records the proxied traffic, kept apart from
+    /// the target's own sessions
+    pub recording_connection_id: i64,
+    #[serde(default)]
+    pub rules: Vec<ProxyRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyRule {
+    /// Event name to match, or `*` for every event
+    pub event: String,
+    /// `out` (app to server) or `in` (server to app); both when omitted
+    pub direction: Option<String>,
+    pub action: ProxyAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ProxyAction {
+    /// Do not forward the message
+    Drop,
+    /// Hold the message before forwarding it
+    #[serde(rename_all = "camelCase")]
+    Delay { delay_ms: u64 },
+    /// Forward `payload` instead of the original payload
+    Replace { payload: Value },
+    /// Shallow-merge `payload` into the original object payload
+    Merge { payload: Value },
+    /// Pause until the user forwards (optionally edited) or drops the message
+    Breakpoint,
+}
+
+impl ProxyRule {
+    fn matches(&self, event_name: &str, direction: &str) -> bool {
+        (self.event == "*" || self.event == event_name)
+            && self.direction.as_deref().is_none_or(|d| d == direction)
+    }
+}
+
+/// How the user resolved a breakpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BreakpointDecision {
+    /// Forward the message, replacing its payload when given
+    Forward {
+        payload: Option<Value>,
+    },
+    Drop,
+}
+
+// ============================================================================
+// Proxy
+// ============================================================================
+
+/// State shared by every proxied socket
+struct ProxyContext {
+    recording_connection_id: i64,
+    rules: RwLock<Vec<ProxyRule>>,
+    sink: Arc<dyn EventSink>,
+    next_breakpoint_id: AtomicU64,
+    breakpoints: Mutex<HashMap<u64, oneshot::Sender<BreakpointDecision>>>,
+}
+
+impl ProxyContext {
+    fn record(&self, event_name: &str, payload: &Value, direction: &str) {
+        let payload = payload.to_string();
+        let timestamp = db::format_timestamp(Utc::now());
+        history_writer::record(
+            self.recording_connection_id,
+            event_name,
+            &payload,
+            &timestamp,
+            direction,
+        );
+        self.sink
+            .send(SocketNotification::Event(SocketEventPayload {
+                connection_id: self.recording_connection_id,
+                event_name: event_name.to_string(),
+                payload,
+                timestamp,
+                direction: direction.to_string(),
+            }));
+    }
+
+    /// Apply the matching rules; `None` means the message is dropped
+    async fn apply_rules(
+        &self,
+        event_name: &str,
+        payload: Value,
+        direction: &str,
+    ) -> Option<Value> {
+        let rules: Vec<ProxyRule> = match self.rules.read() {
+            Ok(rules) => rules
+                .iter()
+                .filter(|rule| rule.matches(event_name, direction))
+                .cloned()
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        let mut payload = payload;
+        for rule in rules {
+            match rule.action {
+                ProxyAction::Drop => return None,
+                ProxyAction::Delay { delay_ms } => {
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                }
+                ProxyAction::Replace {
+                    payload: replacement,
+                } => payload = replacement,
+                ProxyAction::Merge { payload: patch } => {
+                    if let (Value::Object(target), Value::Object(patch)) = (&mut payload, patch) {
+                        target.extend(patch);
+                    }
+                }
+                ProxyAction::Breakpoint => {
+                    match self
+                        .wait_for_breakpoint(event_name, &payload, direction)
+                        .await
+                    {
+                        BreakpointDecision::Forward {
+                            payload: Some(edited),
+                        } => payload = edited,
+                        BreakpointDecision::Forward { payload: None } => {}
+                        BreakpointDecision::Drop => return None,
+                    }
+                }
+            }
+        }
+        Some(payload)
+    }
+
+    async fn wait_for_breakpoint(
+        &self,
+        event_name: &str,
+        payload: &Value,
+        direction: &str,
+    ) -> BreakpointDecision {
+        let breakpoint_id = self.next_breakpoint_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut breakpoints) = self.breakpoints.lock() {
+            breakpoints.insert(breakpoint_id, tx);
+        }
+
+        self.sink
+            .send(SocketNotification::Breakpoint(ProxyBreakpointPayload {
+                connection_id: self.recording_connection_id,
+                breakpoint_id,
+                event_name: event_name.to_string(),
+                payload: payload.to_string(),
+                direction: direction.to_string(),
+            }));
+
+        // A dropped sender (proxy stopped) releases the message unchanged
+        rx.await
+            .unwrap_or(BreakpointDecision::Forward { payload: None })
+    }
+
+    fn resolve_breakpoint(&self, breakpoint_id: u64, decision: BreakpointDecision) -> bool {
+        let sender = self
+            .breakpoints
+            .lock()
+            .ok()
+            .and_then(|mut breakpoints| breakpoints.remove(&breakpoint_id));
+        match sender {
+            Some(tx) => tx.send(decision).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// A running proxy server
+pub struct ProxyServer {
+    server: LocalServer,
+    context: Arc<ProxyContext>,
+}
+
+impl ProxyServer {
+    pub async fn start(config: ProxyConfig, sink: Arc<dyn EventSink>) -> Result<Self, String> {
+        if config.recording_connection_id == config.connection_id {
+            return Err(
+                "Proxy traffic must be recorded under a different connection than its target"
+                    .to_string(),
+            );
+        }
+        let db::ConnectionRecord {
+            url,
+            namespace,
+            auth_token,
+            options,
+            ..
+        } = db::get_connection_by_id(config.connection_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Connection not found".to_string())
+            .and_then(secrets::reveal_connection)?;
+        let options_value: Value = serde_json::from_str(&options).unwrap_or(Value::Null);
+        let upstream = Arc::new(Upstream {
+            url,
+            namespace: namespace.clone(),
+            auth_token,
+            options_value,
+        });
+
+        let context = Arc::new(ProxyContext {
+            recording_connection_id: config.recording_connection_id,
+            rules: RwLock::new(config.rules),
+            sink,
+            next_breakpoint_id: AtomicU64::new(1),
+            breakpoints: Mutex::new(HashMap::new()),
+        });
+
+        let (layer, io) = SocketIo::new_layer();
+        let ns_context = context.clone();
+        io.ns(namespace, move |socket: SocketRef| {
+            on_connect(socket, upstream.clone(), ns_context.clone());
+        });
+
+        let (shutdown_tx, _) = watch::channel(false);
+        let server = LocalServer::start("Proxy", config.port, io, layer, shutdown_tx).await?;
+        Ok(Self { server, context })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.server.local_addr()
+    }
+
+    pub fn set_rules(&self, rules: Vec<ProxyRule>) {
+        if let Ok(mut guard) = self.context.rules.write() {
+            *guard = rules;
+        }
+    }
+
+    /// Release a held message; returns false if the breakpoint is unknown
+    pub fn resolve_breakpoint(&self, breakpoint_id: u64, decision: BreakpointDecision) -> bool {
+        self.context.resolve_breakpoint(breakpoint_id, decision)
+    }
+
+    /// Release held messages, disconnect every app socket, then wait for the server
+    pub async fn stop(self) {
+        // Release held messages so their queues can drain
+        if let Ok(mut breakpoints) = self.context.breakpoints.lock() {
+            breakpoints.clear();
+        }
+        self.server.stop().await;
+    }
+}
+
+impl ManagedServer for ProxyServer {
+    const NAME: &'static str = "Proxy";
+
+    fn local_addr(&self) -> SocketAddr {
+        self.local_addr()
+    }
+
+    async fn stop(self) {
+        self.stop().await
+    }
+}
+
+/// Where proxied sockets are forwarded to
+struct Upstream {
+    url: String,
+    namespace: String,
+    auth_token: Option<String>,
+    options_value: Value,
+}
+
+/// Whether the app asked for an ack; `AckSender` keeps this to itself
+struct AckRequested(bool);
+
+impl<A: Adapter> FromMessageParts<A> for AckRequested {
+    type Error = Infallible;
+
+    fn from_message_parts(
+        _: &Arc<Socket<A>>,
+        _: &mut MessageValue,
+        ack_id: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        Ok(Self(ack_id.is_some()))
+    }
+}
+
+/// Wire one app socket to its own upstream client.
+///
+/// Each direction is a queue drained in order, so delays and breakpoints hold back
+/// later messages the same way a slow network would. Messages the app wants acked are
+/// sent upstream with an ack request and the server's ack is relayed back.
+fn on_connect(socket: SocketRef, upstream: Arc<Upstream>, context: Arc<ProxyContext>) {
+    context.record(
+        "connect",
+        &json!({ "proxy": true, "socketId": socket.id.to_string() }),
+        "in",
+    );
+
+    let (out_tx, out_rx) = mpsc::unbounded_channel::<(String, Value, Option<AckSender>)>();
+    let (in_tx, in_rx) = mpsc::unbounded_channel::<(String, Value, ())>();
+
+    socket.on_fallback(
+        move |Event(event_name): Event,
+              Data::<Value>(data),
+              AckRequested(requested): AckRequested,
+              ack: AckSender| {
+            let _ = out_tx.send((event_name.to_string(), data, requested.then_some(ack)));
+        },
+    );
+
+    let client: Arc<Mutex<Option<Client>>> = Arc::new(Mutex::new(None));
+    let disconnect_client = client.clone();
+    socket.on_disconnect(move || disconnect_upstream(&disconnect_client));
+
+    let downstream = socket.clone();
+    let in_context = context.clone();
+    tokio::spawn(async move {
+        pump(in_rx, "in", &in_context, |event_name, payload, ()| {
+            std::future::ready(
+                downstream
+                    .emit(event_name.as_str(), &payload)
+                    .map_err(|e| e.to_string()),
+            )
+        })
+        .await;
+    });
+
+    tokio::spawn(async move {
+        let connected = connect_upstream(&upstream, socket.clone(), in_tx).await;
+        let upstream_client = match connected {
+            Ok(upstream_client) => upstream_client,
+            Err(e) => {
+                log::error!(
+                    "[Proxy] Upstream connection failed: {}",
+                    redaction::redact_text(Some(context.recording_connection_id), &e)
+                );
+                context.record("connect_error", &json!({ "message": e }), "in");
+                let _ = socket.disconnect();
+                return;
+            }
+        };
+        if let Ok(mut guard) = client.lock() {
+            *guard = Some(upstream_client.clone());
+        }
+        // The app may have left during the handshake, before `on_disconnect` could see the client
+        if !socket.connected() {
+            disconnect_upstream(&client);
+            return;
+        }
+
+        pump(out_rx, "out", &context, |event_name, payload, ack| {
+            let upstream_client = upstream_client.clone();
+            let context = context.clone();
+            async move {
+                // rust_socketio blocks until the message is written
+                tokio::task::spawn_blocking(move || {
+                    forward_upstream(&upstream_client, event_name, payload, ack, context)
+                })
+                .await
+                .map_err(|e| format!("Task join error: {}", e))?
+            }
+        })
+        .await;
+    });
+}
+
+/// Send an app message upstream, relaying the server's ack when the app asked for one
+fn forward_upstream(
+    client: &Client,
+    event_name: String,
+    payload: Value,
+    ack: Option<AckSender>,
+    context: Arc<ProxyContext>,
+) -> Result<(), String> {
+    let Some(ack) = ack else {
+        return client.emit(event_name, payload).map_err(|e| e.to_string());
+    };
+    let ack_event = event_name.clone();
+    let mut ack = Some(ack);
+    client
+        .emit_with_ack(
+            event_name,
+            payload,
+            UPSTREAM_ACK_TIMEOUT,
+            move |response, _| {
+                let response = payload_to_value(&response);
+                if let Some(ack) = ack.take()
+                    && ack.send(&response).is_ok()
+                {
+                    context.record(&ack_event, &response, "in");
+                }
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Disconnect the upstream client, if there is one yet, off the async runtime
+fn disconnect_upstream(client: &Mutex<Option<Client>>) {
+    let client = client.lock().ok().and_then(|mut guard| guard.take());
+    if let Some(client) = client {
+        tokio::task::spawn_blocking(move || {
+            let _ = client.disconnect();
+        });
+    }
+}
+
+async fn connect_upstream(
+    upstream: &Upstream,
+    downstream: SocketRef,
+    in_tx: mpsc::UnboundedSender<(String, Value, ())>,
+) -> Result<Client, String> {
+    let builder = client_builder(
+        upstream.url.clone(),
+        upstream.namespace.clone(),
+        upstream.auth_token.as_deref(),
+        &upstream.options_value,
+    )
+    // The app decides when to reconnect; it will open a new proxied socket
+    .reconnect_on_disconnect(false)
+    .on_any(move |event, payload, _| {
+        if let rust_socketio::Event::Custom(event_name) = event {
+            let _ = in_tx.send((event_name, payload_to_value(&payload), ()));
+        } else if event == rust_socketio::Event::Message {
+            let _ = in_tx.send(("message".to_string(), payload_to_value(&payload), ()));
+        }
+    })
+    .on(rust_socketio::Event::Close, move |_, _| {
+        let _ = downstream.clone().disconnect();
+    });
+
+    tokio::task::spawn_blocking(move || builder.connect().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Forward queued messages through the rules until the queue closes.
+///
+/// `T` travels with each message to `deliver`, e.g. the app's ack callback.
+async fn pump<T, F>(
+    mut rx: mpsc::UnboundedReceiver<(String, Value, T)>,
+    direction: &str,
+    context: &ProxyContext,
+    deliver: impl Fn(String, Value, T) -> F,
+) where
+    F: Future<Output = Result<(), String>>,
+{
+    while let Some((event_name, payload, extra)) = rx.recv().await {
+        let Some(payload) = context.apply_rules(&event_name, payload, direction).await else {
+            log::info!("[Proxy] Dropped {} ({})", event_name, direction);
+            continue;
+        };
+        context.record(&event_name, &payload, direction);
+        if let Err(e) = deliver(event_name, payload, extra).await {
+            log::warn!(
+                "[Proxy] Failed to forward ({}): {}",
+                direction,
+                redaction::redact_text(Some(context.recording_connection_id), &e)
+            );
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn payload_to_value(payload: &Payload) -> Value {
+    match payload {
+        Payload::Binary(bytes) => Value::Array(bytes.iter().map(|b| json!(b)).collect()),
+        Payload::Text(values) => match values.len() {
+            0 => Value::Null,
+            1 => values[0].clone(),
+            _ => Value::Array(values.clone()),
+        },
+        Payload::String(value) => {
+            serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone()))
+        }
+    }
+}
+
+// ============================================================================
+// Tauri State & Commands
+// ============================================================================
+
+pub type ProxyStatus = ServerStatus;
+
+pub type ProxyState = ServerState<ProxyServer>;
+
+#[tauri::command]
+pub async fn start_proxy(
+    config: ProxyConfig,
+    app_handle: tauri::AppHandle,
+    proxy_state: tauri::State<'_, ProxyState>,
+) -> Result<ProxyStatus, String> {
+    let connection_id = config.connection_id;
+    let sink = Arc::new(TauriSink::new(app_handle));
+    proxy_state
+        .start(connection_id, ProxyServer::start(config, sink))
+        .await
+}
+
+#[tauri::command]
+pub async fn stop_proxy(proxy_state: tauri::State<'_, ProxyState>) -> Result<ProxyStatus, String> {
+    Ok(proxy_state.stop().await)
+}
+
+#[tauri::command]
+pub fn get_proxy_status(proxy_state: tauri::State<'_, ProxyState>) -> Result<ProxyStatus, String> {
+    Ok(proxy_state.status())
+}
+
+#[tauri::command]
+pub async fn set_proxy_rules(
+    rules: Vec<ProxyRule>,
+    proxy_state: tauri::State<'_, ProxyState>,
+) -> Result<(), String> {
+    proxy_state
+        .with_server(|server| server.set_rules(rules))
+        .await
+}
+
+#[tauri::command]
+pub async fn resolve_proxy_breakpoint(
+    breakpoint_id: u64,
+    decision: BreakpointDecision,
+    proxy_state: tauri::State<'_, ProxyState>,
+) -> Result<(), String> {
+    if proxy_state
+        .with_server(|server| server.resolve_breakpoint(breakpoint_id, decision))
+        .await?
+    {
+        Ok(())
+    } else {
+        Err("Breakpoint not found".to_string())
+    }
+}
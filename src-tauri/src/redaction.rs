@@ -0,0 +1,288 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db;
+
+const SETTINGS_KEY: &str = "redaction_settings";
+
+/// Replaces every redacted value
+pub const REDACTED: &str = "[REDACTED]";
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+/// Rules for hiding secrets in stored history, emit logs, log lines and MCP results.
+///
+/// Redaction is off until enabled globally or per connection. Payloads are redacted
+/// before they are stored and the original values are not kept, so redacted history
+/// and emit logs cannot be re-sent or replayed as recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RedactionSettings {
+    /// Applies to every connection without its own toggle
+    pub enabled: bool,
+    /// JSON keys whose values are redacted, ignoring case, `-` and `_`
+    pub keys: Vec<String>,
+    /// Header names, redacted as JSON keys and in `Name: value` text
+    pub headers: Vec<String>,
+    /// Regular expressions; each match is redacted
+    pub patterns: Vec<String>,
+    /// Per-connection toggles, overriding `enabled`
+    pub connections: HashMap<i64, bool>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        Self {
+            enabled: false,
+            keys: strings(&[
+                "password",
+                "passwd",
+                "secret",
+                "token",
+                "accessToken",
+                "refreshToken",
+                "idToken",
+                "apiKey",
+                "clientSecret",
+                "privateKey",
+            ]),
+            headers: strings(&[
+                "Authorization",
+                "Proxy-Authorization",
+                "Cookie",
+                "Set-Cookie",
+                "X-Api-Key",
+                "X-Auth-Token",
+            ]),
+            patterns: strings(&[
+                r"(?i)\bbearer\s+[a-z0-9._~+/-]+=*",
+                r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*",
+            ]),
+            connections: HashMap::new(),
+        }
+    }
+}
+
+impl RedactionSettings {
+    pub fn enabled_for(&self, connection_id: Option<i64>) -> bool {
+        connection_id
+            .and_then(|id| self.connections.get(&id).copied())
+            .unwrap_or(self.enabled)
+    }
+}
+
+pub fn load_settings() -> Result<RedactionSettings, String> {
+    match db::get_app_state(SETTINGS_KEY).map_err(|e| e.to_string())? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(RedactionSettings::default()),
+    }
+}
+
+pub fn save_settings(settings: &RedactionSettings) -> Result<(), String> {
+    let redactor = Redactor::new(settings.clone())?;
+    let json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::set_app_state(SETTINGS_KEY, &json).map_err(|e| e.to_string())?;
+    *REDACTOR.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(redactor));
+    Ok(())
+}
+
+// ============================================================================
+// Redactor
+// ============================================================================
+
+/// Settings compiled for matching
+pub struct Redactor {
+    settings: RedactionSettings,
+    keys: HashSet<String>,
+    patterns: Vec<Regex>,
+    /// `name=value` and `Name: value` pairs for every key and header
+    pairs: Option<Regex>,
+}
+
+static REDACTOR: RwLock<Option<Arc<Redactor>>> = RwLock::new(None);
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl Redactor {
+    pub fn new(settings: RedactionSettings) -> Result<Self, String> {
+        let patterns = settings
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| format!("Invalid pattern {}: {}", pattern, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let names: Vec<&String> = settings.keys.iter().chain(&settings.headers).collect();
+        let pairs = if names.is_empty() {
+            None
+        } else {
+            let alternatives: Vec<String> = names.iter().map(|name| regex::escape(name)).collect();
+            let pattern = format!(
+                r#"(?i)\b({})(\s*[=:]\s*)[^\s&,;"']+"#,
+                alternatives.join("|")
+            );
+            Some(Regex::new(&pattern).map_err(|e| e.to_string())?)
+        };
+
+        Ok(Self {
+            keys: names.iter().map(|name| normalize_key(name)).collect(),
+            settings,
+            patterns,
+            pairs,
+        })
+    }
+
+    pub fn enabled_for(&self, connection_id: Option<i64>) -> bool {
+        self.settings.enabled_for(connection_id)
+    }
+
+    /// Redact a payload; JSON keeps its shape, anything else is treated as text
+    pub fn redact_payload(&self, payload: &str) -> Option<String> {
+        if let Some(mut value) = parse_json_container(payload) {
+            return self.redact_value(&mut value).then(|| value.to_string());
+        }
+        self.redact_text(payload)
+    }
+
+    /// Redact secret keys and matching strings in place; returns whether anything changed
+    pub fn redact_value(&self, value: &mut Value) -> bool {
+        match value {
+            Value::Object(map) => {
+                let mut changed = false;
+                for (key, child) in map.iter_mut() {
+                    if self.keys.contains(&normalize_key(key)) {
+                        if child.as_str() != Some(REDACTED) && !child.is_null() {
+                            *child = Value::String(REDACTED.to_string());
+                            changed = true;
+                        }
+                    } else {
+                        changed |= self.redact_value(child);
+                    }
+                }
+                changed
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .fold(false, |changed, item| self.redact_value(item) | changed),
+            Value::String(text) => match self.redact_payload(text) {
+                Some(redacted) => {
+                    *text = redacted;
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Redact pattern matches and `name=value` pairs in free text
+    pub fn redact_text(&self, text: &str) -> Option<String> {
+        let mut result = text.to_string();
+        for pattern in &self.patterns {
+            if pattern.is_match(&result) {
+                result = pattern.replace_all(&result, REDACTED).into_owned();
+            }
+        }
+        if let Some(pairs) = &self.pairs {
+            result = pairs
+                .replace_all(&result, format!("${{1}}${{2}}{}", REDACTED))
+                .into_owned();
+        }
+        (result != text).then_some(result)
+    }
+}
+
+/// Strings holding a JSON object or array are redacted structurally
+fn parse_json_container(text: &str) -> Option<Value> {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
+
+/// The active redactor, loaded from the settings on first use
+pub fn redactor() -> Arc<Redactor> {
+    if let Some(redactor) = REDACTOR.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return redactor.clone();
+    }
+    let settings = load_settings().unwrap_or_else(|e| {
+        log::warn!("[Redaction] Failed to load settings, using defaults: {}", e);
+        RedactionSettings::default()
+    });
+    let redactor = Redactor::new(settings).unwrap_or_else(|e| {
+        log::warn!("[Redaction] Invalid settings, using defaults: {}", e);
+        Redactor::new(RedactionSettings::default()).expect("default rules compile")
+    });
+    let redactor = Arc::new(redactor);
+    *REDACTOR.write().unwrap_or_else(|e| e.into_inner()) = Some(redactor.clone());
+    redactor
+}
+
+/// Redact a payload before it is stored for `connection_id`.
+///
+/// Also returns whether anything was replaced, to be stored alongside the payload.
+pub fn redact_payload(connection_id: i64, payload: &str) -> (String, bool) {
+    let redactor = redactor();
+    if !redactor.enabled_for(Some(connection_id)) {
+        return (payload.to_string(), false);
+    }
+    match redactor.redact_payload(payload) {
+        Some(redacted) => (redacted, true),
+        None => (payload.to_string(), false),
+    }
+}
+
+/// Redact a value leaving the app, such as an MCP tool result
+pub fn redact_value(connection_id: Option<i64>, value: &mut Value) {
+    let redactor = redactor();
+    if redactor.enabled_for(connection_id) {
+        redactor.redact_value(value);
+    }
+}
+
+/// Redact text written to the log
+pub fn redact_text(connection_id: Option<i64>, text: &str) -> String {
+    let redactor = redactor();
+    if !redactor.enabled_for(connection_id) {
+        return text.to_string();
+    }
+    redactor
+        .redact_text(text)
+        .unwrap_or_else(|| text.to_string())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_redaction_settings() -> Result<RedactionSettings, String> {
+    load_settings()
+}
+
+#[tauri::command]
+pub fn save_redaction_settings(settings: RedactionSettings) -> Result<(), String> {
+    save_settings(&settings)
+}
+
+/// Turn redaction on or off for one connection
+#[tauri::command]
+pub fn set_connection_redaction(connection_id: i64, enabled: bool) -> Result<(), String> {
+    let mut settings = load_settings()?;
+    settings.connections.insert(connection_id, enabled);
+    save_settings(&settings)
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db;
+use crate::event_sink::TauriSink;
+use crate::history_writer;
+use crate::mock_server::{
+    MockNamespace, MockScript, MockServer, MockServerConfig, MockServerState, MockServerStatus,
+    ScriptExchange, ScriptStep,
+};
+use crate::socket_client::SocketManager;
+
+/// Events synthesized by `SocketManager` rather than sent over the wire
+const SYNTHETIC_EVENTS: &[&str] = &["connect", "disconnect", "connect_error"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedEvent {
+    pub id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub timestamp: String,
+    pub direction: String,
+    /// Secrets in the payload were replaced when it was stored
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl RecordedEvent {
+    fn is_synthetic(&self) -> bool {
+        SYNTHETIC_EVENTS.contains(&self.event_name.as_str())
+    }
+
+    fn time(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.timestamp).ok()
+    }
+}
+
+/// Load a recorded session of `connection_id`.
+///
+/// Without `from_id` the session starts at the most recent `connect` event.
+pub fn load_session(
+    connection_id: i64,
+    from_id: Option<i64>,
+    to_id: Option<i64>,
+) -> Result<Vec<RecordedEvent>, String> {
+    // Include events still queued on the history writer
+    history_writer::flush();
+
+    let from_id = match from_id {
+        Some(id) => Some(id),
+        None => db::find_last_session_start(connection_id).map_err(|e| e.to_string())?,
+    };
+    let rows =
+        db::list_event_history_between(connection_id, from_id, to_id).map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|event| RecordedEvent {
+            id: event.id,
+            event_name: event.event_name,
+            payload: event.payload,
+            timestamp: event.timestamp,
+            direction: event.direction,
+            redacted: event.redacted,
+        })
+        .collect())
+}
+
+fn validate_speed(speed: f64) -> Result<f64, String> {
+    if speed.is_finite() && speed > 0.0 {
+        Ok(speed)
+    } else {
+        Err("Speed must be a positive number".to_string())
+    }
+}
+
+/// Time between two recorded events, divided by `speed`
+fn scaled_delay(
+    previous: Option<DateTime<FixedOffset>>,
+    current: Option<DateTime<FixedOffset>>,
+    speed: f64,
+) -> Duration {
+    match (previous, current) {
+        (Some(previous), Some(current)) => {
+            let millis = (current - previous).num_milliseconds().max(0) as f64;
+            Duration::from_millis((millis / speed) as u64)
+        }
+        _ => Duration::ZERO,
+    }
+}
+
+fn parse_payload(payload: &str) -> Value {
+    serde_json::from_str(payload).unwrap_or_else(|_| Value::String(payload.to_string()))
+}
+
+/// Turn a client-side recording into a mock server script.
+///
+/// Each outgoing event becomes an expected client event, answered by the incoming
+/// events recorded after it; incoming events before the first one are sent on connect.
+pub fn build_server_script(events: &[RecordedEvent], speed: f64) -> Result<MockScript, String> {
+    let speed = validate_speed(speed)?;
+    let mut script = MockScript::default();
+    let mut previous = None;
+
+    for event in events {
+        let time = event.time();
+        if !event.is_synthetic() {
+            match event.direction.as_str() {
+                "out" => script.exchanges.push(ScriptExchange {
+                    event: event.event_name.clone(),
+                    replies: Vec::new(),
+                }),
+                "in" => {
+                    let step = ScriptStep {
+                        event: event.event_name.clone(),
+                        payload: parse_payload(&event.payload),
+                        delay_ms: scaled_delay(previous, time, speed).as_millis() as u64,
+                    };
+                    match script.exchanges.last_mut() {
+                        Some(exchange) => exchange.replies.push(step),
+                        None => script.on_connect.push(step),
+                    }
+                }
+                _ => {}
+            }
+        }
+        previous = time.or(previous);
+    }
+
+    Ok(script)
+}
+
+/// Refuse to re-send outgoing events whose secrets were redacted when they were stored
+fn ensure_not_redacted(events: &[RecordedEvent]) -> Result<(), String> {
+    let redacted = events
+        .iter()
+        .filter(|e| e.direction == "out" && e.redacted)
+        .count();
+    if redacted > 0 {
+        return Err(format!(
+            "{} recorded events contain redacted values and cannot be re-sent; \
+             turn off redaction for the source connection to record replayable sessions",
+            redacted
+        ));
+    }
+    Ok(())
+}
+
+/// Re-emit the outgoing events of a recording on `connection_id`, keeping their spacing.
+///
+/// Blocks until done or `cancel` is set; returns the number of events sent.
+pub fn replay_as_client(
+    manager: &SocketManager,
+    connection_id: i64,
+    events: &[RecordedEvent],
+    speed: f64,
+    cancel: &AtomicBool,
+) -> Result<usize, String> {
+    let speed = validate_speed(speed)?;
+    ensure_not_redacted(events)?;
+    let mut previous = None;
+    let mut sent = 0;
+
+    for event in events
+        .iter()
+        .filter(|e| e.direction == "out" && !e.is_synthetic())
+    {
+        let time = event.time();
+        let mut remaining = scaled_delay(previous, time, speed);
+        previous = time.or(previous);
+
+        // Sleep in short slices so cancellation is picked up promptly
+        while !remaining.is_zero() && !cancel.load(Ordering::Relaxed) {
+            let slice = remaining.min(Duration::from_millis(100));
+            thread::sleep(slice);
+            remaining -= slice;
+        }
+        if cancel.load(Ordering::Relaxed) {
+            log::info!("[Replay] Cancelled after {} events", sent);
+            break;
+        }
+
+        manager.emit_message(connection_id, &event.event_name, &event.payload)?;
+        sent += 1;
+    }
+
+    Ok(sent)
+}
+
+// ============================================================================
+// Tauri State & Commands
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaySessionInput {
+    /// Connection whose event history is replayed
+    pub source_connection_id: i64,
+    pub from_id: Option<i64>,
+    pub to_id: Option<i64>,
+    /// Playback speed multiplier (default: 1.0)
+    pub speed: Option<f64>,
+}
+
+/// Client replays in progress, keyed by target connection id
+#[derive(Default)]
+pub struct ReplayState {
+    running: Arc<Mutex<HashMap<i64, Arc<AtomicBool>>>>,
+}
+
+impl ReplayState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[tauri::command]
+pub fn replay_session_as_client(
+    input: ReplaySessionInput,
+    target_connection_id: i64,
+    socket_state: tauri::State<'_, SocketManager>,
+    replay_state: tauri::State<'_, ReplayState>,
+) -> Result<usize, String> {
+    let events = load_session(input.source_connection_id, input.from_id, input.to_id)?;
+    let speed = validate_speed(input.speed.unwrap_or(1.0))?;
+    ensure_not_redacted(&events)?;
+    let outgoing = events
+        .iter()
+        .filter(|e| e.direction == "out" && !e.is_synthetic())
+        .count();
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut running = replay_state
+            .running
+            .lock()
+            .map_err(|_| "Failed to lock replay state".to_string())?;
+        if running.contains_key(&target_connection_id) {
+            return Err("Replay already running for this connection".to_string());
+        }
+        running.insert(target_connection_id, cancel.clone());
+    }
+
+    let manager = socket_state.inner().clone();
+    let running = replay_state.running.clone();
+    thread::spawn(move || {
+        match replay_as_client(&manager, target_connection_id, &events, speed, &cancel) {
+            Ok(sent) => log::info!("[Replay] Sent {} events", sent),
+            Err(e) => log::error!("[Replay] Failed: {}", e),
+        }
+        if let Ok(mut running) = running.lock() {
+            running.remove(&target_connection_id);
+        }
+    });
+
+    Ok(outgoing)
+}
+
+#[tauri::command]
+pub fn stop_replay(
+    target_connection_id: i64,
+    replay_state: tauri::State<'_, ReplayState>,
+) -> Result<(), String> {
+    if let Ok(running) = replay_state.running.lock()
+        && let Some(cancel) = running.get(&target_connection_id)
+    {
+        cancel.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Build the mock server script for a recorded session without starting a server
+#[tauri::command]
+pub fn build_replay_script(input: ReplaySessionInput) -> Result<MockScript, String> {
+    let events = load_session(input.source_connection_id, input.from_id, input.to_id)?;
+    build_server_script(&events, input.speed.unwrap_or(1.0))
+}
+
+/// Start the mock server answering like the recorded server did
+#[tauri::command]
+pub async fn start_replay_server(
+    input: ReplaySessionInput,
+    port: u16,
+    recording_connection_id: i64,
+    app_handle: tauri::AppHandle,
+    mock_state: tauri::State<'_, MockServerState>,
+) -> Result<MockServerStatus, String> {
+    let namespace = db::get_connection_by_id(input.source_connection_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Connection not found".to_string())?
+        .namespace;
+
+    let events = load_session(input.source_connection_id, input.from_id, input.to_id)?;
+    let script = build_server_script(&events, input.speed.unwrap_or(1.0))?;
+
+    let config = MockServerConfig {
+        port,
+        connection_id: recording_connection_id,
+        namespaces: vec![MockNamespace {
+            path: namespace,
+            rules: Vec::new(),
+            script: Some(script),
+        }],
+    };
+    let sink = Arc::new(TauriSink::new(app_handle));
+    mock_state
+        .start(recording_connection_id, MockServer::start(config, sink))
+        .await
+}
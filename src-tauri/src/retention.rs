@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+pub use crate::db::ConnectionStorageRecord as ConnectionStorage;
+
+const SETTINGS_KEY: &str = "retention_settings";
+const DEFAULT_PRUNE_INTERVAL_SECS: u64 = 300;
+/// Oldest events deleted per round while the database is over its size limit
+const SIZE_PRUNE_BATCH: i64 = 1000;
+/// Upper bounds accepted by `validate`
+const MAX_AGE_DAYS: i64 = 36_500;
+const MAX_DB_SIZE_MB: u64 = 1024 * 1024;
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+/// Limits on a connection's event history; unset limits keep everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    /// Keep at most this many events per connection
+    pub max_rows: Option<i64>,
+    /// Delete events older than this many days
+    pub max_age_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionSettings {
+    /// Applies to every connection without its own limit
+    #[serde(default)]
+    pub global: RetentionPolicy,
+    /// Per-connection limits, overriding the global ones field by field
+    #[serde(default)]
+    pub connections: HashMap<i64, RetentionPolicy>,
+    /// Limits on the MCP audit log
+    #[serde(default)]
+    pub mcp_audit: RetentionPolicy,
+    /// Delete the oldest events across all connections while the database is larger
+    pub max_db_size_mb: Option<u64>,
+    /// How often the background task prunes (default: 5 minutes)
+    pub prune_interval_secs: Option<u64>,
+}
+
+impl RetentionSettings {
+    pub fn policy_for(&self, connection_id: i64) -> RetentionPolicy {
+        let own = self.connections.get(&connection_id);
+        RetentionPolicy {
+            max_rows: own.and_then(|p| p.max_rows).or(self.global.max_rows),
+            max_age_days: own
+                .and_then(|p| p.max_age_days)
+                .or(self.global.max_age_days),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let policies = [&self.global, &self.mcp_audit]
+            .into_iter()
+            .chain(self.connections.values());
+        for policy in policies {
+            if policy.max_rows.is_some_and(|rows| rows < 0) {
+                return Err("maxRows must not be negative".to_string());
+            }
+            if policy.max_age_days.is_some_and(|days| days < 0) {
+                return Err("maxAgeDays must not be negative".to_string());
+            }
+            if policy.max_age_days.is_some_and(|days| days > MAX_AGE_DAYS) {
+                return Err(format!("maxAgeDays must be at most {}", MAX_AGE_DAYS));
+            }
+        }
+        if self.max_db_size_mb.is_some_and(|mb| mb > MAX_DB_SIZE_MB) {
+            return Err(format!("maxDbSizeMb must be at most {}", MAX_DB_SIZE_MB));
+        }
+        if self.prune_interval_secs == Some(0) {
+            return Err("pruneIntervalSecs must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub fn load_settings() -> Result<RetentionSettings, String> {
+    match db::get_app_state(SETTINGS_KEY).map_err(|e| e.to_string())? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(RetentionSettings::default()),
+    }
+}
+
+pub fn save_settings(settings: &RetentionSettings) -> Result<(), String> {
+    settings.validate()?;
+    let json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::set_app_state(SETTINGS_KEY, &json).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Pruning
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub deleted_by_age: usize,
+    pub deleted_by_rows: usize,
+    pub deleted_by_size: usize,
+    pub deleted_mcp_audit: usize,
+}
+
+/// The time `days` before `now`, or `None` if nothing can be that old
+fn age_cutoff(now: DateTime<Utc>, days: i64) -> Option<String> {
+    chrono::Duration::try_days(days)
+        .and_then(|age| now.checked_sub_signed(age))
+        .map(db::format_timestamp)
+}
+
+/// Apply `settings` to the event history and MCP audit log once
+pub fn prune(settings: &RetentionSettings) -> Result<PruneReport, String> {
+    let mut report = PruneReport::default();
+    let now = Utc::now();
+
+    for connection_id in db::list_event_history_connection_ids().map_err(|e| e.to_string())? {
+        let policy = settings.policy_for(connection_id);
+        if let Some(cutoff) = policy.max_age_days.and_then(|days| age_cutoff(now, days)) {
+            report.deleted_by_age += db::prune_event_history_before(connection_id, &cutoff)
+                .map_err(|e| e.to_string())?;
+        }
+        if let Some(max_rows) = policy.max_rows {
+            report.deleted_by_rows += db::prune_event_history_to_rows(connection_id, max_rows)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    if let Some(cutoff) = settings
+        .mcp_audit
+        .max_age_days
+        .and_then(|days| age_cutoff(now, days))
+    {
+        report.deleted_mcp_audit +=
+            db::prune_mcp_audit_before(&cutoff).map_err(|e| e.to_string())?;
+    }
+    if let Some(max_rows) = settings.mcp_audit.max_rows {
+        report.deleted_mcp_audit +=
+            db::prune_mcp_audit_to_rows(max_rows).map_err(|e| e.to_string())?;
+    }
+
+    // Settings saved before the bounds existed may still hold huge limits
+    let max_bytes = settings
+        .max_db_size_mb
+        .and_then(|mb| mb.checked_mul(1024 * 1024))
+        .and_then(|bytes| i64::try_from(bytes).ok());
+    if let Some(max_bytes) = max_bytes {
+        loop {
+            let (_, used_bytes) = db::database_size().map_err(|e| e.to_string())?;
+            if used_bytes <= max_bytes {
+                break;
+            }
+            let deleted =
+                db::prune_oldest_event_history(SIZE_PRUNE_BATCH).map_err(|e| e.to_string())?;
+            if deleted == 0 {
+                break;
+            }
+            report.deleted_by_size += deleted;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Prune on an interval for as long as the app runs, re-reading settings each round
+pub async fn run_pruner() {
+    loop {
+        let settings = match load_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("[Retention] Failed to load settings: {}", e);
+                RetentionSettings::default()
+            }
+        };
+
+        let round = settings.clone();
+        match tokio::task::spawn_blocking(move || prune(&round)).await {
+            Ok(Ok(report)) => {
+                let total = report.deleted_by_age + report.deleted_by_rows + report.deleted_by_size;
+                if total > 0 || report.deleted_mcp_audit > 0 {
+                    log::info!("[Retention] Pruned {} events: {:?}", total, report);
+                }
+            }
+            Ok(Err(e)) => log::warn!("[Retention] Pruning failed: {}", e),
+            Err(e) => log::warn!("[Retention] Pruning task failed: {}", e),
+        }
+
+        let interval = settings
+            .prune_interval_secs
+            .unwrap_or(DEFAULT_PRUNE_INTERVAL_SECS);
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+// ============================================================================
+// Storage Report
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub file_bytes: i64,
+    /// File bytes minus free pages that VACUUM would release
+    pub used_bytes: i64,
+    pub connections: Vec<ConnectionStorage>,
+}
+
+pub fn storage_report() -> Result<StorageReport, String> {
+    let (file_bytes, used_bytes) = db::database_size().map_err(|e| e.to_string())?;
+    let connections = db::list_connection_storage().map_err(|e| e.to_string())?;
+
+    Ok(StorageReport {
+        file_bytes,
+        used_bytes,
+        connections,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_retention_settings() -> Result<RetentionSettings, String> {
+    load_settings()
+}
+
+#[tauri::command]
+pub fn set_retention_settings(settings: RetentionSettings) -> Result<(), String> {
+    save_settings(&settings)
+}
+
+#[tauri::command]
+pub async fn prune_event_history_now() -> Result<PruneReport, String> {
+    tokio::task::spawn_blocking(|| prune(&load_settings()?))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_storage_report() -> Result<StorageReport, String> {
+    storage_report()
+}
+
+/// VACUUM the database and return the storage report afterwards
+#[tauri::command]
+pub async fn vacuum_database() -> Result<StorageReport, String> {
+    tokio::task::spawn_blocking(|| {
+        db::vacuum().map_err(|e| e.to_string())?;
+        storage_report()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
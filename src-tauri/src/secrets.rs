@@ -0,0 +1,465 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use argon2::Argon2;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::{self, ConnectionRecord};
+
+const SETTINGS_KEY: &str = "secret_settings";
+const KEY_FILE_NAME: &str = "secret.key";
+/// New key file written while secrets are re-encrypted, renamed over the old one afterwards
+const NEW_KEY_FILE_NAME: &str = "secret.key.new";
+/// Marks values encrypted by this module; anything else is legacy plaintext
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+/// Encrypted with the passphrase-derived key to tell a wrong passphrase from a right one
+const PASSPHRASE_CHECK: &str = "socket-io-client";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Shown in place of a stored secret; saving it back keeps the stored value
+pub const SECRET_MASK: &str = "********";
+/// Connection options that carry credentials
+pub const SECRET_OPTION_KEYS: &[&str] = &["auth", "extraHeaders"];
+
+const LOCKED_ERROR: &str = "Secrets are locked; unlock them with your passphrase";
+
+struct KeyState {
+    /// Directory holding the key file
+    dir: Option<PathBuf>,
+    key: Option<Key>,
+}
+
+static STATE: Mutex<KeyState> = Mutex::new(KeyState {
+    dir: None,
+    key: None,
+});
+
+fn state() -> Result<MutexGuard<'static, KeyState>, String> {
+    STATE.lock().map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeySource {
+    /// Random key in a local file readable only by the user
+    #[default]
+    KeyFile,
+    /// Key derived from a passphrase entered on each start
+    Passphrase,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretSettings {
+    #[serde(default)]
+    source: KeySource,
+    salt: Option<String>,
+    check: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStatus {
+    pub source: KeySource,
+    pub locked: bool,
+}
+
+fn load_settings() -> Result<SecretSettings, String> {
+    match db::get_app_state(SETTINGS_KEY).map_err(|e| e.to_string())? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(SecretSettings::default()),
+    }
+}
+
+// ============================================================================
+// Keys
+// ============================================================================
+
+/// Load the encryption key and encrypt any secrets still stored in plaintext.
+///
+/// `dir` holds the key file, which is created on first use. With a passphrase
+/// configured, secrets stay locked until `unlock` is called.
+pub fn init(dir: &Path) -> Result<(), String> {
+    let settings = load_settings()?;
+    let key = match settings.source {
+        KeySource::KeyFile => Some(load_or_create_key_file(dir)?),
+        KeySource::Passphrase => None,
+    };
+
+    let unlocked = key.is_some();
+    {
+        let mut state = state()?;
+        state.dir = Some(dir.to_path_buf());
+        state.key = key;
+    }
+    if unlocked {
+        seal_plaintext_secrets()?;
+    }
+    Ok(())
+}
+
+pub fn status() -> Result<SecretStatus, String> {
+    let settings = load_settings()?;
+    Ok(SecretStatus {
+        source: settings.source,
+        locked: state()?.key.is_none(),
+    })
+}
+
+/// Derive the key from `passphrase` and keep it for this session
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    let settings = load_settings()?;
+    let (Some(salt), Some(check)) = (&settings.salt, &settings.check) else {
+        return Err("No passphrase is set".to_string());
+    };
+    let salt = BASE64.decode(salt).map_err(|e| e.to_string())?;
+    let key = derive_key(passphrase, &salt)?;
+    if decrypt_with(&key, check).ok().as_deref() != Some(PASSPHRASE_CHECK) {
+        return Err("Incorrect passphrase".to_string());
+    }
+
+    state()?.key = Some(key);
+    seal_plaintext_secrets()
+}
+
+/// Protect secrets with `passphrase`, or with a key file again when `None`.
+///
+/// Every stored secret is re-encrypted with the new key, so secrets must be
+/// unlocked first. A new key file only replaces the old one once the
+/// re-encrypted secrets are committed, so a failed write loses nothing.
+pub fn set_passphrase(passphrase: Option<&str>) -> Result<(), String> {
+    let old_key = current_key()?;
+    let dir = state()?.dir.clone().ok_or("Secrets are not initialized")?;
+
+    let (new_key, settings, new_key_file) = match passphrase {
+        Some(passphrase) => {
+            if passphrase.is_empty() {
+                return Err("Passphrase cannot be empty".to_string());
+            }
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            let key = derive_key(passphrase, &salt)?;
+            let settings = SecretSettings {
+                source: KeySource::Passphrase,
+                salt: Some(BASE64.encode(salt)),
+                check: Some(encrypt_with(&key, PASSPHRASE_CHECK)?),
+            };
+            (key, settings, None)
+        }
+        None => {
+            let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+            let path = dir.join(NEW_KEY_FILE_NAME);
+            write_key_file(&path, &key)?;
+            (key, SecretSettings::default(), Some(path))
+        }
+    };
+
+    let committed = reencrypt_secrets(&old_key, &new_key, &settings);
+    if let Err(e) = committed {
+        if let Some(path) = &new_key_file {
+            let _ = fs::remove_file(path);
+        }
+        return Err(e);
+    }
+
+    state()?.key = Some(new_key);
+    match new_key_file {
+        Some(path) => fs::rename(&path, dir.join(KEY_FILE_NAME)).map_err(|e| {
+            format!(
+                "Secrets were re-encrypted but the key could not be moved from {}: {}",
+                path.display(),
+                e
+            )
+        })?,
+        None => {
+            let _ = fs::remove_file(dir.join(KEY_FILE_NAME));
+        }
+    }
+    Ok(())
+}
+
+/// Re-encrypt every stored secret and save `settings` in one transaction
+fn reencrypt_secrets(
+    old_key: &Key,
+    new_key: &Key,
+    settings: &SecretSettings,
+) -> Result<(), String> {
+    let mut updates = Vec::new();
+    for connection in db::list_connections().map_err(|e| e.to_string())? {
+        let auth_token = connection
+            .auth_token
+            .as_deref()
+            .map(|token| reencrypt(old_key, new_key, token))
+            .transpose()?;
+        let options = map_secret_options(&connection.options, |value| {
+            let stored = match value {
+                Value::String(stored) if is_encrypted(stored) => stored.clone(),
+                plaintext => plaintext.to_string(),
+            };
+            reencrypt(old_key, new_key, &stored).map(Value::String)
+        })?;
+        updates.push((connection.id, auth_token, options));
+    }
+    let settings_json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::replace_connection_secrets(&updates, SETTINGS_KEY, &settings_json)
+        .map_err(|e| e.to_string())
+}
+
+fn current_key() -> Result<Key, String> {
+    state()?.key.ok_or_else(|| LOCKED_ERROR.to_string())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn load_or_create_key_file(dir: &Path) -> Result<Key, String> {
+    let path = dir.join(KEY_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(text) => {
+            let bytes = BASE64
+                .decode(text.trim())
+                .map_err(|e| format!("Invalid key file {}: {}", path.display(), e))?;
+            if bytes.len() != Key::default().len() {
+                return Err(format!("Invalid key file {}", path.display()));
+            }
+            Ok(*Key::from_slice(&bytes))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+            write_key_file(&path, &key)?;
+            Ok(key)
+        }
+        Err(e) => Err(format!("Failed to read key file: {}", e)),
+    }
+}
+
+/// Write the key readable and writable by the current user only
+fn write_key_file(path: &Path, key: &Key) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to write key file: {}", e))?;
+    file.write_all(BASE64.encode(key).as_bytes())
+        .map_err(|e| format!("Failed to write key file: {}", e))
+}
+
+// ============================================================================
+// Encryption
+// ============================================================================
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+pub fn encrypt(plaintext: &str) -> Result<String, String> {
+    encrypt_with(&current_key()?, plaintext)
+}
+
+/// Decrypt a stored value; values that were never encrypted are returned as-is
+pub fn decrypt(stored: &str) -> Result<String, String> {
+    if !is_encrypted(stored) {
+        return Ok(stored.to_string());
+    }
+    decrypt_with(&current_key()?, stored)
+}
+
+fn encrypt_with(key: &Key, plaintext: &str) -> Result<String, String> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(key)
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Failed to encrypt secret".to_string())?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(sealed)))
+}
+
+fn decrypt_with(key: &Key, stored: &str) -> Result<String, String> {
+    let encoded = stored
+        .strip_prefix(ENCRYPTED_PREFIX)
+        .ok_or("Value is not encrypted")?;
+    let sealed = BASE64.decode(encoded).map_err(|e| e.to_string())?;
+    if sealed.len() < NONCE_LEN {
+        return Err("Encrypted secret is truncated".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let plaintext = XChaCha20Poly1305::new(key)
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt secret; the key does not match".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
+
+/// Random hex string of `bytes` bytes, for access tokens
+pub fn generate_token(bytes: usize) -> String {
+    let mut token = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut token);
+    token.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn reencrypt(old_key: &Key, new_key: &Key, stored: &str) -> Result<String, String> {
+    let plaintext = if is_encrypted(stored) {
+        decrypt_with(old_key, stored)?
+    } else {
+        stored.to_string()
+    };
+    encrypt_with(new_key, &plaintext)
+}
+
+// ============================================================================
+// Connection Secrets
+// ============================================================================
+
+/// Apply `f` to each secret option; options that are not a JSON object are left alone
+fn map_secret_options(
+    options: &str,
+    mut f: impl FnMut(&Value) -> Result<Value, String>,
+) -> Result<String, String> {
+    let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(options) else {
+        return Ok(options.to_string());
+    };
+    let mut changed = false;
+    for key in SECRET_OPTION_KEYS {
+        if let Some(value) = map.get_mut(*key) {
+            *value = f(value)?;
+            changed = true;
+        }
+    }
+    Ok(if changed {
+        Value::Object(map).to_string()
+    } else {
+        options.to_string()
+    })
+}
+
+/// The auth token to store when saving `new` over `existing`.
+///
+/// `SECRET_MASK` keeps the existing token; anything else is encrypted.
+pub fn seal_auth_token(
+    new: Option<&str>,
+    existing: Option<&str>,
+) -> Result<Option<String>, String> {
+    match new {
+        None | Some("") => Ok(None),
+        Some(SECRET_MASK) => Ok(existing.map(str::to_string)),
+        Some(token) => encrypt(token).map(Some),
+    }
+}
+
+/// The options JSON to store when saving `new` over `existing`.
+///
+/// Secret options set to `SECRET_MASK` keep their existing value, or are
+/// dropped when there is none; the rest are encrypted.
+pub fn seal_options(new: &str, existing: Option<&str>) -> Result<String, String> {
+    let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(new) else {
+        return Ok(new.to_string());
+    };
+    let existing_map = existing
+        .and_then(|options| serde_json::from_str::<Value>(options).ok())
+        .and_then(|value| match value {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    for key in SECRET_OPTION_KEYS {
+        match map.get(*key) {
+            None => {}
+            Some(Value::String(value)) if value == SECRET_MASK => {
+                match existing_map.get(*key) {
+                    Some(stored) => map.insert(key.to_string(), stored.clone()),
+                    None => map.remove(*key),
+                };
+            }
+            Some(Value::String(value)) if is_encrypted(value) => {}
+            Some(value) => {
+                let sealed = encrypt(&value.to_string())?;
+                map.insert(key.to_string(), Value::String(sealed));
+            }
+        }
+    }
+    Ok(Value::Object(map).to_string())
+}
+
+/// Replace stored secrets with `SECRET_MASK` for display and export
+pub fn mask_connection(mut connection: ConnectionRecord) -> ConnectionRecord {
+    if connection.auth_token.is_some() {
+        connection.auth_token = Some(SECRET_MASK.to_string());
+    }
+    if let Ok(options) = map_secret_options(&connection.options, |_| {
+        Ok(Value::String(SECRET_MASK.to_string()))
+    }) {
+        connection.options = options;
+    }
+    connection
+}
+
+/// Decrypt the auth token and secret options of a connection about to be used
+pub fn reveal_connection(mut connection: ConnectionRecord) -> Result<ConnectionRecord, String> {
+    connection.auth_token = connection.auth_token.as_deref().map(decrypt).transpose()?;
+    connection.options = map_secret_options(&connection.options, |value| match value {
+        Value::String(stored) if is_encrypted(stored) => {
+            let json = decrypt(stored)?;
+            serde_json::from_str(&json).map_err(|e| e.to_string())
+        }
+        other => Ok(other.clone()),
+    })?;
+    Ok(connection)
+}
+
+/// Encrypt secrets saved before encryption was enabled
+fn seal_plaintext_secrets() -> Result<(), String> {
+    for connection in db::list_connections().map_err(|e| e.to_string())? {
+        let auth_token = match connection.auth_token.as_deref() {
+            Some(token) if !is_encrypted(token) => Some(encrypt(token)?),
+            other => other.map(str::to_string),
+        };
+        let options = seal_options(&connection.options, None)?;
+        if auth_token != connection.auth_token || options != connection.options {
+            db::update_connection_secrets(connection.id, auth_token.as_deref(), &options)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_secret_status() -> Result<SecretStatus, String> {
+    status()
+}
+
+#[tauri::command]
+pub fn unlock_secrets(passphrase: String) -> Result<(), String> {
+    unlock(&passphrase)
+}
+
+#[tauri::command]
+pub fn set_secret_passphrase(passphrase: Option<String>) -> Result<(), String> {
+    set_passphrase(passphrase.as_deref())
+}
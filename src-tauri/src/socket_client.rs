@@ -8,39 +8,18 @@ use rust_socketio::client::Client;
 use rust_socketio::{ClientBuilder, Event, Payload, TransportType};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
 
 use crate::db;
+use crate::event_sink::{
+    EventSink, SocketErrorPayload, SocketEventPayload, SocketNotification, SocketStatusPayload,
+};
+use crate::history_writer;
+use crate::redaction;
+use crate::secrets;
 
-const SOCKET_STATUS_EVENT: &str = "socket:status";
-const SOCKET_EVENT_EVENT: &str = "socket:event";
-const SOCKET_ERROR_EVENT: &str = "socket:error";
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SocketStatusPayload {
-    connection_id: i64,
-    status: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    message: Option<String>,
-}
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SocketErrorPayload {
-    connection_id: i64,
-    message: String,
-}
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SocketEventPayload {
-    connection_id: i64,
-    event_name: String,
-    payload: String,
-    timestamp: String,
-    direction: String,
-}
+/// Incoming events buffered for slow in-process subscribers
+const INCOMING_CHANNEL_CAPACITY: usize = 256;
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -102,20 +81,38 @@ pub struct SocketManager {
     connecting: Arc<Mutex<HashSet<i64>>>,
     /// Tracks connections that have connected at least once (for reconnect detection)
     connected_once: Arc<Mutex<HashSet<i64>>>,
-    app_handle: AppHandle,
+    sink: Arc<dyn EventSink>,
+    /// Incoming events, for in-process subscribers such as the MCP server
+    incoming: broadcast::Sender<SocketEventPayload>,
 }
 
 impl SocketManager {
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(sink: Arc<dyn EventSink>) -> Self {
         Self {
             connections: Arc::new(Mutex::new(HashMap::new())),
             active_connection_id: Arc::new(Mutex::new(None)),
             connecting: Arc::new(Mutex::new(HashSet::new())),
             connected_once: Arc::new(Mutex::new(HashSet::new())),
-            app_handle,
+            sink,
+            incoming: broadcast::channel(INCOMING_CHANNEL_CAPACITY).0,
         }
     }
 
+    /// Receive every incoming event recorded from now on
+    pub fn subscribe_incoming(&self) -> broadcast::Receiver<SocketEventPayload> {
+        self.incoming.subscribe()
+    }
+
+    /// Pass a notification from another component to this manager's sink
+    pub fn notify(&self, notification: SocketNotification) {
+        self.sink.send(notification);
+    }
+
+    /// Whether MCP confirmations sent through `notify` can be answered
+    pub fn handles_confirmations(&self) -> bool {
+        self.sink.handles_confirmations()
+    }
+
     /// Check if this connection has connected before (for reconnect detection)
     fn has_connected_before(&self, connection_id: i64) -> bool {
         if let Ok(guard) = self.connected_once.lock() {
@@ -215,7 +212,7 @@ impl SocketManager {
     }
 
     /// Perform auto-send for a connection
-    fn do_auto_send(&self, connection_id: i64) {
+    pub fn do_auto_send(&self, connection_id: i64) {
         // Get auto-send messages from DB
         let messages = match db::list_auto_send_messages(connection_id) {
             Ok(msgs) => msgs,
@@ -232,7 +229,7 @@ impl SocketManager {
 
         log::info!("[AutoSend] Sending {} messages", messages.len());
 
-        for (_, event_name, payload, _, _, _) in messages {
+        for message in messages {
             // Small delay between messages
             thread::sleep(Duration::from_millis(50));
 
@@ -242,12 +239,15 @@ impl SocketManager {
                 break;
             }
 
-            log::info!("[AutoSend] Emitting: {}", event_name);
-            if let Err(e) = self.emit_message(connection_id, &event_name, &payload) {
-                log::error!("[AutoSend] Failed to emit {}: {}", event_name, e);
+            log::info!("[AutoSend] Emitting: {}", message.event_name);
+            if let Err(e) = self.emit_message(connection_id, &message.event_name, &message.payload)
+            {
+                log::error!("[AutoSend] Failed to emit {}: {}", message.event_name, e);
             } else {
                 // Log to emit_logs
-                let _ = db::add_emit_log(connection_id, &event_name, &payload);
+                let (payload, redacted) =
+                    redaction::redact_payload(connection_id, &message.payload);
+                let _ = db::add_emit_log(connection_id, &message.event_name, &payload, redacted);
             }
         }
 
@@ -299,7 +299,7 @@ impl SocketManager {
             .emit(event_name, payload_value)
             .map_err(|e| e.to_string())?;
 
-        // Use emit_outgoing_event to both record to DB AND notify frontend via Tauri event
+        // Use emit_outgoing_event to both record to DB AND notify the event sink
         self.emit_outgoing_event(connection_id, event_name, payload.to_string());
         Ok(())
     }
@@ -414,12 +414,8 @@ impl SocketManager {
             state.event_buffer.push(event);
         }
 
-        // Persist to SQLite database
-        if let Err(e) =
-            db::add_event_history(connection_id, event_name, &payload, &timestamp, direction)
-        {
-            log::warn!("Failed to persist event to DB: {}", e);
-        }
+        // Persist to SQLite database (batched on the history writer thread)
+        history_writer::record(connection_id, event_name, &payload, &timestamp, direction);
     }
 
     fn emit_status(&self, connection_id: i64, status: &str, message: Option<String>) {
@@ -429,7 +425,7 @@ impl SocketManager {
             status: status.to_string(),
             message,
         };
-        let _ = self.app_handle.emit(SOCKET_STATUS_EVENT, payload);
+        self.sink.send(SocketNotification::Status(payload));
     }
 
     fn emit_error(&self, connection_id: i64, message: impl Into<String>) {
@@ -437,11 +433,11 @@ impl SocketManager {
             connection_id,
             message: message.into(),
         };
-        let _ = self.app_handle.emit(SOCKET_ERROR_EVENT, payload);
+        self.sink.send(SocketNotification::Error(payload));
     }
 
     fn emit_event(&self, connection_id: i64, event_name: &str, payload: String) {
-        let timestamp = Utc::now().to_rfc3339();
+        let timestamp = db::format_timestamp(Utc::now());
         self.record_event(
             connection_id,
             event_name,
@@ -456,12 +452,14 @@ impl SocketManager {
             timestamp,
             direction: "in".to_string(),
         };
-        let _ = self.app_handle.emit(SOCKET_EVENT_EVENT, event_payload);
+        // No subscribers is not an error
+        let _ = self.incoming.send(event_payload.clone());
+        self.sink.send(SocketNotification::Event(event_payload));
     }
 
     /// Emit outgoing event to frontend (for MCP-sent messages to appear in UI)
     fn emit_outgoing_event(&self, connection_id: i64, event_name: &str, payload: String) {
-        let timestamp = Utc::now().to_rfc3339();
+        let timestamp = db::format_timestamp(Utc::now());
         self.record_event(
             connection_id,
             event_name,
@@ -476,7 +474,7 @@ impl SocketManager {
             timestamp,
             direction: "out".to_string(),
         };
-        let _ = self.app_handle.emit(SOCKET_EVENT_EVENT, event_payload);
+        self.sink.send(SocketNotification::Event(event_payload));
     }
 
     fn disconnect_inner(&self, connection_id: i64, reason: &str) -> Result<(), String> {
@@ -497,16 +495,12 @@ impl SocketManager {
             status: "disconnected".to_string(),
             message: None,
         };
-        let _ = self.app_handle.emit(SOCKET_STATUS_EVENT, status_payload);
+        self.sink.send(SocketNotification::Status(status_payload));
 
         if client.is_some() {
-            let timestamp = Utc::now().to_rfc3339();
+            let timestamp = db::format_timestamp(Utc::now());
             let payload = json!({ "reason": reason }).to_string();
-            if let Err(e) =
-                db::add_event_history(connection_id, "disconnect", &payload, &timestamp, "in")
-            {
-                log::warn!("Failed to persist disconnect event to DB: {}", e);
-            }
+            history_writer::record(connection_id, "disconnect", &payload, &timestamp, "in");
             let event_payload = SocketEventPayload {
                 connection_id,
                 event_name: "disconnect".to_string(),
@@ -514,7 +508,7 @@ impl SocketManager {
                 timestamp,
                 direction: "in".to_string(),
             };
-            let _ = self.app_handle.emit(SOCKET_EVENT_EVENT, event_payload);
+            self.sink.send(SocketNotification::Event(event_payload));
         }
 
         if let Some(client) = client {
@@ -522,7 +516,6 @@ impl SocketManager {
         }
         Ok(())
     }
-
 }
 
 #[tauri::command]
@@ -537,38 +530,30 @@ fn do_connect(connection_id: i64, state: &SocketManager) -> Result<(), String> {
     let connection = db::get_connection_by_id(connection_id)
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "Connection not found".to_string())?;
+    if connection.read_only {
+        return Err("Imported sessions are read-only and cannot connect".to_string());
+    }
+    let connection = secrets::reveal_connection(connection)?;
 
-    let (_, _name, url, namespace, auth_token, options, _created_at, _updated_at, _, _) = connection;
+    let db::ConnectionRecord {
+        url,
+        namespace,
+        auth_token,
+        options,
+        ..
+    } = connection;
 
     let events = db::list_connection_events(connection_id).map_err(|e| e.to_string())?;
     let listening: Vec<String> = events
         .into_iter()
-        .filter(|(_, _, is_listening)| *is_listening)
-        .map(|(_, event_name, _)| event_name)
+        .filter(|event| event.is_listening)
+        .map(|event| event.event_name)
         .collect();
-    state.set_listening_events(connection_id, listening.into_iter());
+    state.set_listening_events(connection_id, listening);
     state.set_client(connection_id, None);
 
     let options_value: Value = serde_json::from_str(&options).unwrap_or(Value::Null);
-    let mut builder = ClientBuilder::new(url).namespace(namespace);
-
-    if let Some(auth_token) = auth_token.as_deref() {
-        builder = builder.auth(json!({ "token": auth_token }));
-    } else if let Some(auth_value) = options_value.get("auth") {
-        builder = builder.auth(auth_value.clone());
-    }
-
-    if let Some(reconnection) = options_value.get("reconnection").and_then(|v| v.as_bool()) {
-        builder = builder.reconnect_on_disconnect(reconnection);
-    } else {
-        builder = builder.reconnect_on_disconnect(true);
-    }
-
-    if let Some(transports) = options_value.get("transports").and_then(|v| v.as_array())
-        && transports.iter().any(|t| t.as_str() == Some("websocket"))
-    {
-        builder = builder.transport_type(TransportType::Websocket);
-    }
+    let mut builder = client_builder(url, namespace, auth_token.as_deref(), &options_value);
 
     let status_state = state.clone();
     let auto_send_on_connect = options_value
@@ -579,12 +564,12 @@ fn do_connect(connection_id: i64, state: &SocketManager) -> Result<(), String> {
         .get("autoSendOnReconnect")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
-    
+
     // Get auto-send settings from DB (these take priority over options)
     let (db_auto_connect, db_auto_reconnect) = db::get_connection_by_id(connection_id)
         .ok()
         .flatten()
-        .map(|(_, _, _, _, _, _, _, _, on_connect, on_reconnect)| (on_connect, on_reconnect))
+        .map(|c| (c.auto_send_on_connect, c.auto_send_on_reconnect))
         .unwrap_or((auto_send_on_connect, auto_send_on_reconnect));
 
     builder = builder.on(Event::Connect, move |_payload, _| {
@@ -690,6 +675,59 @@ fn do_connect(connection_id: i64, state: &SocketManager) -> Result<(), String> {
     }
 }
 
+/// Client builder for a saved connection's URL, namespace, auth and options
+pub(crate) fn client_builder(
+    url: String,
+    namespace: String,
+    auth_token: Option<&str>,
+    options_value: &Value,
+) -> ClientBuilder {
+    let mut builder = ClientBuilder::new(url).namespace(namespace);
+
+    if let Some(auth_token) = auth_token {
+        builder = builder.auth(json!({ "token": auth_token }));
+    } else if let Some(auth_value) = options_value.get("auth") {
+        builder = builder.auth(auth_value.clone());
+    }
+
+    if let Some(reconnection) = options_value.get("reconnection").and_then(|v| v.as_bool()) {
+        builder = builder.reconnect_on_disconnect(reconnection);
+    } else {
+        builder = builder.reconnect_on_disconnect(true);
+    }
+
+    // Same names and defaults as the Socket.IO JavaScript client
+    let delay = |key: &str, default: u64| {
+        options_value
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(default)
+    };
+    let delay_min = delay("reconnectionDelay", 1000);
+    let delay_max = delay("reconnectionDelayMax", 5000).max(delay_min);
+    builder = builder.reconnect_delay(delay_min, delay_max);
+
+    // Sent with the handshake request; like the JavaScript client, only string values
+    if let Some(headers) = options_value
+        .get("extraHeaders")
+        .and_then(|v| v.as_object())
+    {
+        for (name, value) in headers {
+            if let Some(value) = value.as_str() {
+                builder = builder.opening_header(name.as_str(), value);
+            }
+        }
+    }
+
+    if let Some(transports) = options_value.get("transports").and_then(|v| v.as_array())
+        && transports.iter().any(|t| t.as_str() == Some("websocket"))
+    {
+        builder = builder.transport_type(TransportType::Websocket);
+    }
+
+    builder
+}
+
 #[tauri::command]
 pub fn socket_set_active(
     connection_id: i64,
@@ -0,0 +1,167 @@
+mod common;
+
+use common::init_test_db;
+use serde_json::{Value, json};
+use socket_io_client_lib::collection_import::{CollectionFormat, UnmappedItem, import_collection};
+use socket_io_client_lib::profiles::{ImportAction, ImportOptions};
+use socket_io_client_lib::{db, secrets};
+
+fn postman_collection() -> Value {
+    json!({
+        "info": {
+            "_postman_id": "2f6c",
+            "name": "Realtime",
+            "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+        },
+        "variable": [{ "key": "host", "value": "localhost:3000" }],
+        "item": [
+            {
+                "name": "Chat",
+                "item": [
+                    {
+                        "name": "Postman chat",
+                        "type": "socketio",
+                        "request": {
+                            "url": { "raw": "http://{{host}}" },
+                            "namespace": "/chat",
+                            "header": [
+                                { "key": "X-Team", "value": "qa" },
+                                { "key": "X-Debug", "value": "1", "disabled": true }
+                            ],
+                            "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "abc" }] },
+                            "events": [{ "name": "message", "enabled": true }, "typing"],
+                            "messages": [
+                                { "name": "Join lobby", "event": "join", "payload": { "room": "lobby" } },
+                                { "name": "Broken", "payload": "{}" }
+                            ]
+                        }
+                    },
+                    {
+                        "name": "REST health",
+                        "request": { "method": "GET", "url": "http://{{host}}/health" }
+                    },
+                    {
+                        "name": "Staging chat",
+                        "type": "socketio",
+                        "request": { "url": "http://{{stagingHost}}" }
+                    }
+                ]
+            }
+        ]
+    })
+}
+
+fn firecamp_collection() -> Value {
+    json!({
+        "collection": { "name": "Realtime" },
+        "folders": [{ "name": "Games", "__ref": { "id": "f1" } }],
+        "requests": [
+            {
+                "__meta": { "name": "Firecamp game", "type": "socketio" },
+                "__ref": { "id": "r1", "folderId": "f1" },
+                "url": { "raw": "http://localhost:4000" },
+                "connections": [
+                    {
+                        "namespace": "/game",
+                        "listeners": [{ "name": "score", "listen": false }],
+                        "headers": [{ "key": "X-Player", "value": "7" }]
+                    },
+                    { "name": "admin", "namespace": "/admin" }
+                ]
+            },
+            {
+                "__meta": { "name": "Firecamp GraphQL", "type": "graphql" },
+                "__ref": { "id": "r2" },
+                "url": { "raw": "http://localhost:4000/graphql" }
+            }
+        ],
+        "requestItems": [
+            {
+                "name": "Move",
+                "__meta": { "event": "move" },
+                "__ref": { "id": "m1", "requestId": "r1" },
+                "value": { "value": "{\"x\":1}", "__meta": { "type": "json" } }
+            }
+        ]
+    })
+}
+
+fn unmapped_items(unmapped: &[UnmappedItem]) -> Vec<&str> {
+    unmapped.iter().map(|u| u.item.as_str()).collect()
+}
+
+#[test]
+fn postman_socketio_requests_become_connections() {
+    init_test_db();
+
+    let report = import_collection(&postman_collection(), None, &ImportOptions::default()).unwrap();
+
+    assert_eq!(report.format, CollectionFormat::Postman);
+    assert_eq!(report.imported.len(), 1);
+    assert_eq!(report.imported[0].action, ImportAction::Created);
+    assert_eq!(
+        unmapped_items(&report.unmapped),
+        [
+            "Chat / Postman chat / Broken",
+            "Chat / REST health",
+            "Chat / Staging chat"
+        ]
+    );
+
+    let connection_id = report.imported[0].connection_id.unwrap();
+    let stored = db::get_connection_by_id(connection_id).unwrap().unwrap();
+    assert!(secrets::is_encrypted(stored.auth_token.as_deref().unwrap()));
+    let connection = secrets::reveal_connection(stored).unwrap();
+    assert_eq!(connection.url, "http://localhost:3000");
+    assert_eq!(connection.namespace, "/chat");
+    assert_eq!(connection.auth_token.as_deref(), Some("abc"));
+    let options: Value = serde_json::from_str(&connection.options).unwrap();
+    assert_eq!(options, json!({ "extraHeaders": { "X-Team": "qa" } }));
+
+    let events: Vec<String> = db::list_connection_events(connection_id)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.event_name)
+        .collect();
+    assert_eq!(events, ["message", "typing"]);
+
+    let pinned = db::list_pinned_messages(connection_id).unwrap();
+    assert_eq!(pinned.len(), 1);
+    assert_eq!(pinned[0].event_name, "join");
+    assert_eq!(pinned[0].label.as_deref(), Some("Join lobby"));
+    assert_eq!(pinned[0].payload, r#"{"room":"lobby"}"#);
+}
+
+#[test]
+fn firecamp_socketio_requests_become_connections() {
+    init_test_db();
+
+    let report =
+        import_collection(&firecamp_collection(), None, &ImportOptions::default()).unwrap();
+
+    assert_eq!(report.format, CollectionFormat::Firecamp);
+    assert_eq!(report.imported.len(), 1);
+    assert_eq!(
+        unmapped_items(&report.unmapped),
+        ["Games / Firecamp game / admin", "Firecamp GraphQL"]
+    );
+
+    let connection_id = report.imported[0].connection_id.unwrap();
+    let connection = db::get_connection_by_id(connection_id).unwrap().unwrap();
+    assert_eq!(connection.namespace, "/game");
+
+    let events = db::list_connection_events(connection_id).unwrap();
+    assert_eq!(events[0].event_name, "score");
+    assert!(!events[0].is_listening);
+
+    let pinned = db::list_pinned_messages(connection_id).unwrap();
+    assert_eq!(pinned[0].event_name, "move");
+    assert_eq!(pinned[0].payload, r#"{"x":1}"#);
+}
+
+#[test]
+fn unknown_collections_are_rejected() {
+    let error =
+        import_collection(&json!({ "item": [] }), None, &ImportOptions::default()).unwrap_err();
+    assert!(error.contains("Unrecognized"), "{}", error);
+}
@@ -0,0 +1,233 @@
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use serde_json::{Value, json};
+use socket_io_client_lib::event_sink::{MemorySink, SocketNotification};
+use socket_io_client_lib::socket_client::SocketManager;
+use socket_io_client_lib::{db, secrets};
+use socketioxide::SocketIo;
+use socketioxide::extract::{AckSender, Data, SocketRef};
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+static DB_PATH: OnceLock<PathBuf> = OnceLock::new();
+/// Statics are never dropped, so the directory is taken out and dropped at exit
+static DATA_DIR: Mutex<Option<TempDir>> = Mutex::new(None);
+
+unsafe extern "C" {
+    fn atexit(callback: extern "C" fn()) -> std::ffi::c_int;
+}
+
+extern "C" fn remove_data_dir() {
+    if let Ok(mut dir) = DATA_DIR.lock() {
+        dir.take();
+    }
+}
+
+/// Initialize a fresh database and secrets key for this test binary.
+///
+/// `db::init_db` can only be pointed at one file per process, so every test in
+/// a binary shares it and should create its own connections. The temporary
+/// directory holding it is removed when the binary exits.
+pub fn init_test_db() {
+    DB_PATH.get_or_init(|| {
+        let dir = tempfile::Builder::new()
+            .prefix("socket-io-client-test-")
+            .tempdir()
+            .expect("failed to create test data dir");
+        let path = dir.path().join("socket-io-client.db");
+        db::init_db(&path).expect("failed to initialize test database");
+        secrets::init(dir.path()).expect("failed to initialize secrets key");
+
+        *DATA_DIR.lock().unwrap() = Some(dir);
+        // SAFETY: `remove_data_dir` only takes the directory out of its mutex and drops it
+        unsafe { atexit(remove_data_dir) };
+        path
+    });
+}
+
+/// Directory holding the test database and secrets key
+pub fn data_dir() -> PathBuf {
+    init_test_db();
+    DB_PATH
+        .get()
+        .and_then(|path| path.parent())
+        .expect("test database is initialized")
+        .to_path_buf()
+}
+
+/// Start an in-process Socket.IO server on a random port.
+///
+/// - emits `welcome` to every new socket
+/// - `echo`: emits the payload back as `echo`
+/// - `ack`: acknowledges with the payload
+/// - `kick`: disconnects the socket from the server side
+/// - `ping`: emits `pong`, which arrives after the replies to earlier messages
+/// - `header`: emits `header` with the value of the handshake header named in the payload
+pub async fn start_server() -> SocketAddr {
+    let (layer, io) = SocketIo::new_layer();
+    io.ns("/", on_connect);
+
+    let app = axum::Router::new().layer(layer);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("failed to bind test server");
+    let addr = listener.local_addr().expect("failed to get local addr");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.ok();
+    });
+
+    addr
+}
+
+fn on_connect(socket: SocketRef) {
+    socket.emit("welcome", "hello").ok();
+
+    socket.on("echo", |socket: SocketRef, Data::<Value>(data)| {
+        socket.emit("echo", &data).ok();
+    });
+
+    socket.on("ack", |Data::<Value>(data), ack: AckSender| {
+        ack.send(&data).ok();
+    });
+
+    socket.on("kick", |socket: SocketRef| {
+        socket.disconnect().ok();
+    });
+
+    socket.on("ping", |socket: SocketRef| {
+        socket.emit("pong", "").ok();
+    });
+
+    socket.on("header", |socket: SocketRef, Data::<String>(name)| {
+        let value = socket
+            .req_parts()
+            .headers
+            .get(name.as_str())
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        socket.emit("header", &value).ok();
+    });
+}
+
+/// Save a connection profile pointing at `addr`, listening to `events`, that reconnects quickly
+pub fn create_connection(addr: SocketAddr, events: &[&str]) -> i64 {
+    let connection_id = db::create_connection(
+        "test",
+        &format!("http://{}", addr),
+        "/",
+        None,
+        r#"{"transports":["websocket"],"reconnectionDelay":100,"reconnectionDelayMax":200}"#,
+    )
+    .expect("failed to create connection");
+
+    for event_name in events {
+        db::add_connection_event(connection_id, event_name).expect("failed to add event");
+    }
+
+    connection_id
+}
+
+pub fn new_manager() -> (SocketManager, Arc<MemorySink>) {
+    let sink = Arc::new(MemorySink::new());
+    (SocketManager::new(sink.clone()), sink)
+}
+
+pub async fn connect(manager: &SocketManager, connection_id: i64) -> Result<(), String> {
+    let manager = manager.clone();
+    tokio::task::spawn_blocking(move || manager.connect(connection_id))
+        .await
+        .expect("connect task panicked")
+}
+
+/// Poll `condition` until it holds or five seconds pass
+pub async fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    wait_for_with_timeout(Duration::from_secs(5), &mut condition).await
+}
+
+pub async fn wait_for_with_timeout(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    condition()
+}
+
+pub fn count_statuses(sink: &MemorySink, connection_id: i64, status: &str) -> usize {
+    sink.notifications()
+        .iter()
+        .filter(|n| {
+            matches!(n, SocketNotification::Status(p)
+                if p.connection_id == connection_id && p.status == status)
+        })
+        .count()
+}
+
+pub fn find_event(
+    sink: &MemorySink,
+    connection_id: i64,
+    event_name: &str,
+    direction: &str,
+) -> Option<String> {
+    sink.notifications().into_iter().find_map(|n| match n {
+        SocketNotification::Event(p)
+            if p.connection_id == connection_id
+                && p.event_name == event_name
+                && p.direction == direction =>
+        {
+            Some(p.payload)
+        }
+        _ => None,
+    })
+}
+
+/// Bearer token the MCP router is built with in tests
+pub const MCP_TOKEN: &str = "test-token";
+
+/// Send a JSON-RPC request to the MCP router
+pub async fn rpc(app: &Router, method: &str, params: Value) -> Value {
+    let request = Request::post("/message")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", MCP_TOKEN))
+        .body(Body::from(
+            json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string(),
+        ))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+/// Call a tool and parse the JSON text content it returns
+pub async fn call_tool(app: &Router, name: &str, arguments: Value) -> Result<Value, String> {
+    let response = rpc(
+        app,
+        "tools/call",
+        json!({ "name": name, "arguments": arguments }),
+    )
+    .await;
+    let result = &response["result"];
+    let text = result["content"][0]["text"].as_str().unwrap_or_default();
+
+    if result["isError"].as_bool().unwrap_or(false) {
+        Err(text.to_string())
+    } else {
+        Ok(serde_json::from_str(text).unwrap())
+    }
+}
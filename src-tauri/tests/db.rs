@@ -0,0 +1,92 @@
+mod common;
+
+use std::thread;
+
+use common::{create_connection, init_test_db};
+use socket_io_client_lib::db;
+
+#[test]
+fn records_round_trip_through_the_pool() {
+    init_test_db();
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &["news"]);
+    db::set_connection_auto_send(connection_id, true, false).unwrap();
+    let pinned_id = db::add_pinned_message(connection_id, "join", "{}", Some("Join")).unwrap();
+    db::set_pinned_auto_send(pinned_id, true).unwrap();
+
+    let connection = db::get_connection_by_id(connection_id).unwrap().unwrap();
+    assert_eq!(connection.namespace, "/");
+    assert!(connection.auto_send_on_connect);
+    assert!(!connection.auto_send_on_reconnect);
+
+    let events = db::list_connection_events(connection_id).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_name, "news");
+    assert!(events[0].is_listening);
+
+    let pinned = db::list_auto_send_messages(connection_id).unwrap();
+    assert_eq!(pinned.len(), 1);
+    assert_eq!(pinned[0].label.as_deref(), Some("Join"));
+
+    assert!(db::get_connection_by_id(i64::MAX).unwrap().is_none());
+}
+
+#[test]
+fn concurrent_callers_share_the_pool() {
+    init_test_db();
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+
+    let workers: Vec<_> = (0..16)
+        .map(|worker| {
+            thread::spawn(move || {
+                for i in 0..25 {
+                    db::add_emit_log(connection_id, "tick", &format!("{}-{}", worker, i), false)
+                        .unwrap();
+                    db::list_emit_logs(connection_id, 5).unwrap();
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let logs = db::list_emit_logs(connection_id, 1000).unwrap();
+    assert_eq!(logs.len(), 16 * 25);
+    assert!(logs.iter().all(|log| log.event_name == "tick"));
+}
+
+#[test]
+fn timestamps_are_stored_in_one_format_and_filtered_as_text() {
+    init_test_db();
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    for (event_name, timestamp) in [
+        ("early", "2025-01-01T23:30:00+00:00"),
+        // 23:45 UTC, after "early" although it sorts before it as written
+        ("offset", "2025-01-02T01:45:00.5+02:00"),
+        ("late", "2025-01-02 00:30:00"),
+    ] {
+        db::add_event_history(connection_id, event_name, "{}", timestamp, "in", false).unwrap();
+    }
+
+    let mut events = db::list_event_history(connection_id, 10).unwrap();
+    events.sort_by_key(|e| e.id);
+    let timestamps: Vec<&str> = events.iter().map(|e| e.timestamp.as_str()).collect();
+    assert_eq!(
+        timestamps,
+        [
+            "2025-01-01T23:30:00.000Z",
+            "2025-01-01T23:45:00.500Z",
+            "2025-01-02T00:30:00.000Z"
+        ]
+    );
+
+    let filter = db::HistoryFilter {
+        connection_id: Some(connection_id),
+        since: Some("2025-01-02T01:40:00+02:00".to_string()),
+        until: Some("2025-01-02T00:00:00Z".to_string()),
+        ..Default::default()
+    };
+    let events = db::search_event_history("", &filter, 10).unwrap();
+    let names: Vec<&str> = events.iter().map(|e| e.event_name.as_str()).collect();
+    assert_eq!(names, ["offset"]);
+}
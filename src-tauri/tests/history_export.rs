@@ -0,0 +1,162 @@
+mod common;
+
+use common::{connect, create_connection, init_test_db, new_manager};
+use serde_json::{Value, json};
+use socket_io_client_lib::db;
+use socket_io_client_lib::history_export::{
+    HistoryExportFilter, HistoryFormat, export_history, import_history,
+};
+
+/// A connection with a short recorded session, including a payload that needs CSV quoting
+fn recorded_connection() -> i64 {
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    let events = [
+        ("connect", "{}", "2025-01-01T00:00:00+00:00", "in"),
+        (
+            "join",
+            r#"{"room":"a,b"}"#,
+            "2025-01-01T00:00:01+00:00",
+            "out",
+        ),
+        (
+            "message",
+            "\"line one\nline \"two\"\"",
+            "2025-01-01T00:00:02+00:00",
+            "in",
+        ),
+        ("ping", "{}", "2025-01-02T00:00:00+00:00", "out"),
+    ];
+    for (event_name, payload, timestamp, direction) in events {
+        db::add_event_history(
+            connection_id,
+            event_name,
+            payload,
+            timestamp,
+            direction,
+            false,
+        )
+        .unwrap();
+    }
+    connection_id
+}
+
+fn export(filter: &HistoryExportFilter, format: HistoryFormat) -> (usize, Vec<u8>) {
+    let mut out = Vec::new();
+    let count = export_history(filter, format, &mut out).unwrap();
+    (count, out)
+}
+
+#[test]
+fn every_format_round_trips_as_a_read_only_session() {
+    init_test_db();
+    let source = recorded_connection();
+    let filter = HistoryExportFilter {
+        connection_id: source,
+        until: Some("2025-01-01T23:59:59Z".to_string()),
+        ..Default::default()
+    };
+
+    for format in [
+        HistoryFormat::Ndjson,
+        HistoryFormat::Csv,
+        HistoryFormat::Har,
+    ] {
+        let (count, out) = export(&filter, format);
+        assert_eq!(count, 3, "{:?}", format);
+
+        let session = import_history(out.as_slice(), format, None).unwrap();
+        assert_eq!(session.events, 3, "{:?}", format);
+
+        let connection = db::get_connection_by_id(session.connection_id)
+            .unwrap()
+            .unwrap();
+        assert!(connection.read_only);
+        let mut events = db::list_event_history(session.connection_id, 10).unwrap();
+        events.sort_by_key(|e| e.id);
+        let names: Vec<&str> = events.iter().map(|e| e.event_name.as_str()).collect();
+        assert_eq!(names, ["connect", "join", "message"], "{:?}", format);
+        assert_eq!(events[1].payload, r#"{"room":"a,b"}"#);
+        assert_eq!(events[2].payload, "\"line one\nline \"two\"\"");
+        assert_eq!(events[2].direction, "in");
+    }
+}
+
+#[test]
+fn har_export_includes_connection_metadata() {
+    init_test_db();
+    let source = recorded_connection();
+    let filter = HistoryExportFilter {
+        connection_id: source,
+        event_names: vec!["join".to_string(), "ping".to_string()],
+        ..Default::default()
+    };
+
+    let (count, out) = export(&filter, HistoryFormat::Har);
+    assert_eq!(count, 2);
+
+    let har: Value = serde_json::from_slice(&out).unwrap();
+    let entry = &har["log"]["entries"][0];
+    assert_eq!(entry["_connection"]["id"], source);
+    assert_eq!(entry["_connection"]["namespace"], "/");
+    assert_eq!(entry["request"]["url"], "http://127.0.0.1:1");
+    let messages = entry["_webSocketMessages"].as_array().unwrap();
+    assert_eq!(messages[0]["type"], "send");
+    assert_eq!(messages[0]["_event"], "join");
+
+    let session = import_history(out.as_slice(), HistoryFormat::Har, None).unwrap();
+    assert_eq!(session.name, "test (imported)");
+    let connection = db::get_connection_by_id(session.connection_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(connection.url, "http://127.0.0.1:1");
+}
+
+#[test]
+fn browser_har_frames_are_decoded() {
+    init_test_db();
+    let har = json!({
+        "log": {
+            "entries": [{
+                "request": { "url": "wss://example.com/socket.io/?EIO=4&transport=websocket" },
+                "_webSocketMessages": [
+                    { "type": "receive", "time": 1735689600.0, "opcode": 1, "data": "0{\"sid\":\"x\"}" },
+                    { "type": "send", "time": 1735689600.5, "opcode": 1, "data": "42[\"chat\",{\"text\":\"hi\"}]" },
+                    { "type": "receive", "time": 1735689601.0, "opcode": 1, "data": "2" },
+                    { "type": "receive", "time": 1735689601.5, "opcode": 1, "data": "42/admin,7[\"stats\",3]" }
+                ]
+            }]
+        }
+    });
+
+    let session = import_history(
+        har.to_string().as_bytes(),
+        HistoryFormat::Har,
+        Some("browser".to_string()),
+    )
+    .unwrap();
+
+    assert_eq!(session.events, 2);
+    let mut events = db::list_event_history(session.connection_id, 10).unwrap();
+    events.sort_by_key(|e| e.id);
+    assert_eq!(events[0].event_name, "chat");
+    assert_eq!(events[0].payload, r#"{"text":"hi"}"#);
+    assert_eq!(events[0].direction, "out");
+    assert_eq!(events[1].event_name, "stats");
+    assert_eq!(events[1].payload, "3");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn imported_sessions_cannot_connect() {
+    init_test_db();
+    let ndjson = r#"{"event_name":"hello","payload":"{}","timestamp":"2025-01-01T00:00:00Z","direction":"in"}"#;
+    let session = import_history(ndjson.as_bytes(), HistoryFormat::Ndjson, None).unwrap();
+
+    let (manager, _sink) = new_manager();
+    let error = connect(&manager, session.connection_id).await.unwrap_err();
+    assert!(error.contains("read-only"), "{}", error);
+
+    let invalid =
+        r#"{"event_name":"hello","payload":"{}","timestamp":"yesterday","direction":"in"}"#;
+    let error = import_history(invalid.as_bytes(), HistoryFormat::Ndjson, None).unwrap_err();
+    assert!(error.starts_with("Line 1:"), "{}", error);
+}
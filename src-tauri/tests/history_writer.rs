@@ -0,0 +1,32 @@
+mod common;
+
+use common::{create_connection, init_test_db};
+use socket_io_client_lib::{db, history_writer};
+
+#[test]
+fn queued_events_are_written_in_batches() {
+    init_test_db();
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+
+    for n in 0..1200 {
+        history_writer::record(
+            connection_id,
+            "tick",
+            &n.to_string(),
+            "2025-01-01T00:00:00+00:00",
+            "in",
+        );
+    }
+    history_writer::flush();
+
+    let history = db::list_event_history(connection_id, 2000).unwrap();
+    assert_eq!(history.len(), 1200);
+
+    let stats = history_writer::stats().unwrap();
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.dropped, 0);
+    assert_eq!(stats.failed, 0);
+    assert!(stats.written >= 1200);
+    // At most 500 events per transaction
+    assert!(stats.batches >= 3);
+}
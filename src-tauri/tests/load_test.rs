@@ -0,0 +1,69 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::{create_connection, init_test_db, start_server};
+use socket_io_client_lib::load_test::{LatencySummary, LoadStep, LoadTest, LoadTestConfig};
+
+#[test]
+fn latency_percentiles_use_nearest_rank() {
+    let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+    let summary = LatencySummary::from_samples(&samples);
+
+    assert_eq!(summary.count, 100);
+    assert_eq!(summary.min, 1.0);
+    assert_eq!(summary.max, 100.0);
+    assert_eq!(summary.mean, 50.5);
+    assert_eq!(summary.p50, 50.0);
+    assert_eq!(summary.p95, 95.0);
+    assert_eq!(summary.p99, 99.0);
+
+    assert_eq!(LatencySummary::from_samples(&[]).count, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn virtual_clients_report_ack_latency() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &[]);
+
+    let test = Arc::new(
+        LoadTest::new(LoadTestConfig {
+            connection_id,
+            clients: 5,
+            ramp_up_ms: 100,
+            duration_ms: 500,
+            think_time_ms: 50,
+            scenario: Some(vec![
+                LoadStep {
+                    event: "echo".to_string(),
+                    payload: r#"{"n":1}"#.to_string(),
+                    ack: false,
+                },
+                LoadStep {
+                    event: "ack".to_string(),
+                    payload: r#"{"n":2}"#.to_string(),
+                    ack: true,
+                },
+            ]),
+            ack: false,
+            ack_timeout_ms: None,
+            workers: Some(2),
+        })
+        .unwrap(),
+    );
+
+    let report = tokio::task::spawn_blocking(move || test.run())
+        .await
+        .unwrap();
+
+    assert_eq!(report.clients, 5);
+    assert_eq!(report.connected, 5);
+    assert_eq!(report.connect_failures, 0);
+    assert_eq!(report.connect_time.count, 5);
+    assert!(report.messages_sent >= 10);
+    assert!(report.acks_expected > 0);
+    assert_eq!(report.acks_received, report.acks_expected);
+    assert_eq!(report.ack_latency.count as u64, report.acks_received);
+    assert_eq!(report.disconnects, 0);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::{MCP_TOKEN, call_tool, create_connection, init_test_db, new_manager, rpc};
+use serde_json::{Value, json};
+use socket_io_client_lib::redaction::{self, REDACTED};
+use socket_io_client_lib::retention::{RetentionPolicy, RetentionSettings, prune};
+use socket_io_client_lib::{db, mcp_audit, mcp_server};
+
+#[tokio::test]
+async fn tool_calls_are_audited_and_pruned() {
+    init_test_db();
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    redaction::set_connection_redaction(connection_id, true).unwrap();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+
+    rpc(
+        &app,
+        "initialize",
+        json!({ "clientInfo": { "name": "test-client", "version": "1.2.3" } }),
+    )
+    .await;
+    call_tool(
+        &app,
+        "get_connection_status",
+        json!({ "connection_id": connection_id, "token": "abc" }),
+    )
+    .await
+    .unwrap();
+    call_tool(&app, "unknown_tool", json!({}))
+        .await
+        .unwrap_err();
+
+    let entries = mcp_audit::list_mcp_audit_log(None, None, None).unwrap();
+    assert_eq!(entries.len(), 2);
+    let (failed, succeeded) = (&entries[0], &entries[1]);
+
+    assert_eq!(succeeded.tool, "get_connection_status");
+    assert_eq!(succeeded.client_name.as_deref(), Some("test-client"));
+    assert_eq!(succeeded.client_version.as_deref(), Some("1.2.3"));
+    let arguments: Value = serde_json::from_str(&succeeded.arguments).unwrap();
+    assert_eq!(
+        arguments,
+        json!({ "connection_id": connection_id, "token": REDACTED })
+    );
+    assert!(succeeded.result.is_some() && succeeded.error.is_none());
+    assert!(failed.result.is_none() && failed.error.is_some());
+
+    let filtered =
+        mcp_audit::list_mcp_audit_log(Some("unknown_tool".to_string()), None, None).unwrap();
+    assert_eq!(filtered.len(), 1);
+    let older = mcp_audit::list_mcp_audit_log(None, Some(failed.id), None).unwrap();
+    assert_eq!(older.len(), 1);
+    assert_eq!(older[0].id, succeeded.id);
+
+    let settings = RetentionSettings {
+        mcp_audit: RetentionPolicy {
+            max_rows: Some(1),
+            max_age_days: None,
+        },
+        ..Default::default()
+    };
+    assert_eq!(prune(&settings).unwrap().deleted_mcp_audit, 1);
+    let remaining = db::list_mcp_audit(None, None, 10).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, failed.id);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common::{MCP_TOKEN, call_tool, create_connection, init_test_db, new_manager, wait_for};
+use serde_json::json;
+use socket_io_client_lib::event_sink::{MemorySink, SocketNotification};
+use socket_io_client_lib::mcp_policy::{self, EventRules, McpPolicy};
+use socket_io_client_lib::mcp_server;
+use socket_io_client_lib::socket_client::SocketManager;
+
+#[test]
+fn policy_checks_tools_connections_and_events() {
+    let policy = McpPolicy {
+        allowed_connections: Some(vec![1]),
+        events: HashMap::from([(
+            1,
+            EventRules {
+                allow: vec!["chat".to_string(), "reset".to_string()],
+                deny: vec!["reset".to_string()],
+            },
+        )]),
+        ..Default::default()
+    };
+    assert!(policy.check("connect", Some(1), None).is_ok());
+    assert!(policy.check("connect", Some(2), None).is_err());
+    assert!(policy.check("send_message", Some(1), Some("chat")).is_ok());
+    assert!(
+        policy
+            .check("send_message", Some(1), Some("reset"))
+            .is_err()
+    );
+    assert!(
+        policy
+            .check("send_message", Some(1), Some("other"))
+            .is_err()
+    );
+    assert!(policy.check("send_message", Some(2), Some("other")).is_ok());
+
+    let read_only = McpPolicy {
+        read_only: true,
+        ..Default::default()
+    };
+    let error = read_only
+        .check("send_message", Some(1), Some("chat"))
+        .unwrap_err();
+    assert!(error.contains("read-only"), "{}", error);
+    assert!(read_only.check("list_connections", None, None).is_ok());
+}
+
+#[tokio::test]
+async fn write_tools_follow_the_saved_policy() {
+    init_test_db();
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    let sink = Arc::new(MemorySink::with_confirmer());
+    let app = mcp_server::router(SocketManager::new(sink.clone()), MCP_TOKEN.to_string());
+
+    mcp_policy::save_policy(&McpPolicy {
+        events: HashMap::from([(
+            connection_id,
+            EventRules {
+                deny: vec!["reset".to_string()],
+                ..Default::default()
+            },
+        )]),
+        confirm_writes: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    // Denied events fail before anyone is asked
+    let error = call_tool(
+        &app,
+        "send_message",
+        json!({ "connection_id": connection_id, "event_name": "reset", "payload": "{}" }),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.contains("MCP policy"), "{}", error);
+    assert!(sink.notifications().is_empty());
+
+    let call = tokio::spawn({
+        let app = app.clone();
+        async move {
+            call_tool(
+                &app,
+                "add_event_listener",
+                json!({ "connection_id": connection_id, "event_name": "chat" }),
+            )
+            .await
+        }
+    });
+    let request_id = || {
+        sink.notifications().into_iter().find_map(|n| match n {
+            SocketNotification::McpConfirmation(p) if p.connection_id == Some(connection_id) => {
+                Some(p.request_id)
+            }
+            _ => None,
+        })
+    };
+    assert!(wait_for(|| request_id().is_some()).await);
+    assert!(mcp_policy::resolve_confirmation(request_id().unwrap(), false).unwrap());
+
+    let error = call.await.unwrap().unwrap_err();
+    assert!(error.contains("denied by the user"), "{}", error);
+    assert!(!mcp_policy::resolve_confirmation(request_id().unwrap(), true).unwrap());
+
+    // Without a desktop app to answer, confirmations fail at once
+    let (headless, _) = new_manager();
+    let app = mcp_server::router(headless, MCP_TOKEN.to_string());
+    let error = call_tool(
+        &app,
+        "add_event_listener",
+        json!({ "connection_id": connection_id, "event_name": "chat" }),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.contains("not attached"), "{}", error);
+
+    mcp_policy::save_policy(&McpPolicy::default()).unwrap();
+}
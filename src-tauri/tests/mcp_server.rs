@@ -0,0 +1,537 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use common::{
+    MCP_TOKEN, call_tool, create_connection, init_test_db, new_manager, rpc, start_server, wait_for,
+};
+use serde_json::{Value, json};
+use socket_io_client_lib::{db, mcp_server, redaction, secrets};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn requests_need_the_token_and_a_local_origin() {
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+    let status = |authorization: Option<&str>, origin: Option<&str>| {
+        let mut request =
+            Request::post("/message").header(header::CONTENT_TYPE, "application/json");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        if let Some(origin) = origin {
+            request = request.header(header::ORIGIN, origin);
+        }
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }).to_string();
+        let app = app.clone();
+        async move {
+            app.oneshot(request.body(Body::from(body)).unwrap())
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    assert_eq!(status(None, None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        status(Some("Bearer wrong-token"), None).await,
+        StatusCode::UNAUTHORIZED
+    );
+    let bearer = format!("Bearer {}", MCP_TOKEN);
+    assert_eq!(
+        status(Some(&bearer), Some("https://example.com")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(Some(&bearer), Some("http://localhost:6274")).await,
+        StatusCode::OK
+    );
+    assert_eq!(status(Some(&bearer), None).await, StatusCode::OK);
+}
+
+#[test]
+fn auth_token_is_kept_until_rotated() {
+    init_test_db();
+    let token = mcp_server::auth_token().unwrap();
+    assert_eq!(mcp_server::auth_token().unwrap(), token);
+
+    let rotated = mcp_server::rotate_auth_token().unwrap();
+    assert_ne!(rotated, token);
+    assert_eq!(mcp_server::auth_token().unwrap(), rotated);
+}
+
+#[tokio::test]
+async fn initialize_reports_server_info() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+
+    let response = rpc(
+        &app,
+        "initialize",
+        json!({ "protocolVersion": "2024-11-05" }),
+    )
+    .await;
+
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(
+        response["result"]["serverInfo"]["name"],
+        "socket-io-client-mcp"
+    );
+}
+
+#[tokio::test]
+async fn tools_list_includes_all_tools() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+
+    let response = rpc(&app, "tools/list", json!({})).await;
+
+    let names: Vec<&str> = response["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    for expected in [
+        "list_connections",
+        "get_connection_status",
+        "connect",
+        "disconnect",
+        "send_message",
+        "get_recent_events",
+        "list_event_history",
+        "list_emit_logs",
+        "search_event_history",
+        "list_event_listeners",
+        "add_event_listener",
+        "remove_event_listener",
+    ] {
+        assert!(names.contains(&expected), "missing tool {}", expected);
+    }
+}
+
+#[tokio::test]
+async fn unknown_method_is_an_error() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+
+    let response = rpc(&app, "does/not/exist", json!({})).await;
+
+    assert_eq!(response["error"]["code"], -32601);
+    assert!(response.get("result").is_none());
+}
+
+#[tokio::test]
+async fn tool_errors_are_reported_in_content() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+
+    let result = call_tool(
+        &app,
+        "send_message",
+        json!({ "event_name": "x", "payload": "{}" }),
+    )
+    .await;
+    assert_eq!(
+        result,
+        Err("connection_id is required when no active connection is selected".to_string())
+    );
+
+    let result = call_tool(&app, "no_such_tool", json!({})).await;
+    assert_eq!(result, Err("Unknown tool: no_such_tool".to_string()));
+}
+
+#[tokio::test]
+async fn search_event_history_matches_payload_text() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    for (event_name, payload, timestamp, direction) in [
+        (
+            "order",
+            r#"{"orderId":12345}"#,
+            "2025-01-01T10:00:00+00:00",
+            "in",
+        ),
+        (
+            "order",
+            r#"{"orderId":67890}"#,
+            "2025-01-02T10:00:00+00:00",
+            "in",
+        ),
+        (
+            "cancel",
+            r#"{"orderId":12345}"#,
+            "2025-01-03T10:00:00.5+00:00",
+            "out",
+        ),
+    ] {
+        db::add_event_history(
+            connection_id,
+            event_name,
+            payload,
+            timestamp,
+            direction,
+            false,
+        )
+        .unwrap();
+    }
+
+    let search = |arguments: Value| call_tool(&app, "search_event_history", arguments);
+    let names = |result: &Value| -> Vec<String> {
+        result["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["event_name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let result = search(json!({ "query": "12345", "connection_id": connection_id }))
+        .await
+        .unwrap();
+    assert_eq!(names(&result), ["cancel", "order"]);
+
+    let result =
+        search(json!({ "query": "123*", "connection_id": connection_id, "direction": "in" }))
+            .await
+            .unwrap();
+    assert_eq!(names(&result), ["order"]);
+
+    let result = search(json!({
+        "connection_id": connection_id,
+        "since": "2025-01-02T00:00:00Z",
+        "until": "2025-01-03T10:00:00Z"
+    }))
+    .await
+    .unwrap();
+    assert_eq!(names(&result), ["order"]);
+    assert_eq!(result["items"][0]["payload"], r#"{"orderId":67890}"#);
+
+    let result = search(json!({ "query": "\"orderId\" OR", "connection_id": connection_id }))
+        .await
+        .unwrap();
+    assert_eq!(result["items"], json!([]));
+
+    let result = search(json!({ "direction": "sideways" })).await;
+    assert!(result.unwrap_err().starts_with("Invalid direction"));
+}
+
+#[tokio::test]
+async fn history_pages_follow_cursors() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    for n in 0..5 {
+        let timestamp = format!("2025-01-01T00:00:0{}+00:00", n);
+        let direction = if n % 2 == 0 { "in" } else { "out" };
+        db::add_event_history(
+            connection_id,
+            "tick",
+            &n.to_string(),
+            &timestamp,
+            direction,
+            false,
+        )
+        .unwrap();
+        db::add_emit_log(connection_id, "tick", &n.to_string(), false).unwrap();
+    }
+
+    let page = |arguments: Value| call_tool(&app, "list_event_history", arguments);
+    let payloads = |result: &Value| -> Vec<String> {
+        result["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["payload"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let first = page(json!({ "connection_id": connection_id, "limit": 2 }))
+        .await
+        .unwrap();
+    assert_eq!(payloads(&first), ["4", "3"]);
+    assert_eq!(first["hasMore"], true);
+
+    let second = page(json!({
+        "connection_id": connection_id,
+        "limit": 2,
+        "before_id": first["olderCursor"]
+    }))
+    .await
+    .unwrap();
+    assert_eq!(payloads(&second), ["2", "1"]);
+
+    let last = page(json!({
+        "connection_id": connection_id,
+        "limit": 2,
+        "before_id": second["olderCursor"]
+    }))
+    .await
+    .unwrap();
+    assert_eq!(payloads(&last), ["0"]);
+    assert_eq!(last["hasMore"], false);
+
+    // Paging forward returns the rows right after the cursor, still newest first
+    let newer = page(json!({
+        "connection_id": connection_id,
+        "limit": 2,
+        "after_id": last["newerCursor"]
+    }))
+    .await
+    .unwrap();
+    assert_eq!(payloads(&newer), ["2", "1"]);
+    assert_eq!(newer["hasMore"], true);
+
+    let filtered = page(json!({
+        "connection_id": connection_id,
+        "direction": "out",
+        "until": "2025-01-01T00:00:02Z"
+    }))
+    .await
+    .unwrap();
+    assert_eq!(payloads(&filtered), ["1"]);
+
+    let logs = call_tool(
+        &app,
+        "list_emit_logs",
+        json!({ "connection_id": connection_id, "limit": 3 }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(payloads(&logs), ["4", "3", "2"]);
+    assert_eq!(logs["hasMore"], true);
+}
+
+#[tokio::test]
+async fn tool_results_are_redacted() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    redaction::set_connection_redaction(connection_id, true).unwrap();
+    // Written directly, as history saved before redaction was enabled
+    db::add_event_history(
+        connection_id,
+        "login",
+        r#"{"user":"amy","password":"hunter2"}"#,
+        "2025-01-01T00:00:00+00:00",
+        "out",
+        false,
+    )
+    .unwrap();
+
+    let page = call_tool(
+        &app,
+        "list_event_history",
+        json!({ "connection_id": connection_id }),
+    )
+    .await
+    .unwrap();
+
+    let payload: Value =
+        serde_json::from_str(page["items"][0]["payload"].as_str().unwrap()).unwrap();
+    assert_eq!(
+        payload,
+        json!({ "user": "amy", "password": redaction::REDACTED })
+    );
+}
+
+#[tokio::test]
+async fn resources_expose_connections_history_and_pinned() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+    let auth_token = secrets::seal_auth_token(Some("token-1"), None).unwrap();
+    let connection_id = db::create_connection(
+        "resources",
+        "http://localhost:3000",
+        "/",
+        auth_token.as_deref(),
+        "{}",
+    )
+    .unwrap();
+    redaction::set_connection_redaction(connection_id, true).unwrap();
+    db::add_connection_event(connection_id, "chat").unwrap();
+    db::add_pinned_message(connection_id, "chat", r#"{"text":"hi"}"#, Some("greeting")).unwrap();
+    db::add_event_history(
+        connection_id,
+        "login",
+        r#"{"password":"hunter2"}"#,
+        "2025-01-01T00:00:00+00:00",
+        "out",
+        false,
+    )
+    .unwrap();
+    let base = format!("socketio://connections/{}", connection_id);
+
+    let listed = rpc(&app, "resources/list", json!({})).await;
+    let uris: Vec<&str> = listed["result"]["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|r| r["uri"].as_str())
+        .filter(|uri| uri.starts_with(&base))
+        .collect();
+    assert_eq!(
+        uris,
+        [
+            base.clone(),
+            format!("{}/history", base),
+            format!("{}/pinned", base)
+        ]
+    );
+    let templates = rpc(&app, "resources/templates/list", json!({})).await;
+    assert_eq!(
+        templates["result"]["resourceTemplates"][0]["uriTemplate"],
+        "socketio://connections/{id}"
+    );
+
+    let read = |suffix: &str| {
+        let uri = format!("{}{}", base, suffix);
+        let app = app.clone();
+        async move {
+            let response = rpc(&app, "resources/read", json!({ "uri": uri })).await;
+            let text = response["result"]["contents"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        }
+    };
+    let connection = read("").await;
+    assert_eq!(connection["connection"]["auth_token"], secrets::SECRET_MASK);
+    assert_eq!(connection["events"][0]["event_name"], "chat");
+    let history = read("/history").await;
+    assert_eq!(
+        history["items"][0]["payload"],
+        format!(r#"{{"password":"{}"}}"#, redaction::REDACTED)
+    );
+    let pinned = read("/pinned").await;
+    assert_eq!(pinned["pinned"][0]["label"], "greeting");
+
+    let missing = rpc(
+        &app,
+        "resources/read",
+        json!({ "uri": "socketio://connections/0/history" }),
+    )
+    .await;
+    assert_eq!(missing["error"]["code"], -32002);
+}
+
+#[tokio::test]
+async fn listeners_are_persisted() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &[]);
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager, MCP_TOKEN.to_string());
+
+    call_tool(
+        &app,
+        "add_event_listener",
+        json!({ "connection_id": connection_id, "event_name": "news" }),
+    )
+    .await
+    .unwrap();
+
+    let listeners = call_tool(
+        &app,
+        "list_event_listeners",
+        json!({ "connection_id": connection_id }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        listeners["listeners"],
+        json!([{ "event_name": "news", "persisted": true }])
+    );
+
+    call_tool(
+        &app,
+        "remove_event_listener",
+        json!({ "connection_id": connection_id, "event_name": "news" }),
+    )
+    .await
+    .unwrap();
+
+    let events = socket_io_client_lib::db::list_connection_events(connection_id).unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(!events[0].is_listening);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_send_and_read_events() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &["echo"]);
+    let (manager, _sink) = new_manager();
+    let app = mcp_server::router(manager.clone(), MCP_TOKEN.to_string());
+
+    let connections = call_tool(&app, "list_connections", json!({}))
+        .await
+        .unwrap();
+    assert!(
+        connections["connections"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c["id"] == connection_id)
+    );
+
+    call_tool(&app, "connect", json!({ "connection_id": connection_id }))
+        .await
+        .unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+
+    let status = call_tool(&app, "get_connection_status", json!({}))
+        .await
+        .unwrap();
+    assert_eq!(status["current_connection_id"], connection_id);
+    assert_eq!(status["status"], "connected");
+
+    call_tool(
+        &app,
+        "send_message",
+        json!({ "event_name": "echo", "payload": "{\"n\":1}" }),
+    )
+    .await
+    .unwrap();
+    assert!(
+        wait_for(|| {
+            manager
+                .list_buffered_events(connection_id, 10)
+                .iter()
+                .any(|e| e.event_name == "echo" && e.direction == "in")
+        })
+        .await
+    );
+
+    let events = call_tool(
+        &app,
+        "get_recent_events",
+        json!({ "connection_id": connection_id, "limit": 10 }),
+    )
+    .await
+    .unwrap();
+    let events = events["events"].as_array().unwrap();
+    assert_eq!(events[0]["direction"], "in");
+    assert_eq!(events[0]["payload"], "{\"n\":1}");
+    assert_eq!(events[1]["direction"], "out");
+
+    call_tool(
+        &app,
+        "disconnect",
+        json!({ "connection_id": connection_id }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        manager.get_status_for_connection(connection_id),
+        "disconnected"
+    );
+}
@@ -0,0 +1,360 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::{Body, BodyDataStream};
+use axum::http::{HeaderMap, Method, Request, StatusCode, header};
+use common::{MCP_TOKEN, create_connection, init_test_db, new_manager, rpc, start_server};
+use serde_json::{Value, json};
+use socket_io_client_lib::mcp_server;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+
+const SESSION_ID: &str = "mcp-session-id";
+
+fn app() -> Router {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    mcp_server::router(manager, MCP_TOKEN.to_string())
+}
+
+async fn send(
+    app: &Router,
+    method: Method,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: Option<Value>,
+) -> (StatusCode, HeaderMap, Option<Value>) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(path)
+        .header(header::AUTHORIZATION, format!("Bearer {}", MCP_TOKEN))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCEPT, "application/json, text/event-stream");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).ok();
+    (parts.status, parts.headers, json)
+}
+
+/// Reads the frames of an SSE response
+struct SseReader {
+    stream: BodyDataStream,
+    buffer: String,
+}
+
+impl SseReader {
+    async fn open(app: &Router, path: &str, headers: &[(&str, &str)]) -> Self {
+        let mut request =
+            Request::get(path).header(header::AUTHORIZATION, format!("Bearer {}", MCP_TOKEN));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        Self {
+            stream: response.into_body().into_data_stream(),
+            buffer: String::new(),
+        }
+    }
+
+    /// Data of the next frame with the given event name
+    async fn next_data(&mut self, event: &str) -> String {
+        let marker = format!("event: {}\ndata: ", event);
+        loop {
+            if let Some(start) = self.buffer.find(&marker)
+                && let Some(end) = self.buffer[start + marker.len()..].find('\n')
+            {
+                let data = self.buffer[start + marker.len()..][..end].to_string();
+                self.buffer.drain(..start + marker.len() + end);
+                return data;
+            }
+            let chunk = tokio::time::timeout(Duration::from_secs(5), self.stream.next())
+                .await
+                .expect("timed out waiting for an SSE frame")
+                .unwrap()
+                .unwrap();
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
+
+fn request(id: i64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[tokio::test]
+async fn streamable_http_sessions() {
+    let app = app();
+
+    let initialize = request(
+        1,
+        "initialize",
+        json!({ "protocolVersion": "2025-03-26", "clientInfo": { "name": "test" } }),
+    );
+    let (status, headers, body) =
+        send(&app, Method::POST, "/mcp", &[], Some(initialize.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["result"]["protocolVersion"], "2025-03-26");
+    let session = headers[SESSION_ID].to_str().unwrap().to_string();
+
+    // Unknown versions get the newest supported one
+    let mut newer = initialize;
+    newer["params"]["protocolVersion"] = json!("2099-01-01");
+    let (_, _, body) = send(&app, Method::POST, "/mcp", &[], Some(newer)).await;
+    assert_eq!(body.unwrap()["result"]["protocolVersion"], "2025-06-18");
+
+    let ping = request(2, "ping", json!({}));
+    let (status, _, _) = send(&app, Method::POST, "/mcp", &[], Some(ping.clone())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        "/mcp",
+        &[(SESSION_ID, "unknown")],
+        Some(ping.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        "/mcp",
+        &[
+            (SESSION_ID, &session),
+            ("mcp-protocol-version", "1999-01-01"),
+        ],
+        Some(ping.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/mcp",
+        &[(SESSION_ID, &session)],
+        Some(notification),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body, None);
+
+    let batch = json!([ping, request(3, "tools/list", json!({}))]);
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/mcp",
+        &[
+            (SESSION_ID, &session),
+            ("mcp-protocol-version", "2025-03-26"),
+        ],
+        Some(batch),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let body = body.unwrap();
+    let responses = body.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[1]["id"], 3);
+    assert!(responses[1]["result"]["tools"].is_array());
+
+    let (status, _, _) = send(
+        &app,
+        Method::DELETE,
+        "/mcp",
+        &[(SESSION_ID, &session)],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        "/mcp",
+        &[(SESSION_ID, &session)],
+        Some(request(4, "ping", json!({}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn legacy_sse_responses_arrive_on_the_stream() {
+    let app = app();
+    let mut stream = SseReader::open(&app, "/sse", &[]).await;
+
+    let endpoint = stream.next_data("endpoint").await;
+    assert!(endpoint.starts_with("/message?sessionId="), "{}", endpoint);
+
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        &endpoint,
+        &[],
+        Some(request(7, "ping", json!({}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body, None);
+
+    let message: Value = serde_json::from_str(&stream.next_data("message").await).unwrap();
+    assert_eq!(message["id"], 7);
+    assert_eq!(message["result"], json!({}));
+
+    // Closing the stream ends the session
+    drop(stream);
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        &endpoint,
+        &[],
+        Some(request(8, "ping", json!({}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// Start a Streamable HTTP session and return its id
+async fn initialize(app: &Router) -> String {
+    let initialize = request(1, "initialize", json!({ "protocolVersion": "2025-06-18" }));
+    let (_, headers, _) = send(app, Method::POST, "/mcp", &[], Some(initialize)).await;
+    headers[SESSION_ID].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn sessions_start_after_initialize_and_are_capped() {
+    let app = app();
+
+    // A malformed initialize is answered without a session
+    let invalid = json!({ "id": 1, "method": "initialize", "params": {} });
+    let (status, headers, body) = send(&app, Method::POST, "/mcp", &[], Some(invalid)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["error"]["code"], -32600);
+    assert!(!headers.contains_key(SESSION_ID));
+
+    let mut sessions = Vec::new();
+    for _ in 0..mcp_server::MAX_SESSIONS {
+        sessions.push(initialize(&app).await);
+    }
+    let initialize_request = request(1, "initialize", json!({}));
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        "/mcp",
+        &[],
+        Some(initialize_request.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    send(
+        &app,
+        Method::DELETE,
+        "/mcp",
+        &[(SESSION_ID, &sessions[0])],
+        None,
+    )
+    .await;
+    let (status, headers, _) =
+        send(&app, Method::POST, "/mcp", &[], Some(initialize_request)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(headers.contains_key(SESSION_ID));
+}
+
+#[tokio::test]
+async fn subscriptions_need_a_session_and_a_history_uri() {
+    let app = app();
+    let uri = "socketio://connections/1/history";
+
+    let response = rpc(&app, "resources/subscribe", json!({ "uri": uri })).await;
+    assert_eq!(response["error"]["code"], -32602);
+
+    let session = initialize(&app).await;
+    for (uri, ok) in [(uri, true), ("socketio://connections/1", false)] {
+        let (_, _, body) = send(
+            &app,
+            Method::POST,
+            "/mcp",
+            &[(SESSION_ID, &session)],
+            Some(request(2, "resources/subscribe", json!({ "uri": uri }))),
+        )
+        .await;
+        assert_eq!(body.unwrap()["result"].is_object(), ok, "{}", uri);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subscribed_sessions_are_notified_of_incoming_events() {
+    let app = app();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &[]);
+    let session = initialize(&app).await;
+    let headers = [(SESSION_ID, session.as_str())];
+    let mut stream = SseReader::open(&app, "/mcp", &headers).await;
+
+    let uri = format!("socketio://connections/{}/history", connection_id);
+    let subscribe = request(2, "resources/subscribe", json!({ "uri": uri }));
+    send(&app, Method::POST, "/mcp", &headers, Some(subscribe)).await;
+    let connect = request(
+        3,
+        "tools/call",
+        json!({ "name": "connect", "arguments": { "connection_id": connection_id } }),
+    );
+    let (_, _, body) = send(&app, Method::POST, "/mcp", &headers, Some(connect)).await;
+    assert!(body.unwrap()["result"]["isError"].is_null());
+
+    // Connecting records an incoming `connect` event
+    let notification: Value = serde_json::from_str(&stream.next_data("message").await).unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], uri);
+}
+
+#[tokio::test]
+async fn stdio_answers_one_line_per_request() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+    let input = [
+        request(1, "initialize", json!({ "protocolVersion": "2025-06-18" })).to_string(),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }).to_string(),
+        String::new(),
+        json!([
+            request(2, "ping", json!({})),
+            request(3, "tools/list", json!({}))
+        ])
+        .to_string(),
+        "not json".to_string(),
+    ]
+    .join("\n");
+    let mut output = Vec::new();
+
+    mcp_server::serve_stdio(manager, input.as_bytes(), &mut output)
+        .await
+        .unwrap();
+
+    let lines: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["result"]["protocolVersion"], "2025-06-18");
+    assert_eq!(lines[1][1]["id"], 3);
+    assert_eq!(lines[2]["error"]["code"], -32700);
+}
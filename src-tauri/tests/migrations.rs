@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+use socket_io_client_lib::db;
+
+/// Schema written by releases before migrations were versioned
+const LEGACY_SCHEMA: &str = "
+    CREATE TABLE connections (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        url TEXT NOT NULL,
+        namespace TEXT DEFAULT '/',
+        auth_token TEXT,
+        options TEXT DEFAULT '{}',
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE pinned_messages (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        connection_id INTEGER NOT NULL,
+        event_name TEXT NOT NULL,
+        payload TEXT DEFAULT '{}',
+        label TEXT,
+        sort_order INTEGER DEFAULT 0,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE event_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        connection_id INTEGER NOT NULL,
+        event_name TEXT NOT NULL,
+        payload TEXT DEFAULT '{}',
+        timestamp TEXT NOT NULL,
+        direction TEXT NOT NULL CHECK(direction IN ('in', 'out')),
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    INSERT INTO connections (name, url) VALUES ('legacy', 'http://localhost:3000');
+    INSERT INTO pinned_messages (connection_id, event_name, payload) VALUES (1, 'join', '{}');
+    INSERT INTO event_history (connection_id, event_name, payload, timestamp, direction)
+        VALUES (1, 'order', '{\"orderId\":12345}', '2025-01-01T00:00:00+00:00', 'in');
+    INSERT INTO event_history (connection_id, event_name, payload, timestamp, direction)
+        VALUES (1, 'login', '{\"token\":\"[REDACTED]\"}', '2025-01-01T00:00:01+00:00', 'out');
+";
+
+fn temp_db(name: &str) -> Connection {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "socket-io-client-migration-{}-{}-{}.db",
+        name,
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    Connection::open(path).unwrap()
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> bool {
+    let sql = format!(
+        "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1",
+        table
+    );
+    conn.query_row(&sql, [column], |row| row.get::<_, i64>(0))
+        .unwrap()
+        > 0
+}
+
+#[test]
+fn legacy_database_migrates_forward() {
+    let mut conn = temp_db("legacy");
+    conn.execute_batch(LEGACY_SCHEMA).unwrap();
+    assert_eq!(db::schema_version(&conn).unwrap(), 0);
+
+    db::migrate(&mut conn).unwrap();
+
+    assert_eq!(db::schema_version(&conn).unwrap(), db::SCHEMA_VERSION);
+    assert!(has_column(&conn, "pinned_messages", "auto_send"));
+    assert!(has_column(&conn, "connections", "auto_send_on_connect"));
+    assert!(has_column(&conn, "connections", "auto_send_on_reconnect"));
+    assert!(has_column(&conn, "connections", "read_only"));
+
+    // Existing rows survive and old history is searchable
+    let name: String = conn
+        .query_row("SELECT name FROM connections WHERE id = 1", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(name, "legacy");
+    let matches: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM event_history_fts WHERE event_history_fts MATCH '12345'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(matches, 1);
+    let timestamp: String = conn
+        .query_row(
+            "SELECT timestamp FROM event_history WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(timestamp, "2025-01-01T00:00:00.000Z");
+    let flags: Vec<bool> = conn
+        .prepare("SELECT redacted FROM event_history ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(flags, [false, true]);
+
+    // Running again is a no-op
+    db::migrate(&mut conn).unwrap();
+    assert_eq!(db::schema_version(&conn).unwrap(), db::SCHEMA_VERSION);
+}
+
+#[test]
+fn empty_database_gets_current_schema() {
+    let mut conn = temp_db("empty");
+
+    db::migrate(&mut conn).unwrap();
+
+    assert_eq!(db::schema_version(&conn).unwrap(), db::SCHEMA_VERSION);
+    for table in [
+        "connections",
+        "connection_events",
+        "emit_logs",
+        "pinned_messages",
+        "event_history",
+        "event_history_fts",
+        "app_state",
+    ] {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = ?1)",
+                [table],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists, "missing table {}", table);
+    }
+}
+
+#[test]
+fn newer_database_is_refused() {
+    let mut conn = temp_db("newer");
+    conn.pragma_update(None, "user_version", db::SCHEMA_VERSION + 1)
+        .unwrap();
+
+    let error = db::migrate(&mut conn).unwrap_err().to_string();
+
+    assert!(error.contains("created by a newer version"), "{}", error);
+    assert_eq!(db::schema_version(&conn).unwrap(), db::SCHEMA_VERSION + 1);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{
+    connect, create_connection, find_event, init_test_db, new_manager, wait_for,
+    wait_for_with_timeout,
+};
+use serde_json::json;
+use socket_io_client_lib::event_sink::{MemorySink, SocketNotification};
+use socket_io_client_lib::mock_server::{MockNamespace, MockRule, MockServer, MockServerConfig};
+use socket_io_client_lib::{db, history_writer};
+
+/// Start a mock server recording into a fresh connection profile
+async fn start_mock(rules: Vec<MockRule>) -> (MockServer, i64, Arc<MemorySink>) {
+    let recording_id = create_connection(SocketAddr::from(([127, 0, 0, 1], 0)), &[]);
+    let sink = Arc::new(MemorySink::new());
+    let server = MockServer::start(
+        MockServerConfig {
+            port: 0,
+            connection_id: recording_id,
+            namespaces: vec![MockNamespace {
+                path: "/".to_string(),
+                rules,
+                script: None,
+            }],
+        },
+        sink.clone(),
+    )
+    .await
+    .unwrap();
+    (server, recording_id, sink)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn scripted_replies_and_echo() {
+    init_test_db();
+    let (server, recording_id, server_sink) = start_mock(vec![
+        MockRule::Reply {
+            event: "join".to_string(),
+            reply_event: "joined".to_string(),
+            payload: json!({ "room": "lobby" }),
+        },
+        MockRule::Echo {
+            event: "ping".to_string(),
+        },
+    ])
+    .await;
+    let connection_id = create_connection(server.local_addr(), &["joined", "ping"]);
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+
+    manager
+        .emit_message_async(connection_id, "join".into(), "{}".into())
+        .await
+        .unwrap();
+    manager
+        .emit_message_async(connection_id, "ping".into(), r#"{"n":1}"#.into())
+        .await
+        .unwrap();
+
+    assert!(wait_for(|| find_event(&sink, connection_id, "joined", "in").is_some()).await);
+    assert_eq!(
+        find_event(&sink, connection_id, "joined", "in").as_deref(),
+        Some(r#"{"room":"lobby"}"#)
+    );
+    assert!(wait_for(|| find_event(&sink, connection_id, "ping", "in").is_some()).await);
+    assert_eq!(
+        find_event(&sink, connection_id, "ping", "in").as_deref(),
+        Some(r#"{"n":1}"#)
+    );
+
+    // Server-side traffic is recorded under the mock's connection profile
+    assert!(find_event(&server_sink, recording_id, "connect", "in").is_some());
+    assert!(find_event(&server_sink, recording_id, "join", "in").is_some());
+    assert!(find_event(&server_sink, recording_id, "joined", "out").is_some());
+    history_writer::flush();
+    let history = db::list_event_history(recording_id, 100).unwrap();
+    assert!(
+        history
+            .iter()
+            .any(|e| e.event_name == "joined" && e.direction == "out")
+    );
+
+    manager.disconnect(connection_id, "test").unwrap();
+    assert!(
+        wait_for(|| find_event(&server_sink, recording_id, "disconnect", "in").is_some()).await
+    );
+    server.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn interval_rule_emits_periodically() {
+    init_test_db();
+    let (server, recording_id, server_sink) = start_mock(vec![MockRule::Interval {
+        event: "tick".to_string(),
+        payload: json!({ "tick": true }),
+        interval_ms: 100,
+    }])
+    .await;
+    let connection_id = create_connection(server.local_addr(), &["tick"]);
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+
+    assert!(
+        wait_for(|| {
+            manager
+                .list_buffered_events(connection_id, 100)
+                .iter()
+                .filter(|e| e.event_name == "tick")
+                .count()
+                >= 3
+        })
+        .await
+    );
+    assert!(find_event(&sink, connection_id, "tick", "in").is_some());
+    assert!(find_event(&server_sink, recording_id, "tick", "out").is_some());
+
+    // Stopping the server ends its timers, even for sockets that are still connected
+    server.stop().await;
+    let ticks = || {
+        server_sink
+            .notifications()
+            .into_iter()
+            .filter(|n| matches!(n, SocketNotification::Event(e) if e.event_name == "tick"))
+            .count()
+    };
+    let stopped_at = ticks();
+    assert!(!wait_for_with_timeout(Duration::from_millis(350), || ticks() > stopped_at).await);
+
+    let _ = manager.disconnect(connection_id, "test");
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::init_test_db;
+use serde_json::json;
+use socket_io_client_lib::db;
+use socket_io_client_lib::profiles::{
+    self, ConflictStrategy, ImportAction, ImportOptions, PROFILE_FORMAT_VERSION,
+};
+use socket_io_client_lib::secrets::SECRET_MASK;
+
+/// Save a connection with a listener, pinned auto-send message and credentials
+fn create_profile(name: &str) -> i64 {
+    let connection_id = db::create_connection(
+        name,
+        "http://localhost:3000",
+        "/chat",
+        Some("secret-token"),
+        r#"{"transports":["websocket"],"extraHeaders":{"Authorization":"Bearer x"}}"#,
+    )
+    .unwrap();
+    db::set_connection_auto_send(connection_id, true, false).unwrap();
+    db::add_connection_event(connection_id, "message").unwrap();
+    let pinned_id = db::add_pinned_message(connection_id, "join", r#"{"room":1}"#, None).unwrap();
+    db::set_pinned_auto_send(pinned_id, true).unwrap();
+    db::add_pinned_message(connection_id, "leave", "{}", Some("Leave")).unwrap();
+    connection_id
+}
+
+fn import(
+    bundle: &profiles::ProfileBundle,
+    on_conflict: ConflictStrategy,
+) -> Vec<profiles::ImportedProfile> {
+    let options = ImportOptions {
+        on_conflict,
+        strip_secrets: false,
+    };
+    let value = serde_json::to_value(bundle).unwrap();
+    profiles::import_profiles(profiles::parse_bundle(value).unwrap().connections, &options).unwrap()
+}
+
+#[test]
+fn export_and_import_round_trip() {
+    init_test_db();
+    let source = create_profile("round-trip");
+
+    let bundle = profiles::export_profiles(&[source], false).unwrap();
+    assert_eq!(bundle.version, PROFILE_FORMAT_VERSION);
+    assert_eq!(bundle.connections.len(), 1);
+
+    let results = import(&bundle, ConflictStrategy::Rename);
+    assert_eq!(results[0].action, ImportAction::Renamed);
+    assert_eq!(results[0].saved_name.as_deref(), Some("round-trip (2)"));
+
+    let copy = results[0].connection_id.unwrap();
+    let connection = db::get_connection_by_id(copy).unwrap().unwrap();
+    assert_eq!(connection.namespace, "/chat");
+    // Exported secrets are masked, so the copy has none
+    assert_eq!(connection.auth_token, None);
+    assert!(connection.auto_send_on_connect);
+    assert!(!connection.auto_send_on_reconnect);
+
+    let events = db::list_connection_events(copy).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_name, "message");
+
+    let pinned = db::list_pinned_messages(copy).unwrap();
+    let names: Vec<&str> = pinned.iter().map(|m| m.event_name.as_str()).collect();
+    assert_eq!(names, ["join", "leave"]);
+    assert!(pinned[0].auto_send);
+    assert_eq!(pinned[1].label.as_deref(), Some("Leave"));
+}
+
+#[test]
+fn conflicts_can_be_skipped_or_overwritten() {
+    init_test_db();
+    let existing = create_profile("conflict");
+    let mut bundle = profiles::export_profiles(&[existing], false).unwrap();
+    bundle.connections[0].url = "http://localhost:4000".to_string();
+    bundle.connections[0].pinned_messages.truncate(1);
+
+    let skipped = import(&bundle, ConflictStrategy::Skip);
+    assert_eq!(skipped[0].action, ImportAction::Skipped);
+    assert_eq!(skipped[0].connection_id, None);
+    let connection = db::get_connection_by_id(existing).unwrap().unwrap();
+    assert_eq!(connection.url, "http://localhost:3000");
+
+    let overwritten = import(&bundle, ConflictStrategy::Overwrite);
+    assert_eq!(overwritten[0].action, ImportAction::Overwritten);
+    assert_eq!(overwritten[0].connection_id, Some(existing));
+    let connection = db::get_connection_by_id(existing).unwrap().unwrap();
+    assert_eq!(connection.url, "http://localhost:4000");
+    // Masked secrets in the file keep the overwritten connection's values
+    assert_eq!(connection.auth_token.as_deref(), Some("secret-token"));
+    assert_eq!(db::list_pinned_messages(existing).unwrap().len(), 1);
+    assert_eq!(db::list_connection_events(existing).unwrap().len(), 1);
+}
+
+#[test]
+fn secrets_are_stripped_on_export() {
+    init_test_db();
+    let source = create_profile("stripped");
+
+    let bundle = profiles::export_profiles(&[source], true).unwrap();
+
+    let profile = &bundle.connections[0];
+    assert_eq!(profile.auth_token, None);
+    let options: serde_json::Value = serde_json::from_str(&profile.options).unwrap();
+    assert_eq!(options, json!({ "transports": ["websocket"] }));
+
+    let masked = profiles::export_profiles(&[source], false).unwrap();
+    let profile = &masked.connections[0];
+    assert_eq!(profile.auth_token.as_deref(), Some(SECRET_MASK));
+    let options: serde_json::Value = serde_json::from_str(&profile.options).unwrap();
+    assert_eq!(options["extraHeaders"], SECRET_MASK);
+}
+
+#[test]
+fn unsupported_files_are_rejected() {
+    let newer = json!({
+        "format": profiles::PROFILE_FORMAT,
+        "version": PROFILE_FORMAT_VERSION + 1,
+        "connections": []
+    });
+    let error = profiles::parse_bundle(newer).unwrap_err();
+    assert!(error.contains("not supported"), "{}", error);
+
+    let unrelated = json!({ "version": 1, "connections": [] });
+    assert!(profiles::parse_bundle(unrelated).is_err());
+}
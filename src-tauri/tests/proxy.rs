@@ -0,0 +1,145 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{
+    connect, create_connection, find_event, init_test_db, new_manager, start_server, wait_for,
+};
+use rust_socketio::{ClientBuilder, Payload};
+use serde_json::json;
+use socket_io_client_lib::event_sink::MemorySink;
+use socket_io_client_lib::proxy::{ProxyAction, ProxyConfig, ProxyRule, ProxyServer};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forwards_both_directions_and_applies_rules() {
+    init_test_db();
+    let upstream_addr = start_server().await;
+    let upstream_id = create_connection(upstream_addr, &[]);
+    let recording_id = create_connection(upstream_addr, &[]);
+    let proxy_sink = Arc::new(MemorySink::new());
+    let proxy = ProxyServer::start(
+        ProxyConfig {
+            port: 0,
+            connection_id: upstream_id,
+            recording_connection_id: recording_id,
+            rules: vec![
+                ProxyRule {
+                    event: "echo".to_string(),
+                    direction: Some("out".to_string()),
+                    action: ProxyAction::Merge {
+                        payload: json!({ "tampered": true }),
+                    },
+                },
+                ProxyRule {
+                    event: "secret".to_string(),
+                    direction: None,
+                    action: ProxyAction::Drop,
+                },
+            ],
+        },
+        proxy_sink.clone(),
+    )
+    .await
+    .unwrap();
+
+    let connection_id = create_connection(proxy.local_addr(), &["welcome", "echo"]);
+    let (manager, sink) = new_manager();
+    connect(&manager, connection_id).await.unwrap();
+
+    // Server-initiated events reach the app through the proxy
+    assert!(wait_for(|| find_event(&sink, connection_id, "welcome", "in").is_some()).await);
+
+    manager
+        .emit_message_async(connection_id, "secret".into(), "{}".into())
+        .await
+        .unwrap();
+    manager
+        .emit_message_async(connection_id, "echo".into(), r#"{"n":1}"#.into())
+        .await
+        .unwrap();
+
+    assert!(wait_for(|| find_event(&sink, connection_id, "echo", "in").is_some()).await);
+    assert_eq!(
+        find_event(&sink, connection_id, "echo", "in").as_deref(),
+        Some(r#"{"n":1,"tampered":true}"#)
+    );
+
+    // Proxied traffic is recorded under its own profile, dropped messages are not
+    assert!(find_event(&proxy_sink, recording_id, "welcome", "in").is_some());
+    assert!(find_event(&proxy_sink, recording_id, "echo", "out").is_some());
+    assert!(find_event(&proxy_sink, recording_id, "secret", "out").is_none());
+    assert!(find_event(&proxy_sink, upstream_id, "welcome", "in").is_none());
+
+    manager.disconnect(connection_id, "test").unwrap();
+    proxy.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn relays_acks_from_the_server() {
+    init_test_db();
+    let upstream_addr = start_server().await;
+    let upstream_id = create_connection(upstream_addr, &[]);
+    let recording_id = create_connection(upstream_addr, &[]);
+    let proxy_sink = Arc::new(MemorySink::new());
+    let proxy = ProxyServer::start(
+        ProxyConfig {
+            port: 0,
+            connection_id: upstream_id,
+            recording_connection_id: recording_id,
+            rules: Vec::new(),
+        },
+        proxy_sink.clone(),
+    )
+    .await
+    .unwrap();
+
+    let url = format!("http://{}", proxy.local_addr());
+    let client = tokio::task::spawn_blocking(move || ClientBuilder::new(url).connect())
+        .await
+        .unwrap()
+        .unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    client
+        .emit_with_ack(
+            "ack",
+            json!({ "n": 1 }),
+            Duration::from_secs(5),
+            move |payload, _| {
+                if let Payload::Text(values) = payload {
+                    let _ = tx.send(values);
+                }
+            },
+        )
+        .unwrap();
+
+    let response = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+        .await
+        .unwrap()
+        .expect("the ack was not relayed");
+    assert_eq!(response, vec![json!({ "n": 1 })]);
+    assert!(find_event(&proxy_sink, recording_id, "ack", "in").is_some());
+
+    client.disconnect().unwrap();
+    proxy.stop().await;
+}
+
+#[tokio::test]
+async fn recording_connection_must_differ_from_target() {
+    init_test_db();
+    let upstream_id = create_connection(start_server().await, &[]);
+
+    let error = ProxyServer::start(
+        ProxyConfig {
+            port: 0,
+            connection_id: upstream_id,
+            recording_connection_id: upstream_id,
+            rules: Vec::new(),
+        },
+        Arc::new(MemorySink::new()),
+    )
+    .await
+    .err()
+    .expect("the proxy started");
+    assert!(error.contains("different connection"), "{}", error);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{create_connection, init_test_db};
+use serde_json::{Value, json};
+use socket_io_client_lib::redaction::{self, REDACTED, RedactionSettings, Redactor};
+use socket_io_client_lib::{db, history_writer};
+
+#[test]
+fn default_rules_redact_keys_headers_and_tokens() {
+    let redactor = Redactor::new(RedactionSettings::default()).unwrap();
+
+    let payload = json!({
+        "user": "amy",
+        "password": "hunter2",
+        "session": { "access_token": "abc", "expires": 3600 },
+        "headers": { "authorization": "Basic Zm9v", "accept": "*/*" },
+        "note": "sent with Bearer abc.def"
+    });
+    let redacted: Value =
+        serde_json::from_str(&redactor.redact_payload(&payload.to_string()).unwrap()).unwrap();
+    assert_eq!(
+        redacted,
+        json!({
+            "user": "amy",
+            "password": REDACTED,
+            "session": { "access_token": REDACTED, "expires": 3600 },
+            "headers": { "authorization": REDACTED, "accept": "*/*" },
+            "note": format!("sent with {}", REDACTED)
+        })
+    );
+
+    assert_eq!(
+        redactor
+            .redact_text("GET /socket.io/?token=abc&EIO=4 Cookie: sid=1")
+            .unwrap(),
+        format!(
+            "GET /socket.io/?token={}&EIO=4 Cookie: {}",
+            REDACTED, REDACTED
+        )
+    );
+    assert_eq!(redactor.redact_payload(r#"{"text":"hello"}"#), None);
+}
+
+#[test]
+fn custom_patterns_are_validated() {
+    let settings = RedactionSettings {
+        patterns: vec!["(unclosed".to_string()],
+        ..Default::default()
+    };
+    let error = Redactor::new(settings).err().unwrap();
+    assert!(error.contains("Invalid pattern"), "{}", error);
+
+    let settings = RedactionSettings {
+        patterns: vec![r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".to_string()],
+        ..Default::default()
+    };
+    let redactor = Redactor::new(settings).unwrap();
+    assert_eq!(
+        redactor.redact_text("card 4111-1111-1111-1111").unwrap(),
+        format!("card {}", REDACTED)
+    );
+}
+
+#[test]
+fn redaction_is_opt_in() {
+    assert!(!RedactionSettings::default().enabled);
+}
+
+#[test]
+fn history_is_redacted_only_where_enabled() {
+    init_test_db();
+    let redacted = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    let raw = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    redaction::set_connection_redaction(redacted, true).unwrap();
+
+    let payload = r#"{"password":"hunter2"}"#;
+    for connection_id in [redacted, raw] {
+        history_writer::record(
+            connection_id,
+            "login",
+            payload,
+            "2025-01-01T00:00:00Z",
+            "out",
+        );
+    }
+    history_writer::flush();
+
+    let stored = |connection_id| db::list_event_history(connection_id, 1).unwrap().remove(0);
+    let redacted = stored(redacted);
+    assert_eq!(
+        redacted.payload,
+        format!(r#"{{"password":"{}"}}"#, REDACTED)
+    );
+    assert!(redacted.redacted);
+    let raw = stored(raw);
+    assert_eq!(raw.payload, payload);
+    assert!(!raw.redacted);
+}
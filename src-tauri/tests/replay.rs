@@ -0,0 +1,161 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use common::{
+    connect, create_connection, find_event, init_test_db, new_manager, start_server, wait_for,
+};
+use serde_json::json;
+use socket_io_client_lib::event_sink::MemorySink;
+use socket_io_client_lib::mock_server::{MockNamespace, MockServer, MockServerConfig};
+use socket_io_client_lib::replay::{
+    RecordedEvent, build_server_script, load_session, replay_as_client,
+};
+
+fn recorded(
+    id: i64,
+    event_name: &str,
+    payload: &str,
+    millis: i64,
+    direction: &str,
+    redacted: bool,
+) -> RecordedEvent {
+    RecordedEvent {
+        id,
+        event_name: event_name.to_string(),
+        payload: payload.to_string(),
+        timestamp: format!(
+            "2025-01-01T00:00:{:02}.{:03}Z",
+            millis / 1000,
+            millis % 1000
+        ),
+        direction: direction.to_string(),
+        redacted,
+    }
+}
+
+#[test]
+fn server_script_pairs_requests_with_replies() {
+    let events = vec![
+        recorded(1, "connect", "{}", 0, "in", false),
+        recorded(2, "welcome", "\"hi\"", 100, "in", false),
+        recorded(3, "join", "{}", 1000, "out", false),
+        recorded(4, "joined", r#"{"room":1}"#, 1400, "in", false),
+        recorded(5, "news", "plain text", 2000, "in", false),
+        recorded(6, "leave", "{}", 3000, "out", false),
+        recorded(7, "disconnect", "{}", 3100, "in", false),
+    ];
+
+    let script = build_server_script(&events, 2.0).unwrap();
+
+    assert_eq!(script.on_connect.len(), 1);
+    assert_eq!(script.on_connect[0].event, "welcome");
+    assert_eq!(script.on_connect[0].payload, json!("hi"));
+    assert_eq!(script.on_connect[0].delay_ms, 50);
+
+    assert_eq!(script.exchanges.len(), 2);
+    assert_eq!(script.exchanges[0].event, "join");
+    let replies = &script.exchanges[0].replies;
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0].event, "joined");
+    assert_eq!(replies[0].payload, json!({ "room": 1 }));
+    assert_eq!(replies[0].delay_ms, 200);
+    assert_eq!(replies[1].payload, json!("plain text"));
+    assert_eq!(replies[1].delay_ms, 300);
+    assert_eq!(script.exchanges[1].event, "leave");
+    assert!(script.exchanges[1].replies.is_empty());
+}
+
+#[test]
+fn speed_must_be_positive() {
+    assert!(build_server_script(&[], 0.0).is_err());
+    assert!(build_server_script(&[], f64::NAN).is_err());
+}
+
+#[test]
+fn redacted_events_are_not_re_sent() {
+    let events = vec![
+        recorded(1, "login", r#"{"token":"[REDACTED]"}"#, 0, "out", true),
+        recorded(2, "ok", "{}", 100, "in", false),
+    ];
+    let (manager, _sink) = new_manager();
+
+    let error = replay_as_client(&manager, 1, &events, 1.0, &AtomicBool::new(false)).unwrap_err();
+    assert!(error.contains("redacted"), "{}", error);
+
+    // The stored flag decides, not placeholder text a payload happens to contain
+    let literal = vec![recorded(1, "note", r#""[REDACTED]""#, 0, "out", false)];
+    let error = replay_as_client(&manager, 1, &literal, 1.0, &AtomicBool::new(false)).unwrap_err();
+    assert!(!error.contains("redacted"), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recorded_session_replays_against_mock_server() {
+    init_test_db();
+
+    // Record a session against the real test server
+    let addr = start_server().await;
+    let source_id = create_connection(addr, &["welcome", "echo"]);
+    let (manager, sink) = new_manager();
+    connect(&manager, source_id).await.unwrap();
+    assert!(wait_for(|| find_event(&sink, source_id, "welcome", "in").is_some()).await);
+    manager
+        .emit_message_async(source_id, "echo".into(), r#"{"n":7}"#.into())
+        .await
+        .unwrap();
+    assert!(wait_for(|| find_event(&sink, source_id, "echo", "in").is_some()).await);
+    manager.disconnect(source_id, "test").unwrap();
+
+    let events = load_session(source_id, None, None).unwrap();
+    assert_eq!(events[0].event_name, "connect");
+    let script = build_server_script(&events, 10.0).unwrap();
+
+    // Serve the recording and replay the client side against it
+    let recording_id = create_connection(addr, &[]);
+    let mock = MockServer::start(
+        MockServerConfig {
+            port: 0,
+            connection_id: recording_id,
+            namespaces: vec![MockNamespace {
+                path: "/".to_string(),
+                rules: Vec::new(),
+                script: Some(script),
+            }],
+        },
+        Arc::new(MemorySink::new()),
+    )
+    .await
+    .unwrap();
+
+    let target_id = create_connection(mock.local_addr(), &["welcome", "echo"]);
+    let (replay_manager, replay_sink) = new_manager();
+    connect(&replay_manager, target_id).await.unwrap();
+    assert!(wait_for(|| find_event(&replay_sink, target_id, "welcome", "in").is_some()).await);
+
+    let sent = tokio::task::spawn_blocking({
+        let replay_manager = replay_manager.clone();
+        move || {
+            replay_as_client(
+                &replay_manager,
+                target_id,
+                &events,
+                10.0,
+                &AtomicBool::new(false),
+            )
+        }
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(sent, 1);
+
+    assert!(wait_for(|| find_event(&replay_sink, target_id, "echo", "in").is_some()).await);
+    assert_eq!(
+        find_event(&replay_sink, target_id, "echo", "in").as_deref(),
+        Some(r#"{"n":7}"#)
+    );
+
+    replay_manager.disconnect(target_id, "test").unwrap();
+    mock.stop().await;
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use common::{create_connection, init_test_db};
+use socket_io_client_lib::db::{self, EventHistoryRecord};
+use socket_io_client_lib::retention::{
+    RetentionPolicy, RetentionSettings, load_settings, prune, save_settings, storage_report,
+};
+
+fn add_events(connection_id: i64, count: i64, age_days: i64) {
+    let timestamp = (Utc::now() - Duration::days(age_days)).to_rfc3339();
+    for n in 0..count {
+        db::add_event_history(
+            connection_id,
+            "tick",
+            &n.to_string(),
+            &timestamp,
+            "in",
+            false,
+        )
+        .unwrap();
+    }
+}
+
+fn event_count(connection_id: i64) -> usize {
+    db::list_event_history(connection_id, 10_000).unwrap().len()
+}
+
+#[test]
+fn per_connection_policies_override_global() {
+    init_test_db();
+    let capped = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    let aged = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    add_events(capped, 5, 30);
+    add_events(capped, 5, 0);
+    add_events(aged, 5, 30);
+    add_events(aged, 5, 0);
+
+    let settings = RetentionSettings {
+        global: RetentionPolicy {
+            max_rows: None,
+            max_age_days: Some(7),
+        },
+        connections: HashMap::from([(
+            capped,
+            RetentionPolicy {
+                max_rows: Some(3),
+                max_age_days: Some(365),
+            },
+        )]),
+        ..Default::default()
+    };
+    save_settings(&settings).unwrap();
+    assert_eq!(load_settings().unwrap(), settings);
+
+    let report = prune(&settings).unwrap();
+
+    assert!(report.deleted_by_rows >= 7);
+    assert!(report.deleted_by_age >= 5);
+    let newest: Vec<String> = db::list_event_history(capped, 10)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.payload)
+        .collect();
+    assert_eq!(newest.len(), 3);
+    assert!(newest.iter().all(|p| ["2", "3", "4"].contains(&p.as_str())));
+    assert_eq!(event_count(aged), 5);
+
+    let storage = storage_report().unwrap();
+    assert!(storage.file_bytes >= storage.used_bytes);
+    let entry = storage
+        .connections
+        .iter()
+        .find(|c| c.connection_id == capped)
+        .unwrap();
+    assert_eq!(entry.event_count, 3);
+}
+
+#[test]
+fn negative_limits_are_rejected() {
+    init_test_db();
+    let settings = RetentionSettings {
+        global: RetentionPolicy {
+            max_rows: Some(-1),
+            max_age_days: None,
+        },
+        ..Default::default()
+    };
+    assert!(save_settings(&settings).is_err());
+}
+
+#[test]
+fn oversized_limits_are_rejected_and_do_not_overflow() {
+    init_test_db();
+    let huge_age = RetentionSettings {
+        global: RetentionPolicy {
+            max_rows: None,
+            max_age_days: Some(i64::MAX),
+        },
+        ..Default::default()
+    };
+    let huge_size = RetentionSettings {
+        max_db_size_mb: Some(u64::MAX),
+        ..Default::default()
+    };
+    assert!(save_settings(&huge_age).is_err());
+    assert!(save_settings(&huge_size).is_err());
+
+    // Settings stored before the bounds existed still prune without panicking
+    let connection_id = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    add_events(connection_id, 3, 0);
+    let report = prune(&RetentionSettings {
+        mcp_audit: huge_age.global.clone(),
+        ..huge_age
+    })
+    .unwrap();
+    assert_eq!(report.deleted_by_age, 0);
+    let report = prune(&huge_size).unwrap();
+    assert_eq!(report.deleted_by_size, 0);
+    assert_eq!(event_count(connection_id), 3);
+}
+
+#[test]
+fn imported_sessions_are_not_pruned() {
+    init_test_db();
+    let recorded = create_connection("127.0.0.1:1".parse().unwrap(), &[]);
+    add_events(recorded, 3, 30);
+    let old = (Utc::now() - Duration::days(30)).to_rfc3339();
+    let events: Vec<EventHistoryRecord> = (0..3)
+        .map(|n| EventHistoryRecord {
+            id: 0,
+            connection_id: 0,
+            event_name: "tick".to_string(),
+            payload: n.to_string(),
+            timestamp: old.clone(),
+            direction: "in".to_string(),
+            redacted: false,
+        })
+        .collect();
+    let imported =
+        db::create_imported_session("capture", "http://localhost:3000", "/", &events).unwrap();
+
+    // Per-connection policies, so tests running alongside keep their events
+    let policy = RetentionPolicy {
+        max_rows: Some(1),
+        max_age_days: Some(7),
+    };
+    let report = prune(&RetentionSettings {
+        connections: HashMap::from([(recorded, policy.clone()), (imported, policy)]),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert!(report.deleted_by_age >= 3);
+    assert_eq!(event_count(recorded), 0);
+    assert_eq!(event_count(imported), 3);
+}
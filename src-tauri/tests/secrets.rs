@@ -0,0 +1,92 @@
+mod common;
+
+use std::sync::Mutex;
+
+use common::{data_dir, init_test_db};
+use serde_json::{Value, json};
+use socket_io_client_lib::db;
+use socket_io_client_lib::secrets::{self, KeySource, SECRET_MASK};
+
+/// Changing the passphrase re-keys every secret, so tests must not overlap
+static KEY_LOCK: Mutex<()> = Mutex::new(());
+
+fn create_secret_connection(name: &str) -> i64 {
+    let auth_token = secrets::seal_auth_token(Some("token-1"), None).unwrap();
+    let options = secrets::seal_options(
+        r#"{"transports":["websocket"],"auth":{"user":"amy"}}"#,
+        None,
+    )
+    .unwrap();
+    db::create_connection(
+        name,
+        "http://localhost:3000",
+        "/",
+        auth_token.as_deref(),
+        &options,
+    )
+    .unwrap()
+}
+
+#[test]
+fn secrets_are_encrypted_at_rest_and_masked() {
+    init_test_db();
+    let _guard = KEY_LOCK.lock().unwrap();
+    let connection_id = create_secret_connection("encrypted");
+
+    let stored = db::get_connection_by_id(connection_id).unwrap().unwrap();
+    assert!(secrets::is_encrypted(stored.auth_token.as_deref().unwrap()));
+    assert!(!stored.options.contains("amy"));
+    let options: Value = serde_json::from_str(&stored.options).unwrap();
+    assert_eq!(options["transports"], json!(["websocket"]));
+
+    let masked = secrets::mask_connection(stored.clone());
+    assert_eq!(masked.auth_token.as_deref(), Some(SECRET_MASK));
+    let options: Value = serde_json::from_str(&masked.options).unwrap();
+    assert_eq!(options["auth"], SECRET_MASK);
+
+    // Saving the masked values back keeps the stored secrets
+    let auth_token =
+        secrets::seal_auth_token(masked.auth_token.as_deref(), stored.auth_token.as_deref())
+            .unwrap();
+    assert_eq!(auth_token, stored.auth_token);
+    let options = secrets::seal_options(&masked.options, Some(&stored.options)).unwrap();
+    assert_eq!(options, stored.options);
+
+    let revealed = secrets::reveal_connection(stored).unwrap();
+    assert_eq!(revealed.auth_token.as_deref(), Some("token-1"));
+    let options: Value = serde_json::from_str(&revealed.options).unwrap();
+    assert_eq!(options["auth"], json!({ "user": "amy" }));
+}
+
+#[test]
+fn passphrase_re_encrypts_stored_secrets() {
+    init_test_db();
+    let _guard = KEY_LOCK.lock().unwrap();
+    let connection_id = create_secret_connection("passphrase");
+    let before = db::get_connection_by_id(connection_id).unwrap().unwrap();
+
+    secrets::set_passphrase(Some("correct horse")).unwrap();
+    let status = secrets::status().unwrap();
+    assert_eq!(status.source, KeySource::Passphrase);
+    assert!(!status.locked);
+
+    let after = db::get_connection_by_id(connection_id).unwrap().unwrap();
+    assert_ne!(after.auth_token, before.auth_token);
+    let revealed = secrets::reveal_connection(after).unwrap();
+    assert_eq!(revealed.auth_token.as_deref(), Some("token-1"));
+
+    let error = secrets::unlock("wrong horse").unwrap_err();
+    assert!(error.contains("Incorrect"), "{}", error);
+    secrets::unlock("correct horse").unwrap();
+
+    secrets::set_passphrase(None).unwrap();
+    assert_eq!(secrets::status().unwrap().source, KeySource::KeyFile);
+    let dir = data_dir();
+    assert!(!dir.join("secret.key.new").exists());
+
+    // The new key file decrypts the re-encrypted secrets after a restart
+    secrets::init(&dir).unwrap();
+    let stored = db::get_connection_by_id(connection_id).unwrap().unwrap();
+    let revealed = secrets::reveal_connection(stored).unwrap();
+    assert_eq!(revealed.auth_token.as_deref(), Some("token-1"));
+}
@@ -0,0 +1,239 @@
+mod common;
+
+use common::{
+    connect, count_statuses, create_connection, find_event, init_test_db, new_manager,
+    start_server, wait_for,
+};
+use socket_io_client_lib::event_sink::SocketNotification;
+use socket_io_client_lib::{db, history_writer};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_reports_status_and_forwards_listened_events() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &["welcome"]);
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+    assert_eq!(count_statuses(&sink, connection_id, "connecting"), 1);
+    assert!(wait_for(|| find_event(&sink, connection_id, "welcome", "in").is_some()).await);
+    assert_eq!(
+        find_event(&sink, connection_id, "welcome", "in").as_deref(),
+        Some("\"hello\"")
+    );
+    assert_eq!(manager.get_current_connection_id(), Some(connection_id));
+
+    manager.disconnect(connection_id, "test").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn extra_headers_are_sent_with_the_handshake() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = db::create_connection(
+        "headers",
+        &format!("http://{}", addr),
+        "/",
+        None,
+        r#"{"transports":["websocket"],"extraHeaders":{"X-Team":"qa"}}"#,
+    )
+    .unwrap();
+    db::add_connection_event(connection_id, "header").unwrap();
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+    manager
+        .emit_message_async(connection_id, "header".into(), r#""x-team""#.into())
+        .await
+        .unwrap();
+
+    assert!(wait_for(|| find_event(&sink, connection_id, "header", "in").is_some()).await);
+    assert_eq!(
+        find_event(&sink, connection_id, "header", "in").as_deref(),
+        Some(r#""qa""#)
+    );
+
+    manager.disconnect(connection_id, "test").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn emit_round_trip_is_recorded_in_history() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &["echo"]);
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+
+    manager
+        .emit_message_async(connection_id, "echo".into(), r#"{"text":"hi"}"#.into())
+        .await
+        .unwrap();
+
+    assert!(find_event(&sink, connection_id, "echo", "out").is_some());
+    assert!(wait_for(|| find_event(&sink, connection_id, "echo", "in").is_some()).await);
+    assert_eq!(
+        find_event(&sink, connection_id, "echo", "in").as_deref(),
+        Some(r#"{"text":"hi"}"#)
+    );
+
+    let buffered = manager.list_buffered_events(connection_id, 10);
+    assert!(
+        buffered
+            .iter()
+            .any(|e| e.event_name == "echo" && e.direction == "out")
+    );
+    assert!(
+        buffered
+            .iter()
+            .any(|e| e.event_name == "echo" && e.direction == "in")
+    );
+
+    history_writer::flush();
+    let history = db::list_event_history(connection_id, 10).unwrap();
+    assert!(
+        history
+            .iter()
+            .any(|e| e.event_name == "echo" && e.direction == "out")
+    );
+    assert!(
+        history
+            .iter()
+            .any(|e| e.event_name == "echo" && e.direction == "in")
+    );
+
+    manager.disconnect(connection_id, "test").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_without_listener_are_not_forwarded() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &["pong"]);
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+
+    manager
+        .emit_message_async(connection_id, "echo".into(), "{}".into())
+        .await
+        .unwrap();
+    manager
+        .emit_message_async(connection_id, "ping".into(), "{}".into())
+        .await
+        .unwrap();
+    // The welcome and echo replies were handled before the pong arrived
+    assert!(wait_for(|| find_event(&sink, connection_id, "pong", "in").is_some()).await);
+
+    assert!(find_event(&sink, connection_id, "welcome", "in").is_none());
+    assert!(find_event(&sink, connection_id, "echo", "in").is_none());
+
+    manager.add_listener(connection_id, "echo").unwrap();
+    manager
+        .emit_message_async(connection_id, "echo".into(), "{}".into())
+        .await
+        .unwrap();
+    assert!(wait_for(|| find_event(&sink, connection_id, "echo", "in").is_some()).await);
+
+    manager.disconnect(connection_id, "test").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn auto_send_runs_on_connect() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &["echo"]);
+    let pinned_id =
+        db::add_pinned_message(connection_id, "echo", r#"{"auto":true}"#, None).unwrap();
+    db::set_pinned_auto_send(pinned_id, true).unwrap();
+    db::set_connection_auto_send(connection_id, true, false).unwrap();
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+
+    assert!(wait_for(|| find_event(&sink, connection_id, "echo", "in").is_some()).await);
+    let emit_logs = db::list_emit_logs(connection_id, 10).unwrap();
+    assert_eq!(emit_logs.len(), 1);
+    assert_eq!(emit_logs[0].event_name, "echo");
+
+    manager.disconnect(connection_id, "test").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reconnects_after_server_disconnect() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &[]);
+    let pinned_id = db::add_pinned_message(connection_id, "noop", "{}", None).unwrap();
+    db::set_pinned_auto_send(pinned_id, true).unwrap();
+    db::set_connection_auto_send(connection_id, false, true).unwrap();
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+
+    manager
+        .emit_message_async(connection_id, "kick".into(), "{}".into())
+        .await
+        .unwrap();
+
+    assert!(wait_for(|| count_statuses(&sink, connection_id, "connected") >= 2).await);
+    assert!(wait_for(|| find_event(&sink, connection_id, "noop", "out").is_some()).await);
+    // Auto-send is only enabled for reconnects, so it ran once
+    let auto_sent = sink
+        .notifications()
+        .into_iter()
+        .filter(|n| {
+            matches!(n, SocketNotification::Event(e)
+                if e.connection_id == connection_id
+                    && e.event_name == "noop"
+                    && e.direction == "out")
+        })
+        .count();
+    assert_eq!(auto_sent, 1);
+
+    manager.disconnect(connection_id, "test").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn manual_disconnect_clears_state() {
+    init_test_db();
+    let addr = start_server().await;
+    let connection_id = create_connection(addr, &[]);
+    let (manager, sink) = new_manager();
+
+    connect(&manager, connection_id).await.unwrap();
+    assert!(wait_for(|| manager.get_status_for_connection(connection_id) == "connected").await);
+
+    manager.disconnect(connection_id, "test").unwrap();
+
+    assert_eq!(
+        manager.get_status_for_connection(connection_id),
+        "disconnected"
+    );
+    assert!(!manager.get_all_statuses().contains_key(&connection_id));
+    assert_eq!(
+        find_event(&sink, connection_id, "disconnect", "in").as_deref(),
+        Some(r#"{"reason":"test"}"#)
+    );
+    assert!(
+        manager
+            .emit_message(connection_id, "echo", "{}")
+            .is_err_and(|e| e == "Not connected")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_to_unknown_connection_fails() {
+    init_test_db();
+    let (manager, _sink) = new_manager();
+
+    let result = connect(&manager, i64::MAX).await;
+
+    assert_eq!(result, Err("Connection not found".to_string()));
+}
@@ -12,6 +12,7 @@ import {
   listPinnedMessages,
   findDuplicatePinnedMessage,
 } from '@/app/hooks/useTauri';
+import { hasRedactedValues } from '@/app/lib/redaction';
 import MessageEditor, { PayloadType } from './MessageEditor';
 import PinNameModal from './PinNameModal';
 
@@ -20,6 +21,8 @@ interface ComposeMessageModalProps {
   onClose: () => void;
   initialEventName?: string;
   initialPayload?: string;
+  /** The payload was redacted when stored, so its placeholders must be replaced */
+  initialRedacted?: boolean;
 }
 
 export default function ComposeMessageModal({
@@ -27,6 +30,7 @@ export default function ComposeMessageModal({
   onClose,
   initialEventName = '',
   initialPayload = '{}',
+  initialRedacted = false,
 }: ComposeMessageModalProps) {
   const { message } = App.useApp();
   const [eventName, setEventName] = useState(initialEventName);
@@ -126,6 +130,11 @@ export default function ComposeMessageModal({
       return;
     }
 
+    if (initialRedacted && hasRedactedValues(payload)) {
+      message.warning('Replace the [REDACTED] values before sending');
+      return;
+    }
+
     try {
       let parsedPayload: unknown;
 
@@ -80,14 +80,14 @@ export default function EmitLogPanel() {
     }
   }
 
-  function handleResend(eventName: string, payload: string) {
+  function handleResend(eventName: string, payload: string, redacted: boolean) {
     // Open compose modal with pre-filled data
-    openComposeModal(eventName, payload);
+    openComposeModal(eventName, payload, redacted);
   }
 
-  function handleEdit(eventName: string, payload: string) {
+  function handleEdit(eventName: string, payload: string, redacted: boolean) {
     // Open compose modal for editing
-    openComposeModal(eventName, payload);
+    openComposeModal(eventName, payload, redacted);
   }
 
   async function handlePin(eventName: string, payload: string) {
@@ -186,7 +186,7 @@ export default function EmitLogPanel() {
                       type="text"
                       size="small"
                       icon={<EditOutlined />}
-                      onClick={() => handleEdit(log.eventName, log.payload)}
+                      onClick={() => handleEdit(log.eventName, log.payload, log.redacted)}
                     />
                   </Tooltip>
                   <Tooltip title="Re-send">
@@ -194,7 +194,7 @@ export default function EmitLogPanel() {
                       type="text"
                       size="small"
                       icon={<SendOutlined />}
-                      onClick={() => handleResend(log.eventName, log.payload)}
+                      onClick={() => handleResend(log.eventName, log.payload, log.redacted)}
                     />
                   </Tooltip>
                   <Tooltip title="Pin">
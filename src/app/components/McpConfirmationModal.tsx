@@ -0,0 +1,103 @@
+'use client';
+
+import { useCallback, useState } from 'react';
+import { Modal, Typography, Space, App } from 'antd';
+import { useMcpStore } from '../stores/mcpStore';
+import { useSocketStore } from '../stores/socketStore';
+import { resolveMcpConfirmation } from '../hooks/useTauri';
+
+const { Text } = Typography;
+
+/**
+ * Asks the user to allow or deny MCP write tools when the policy requires confirmation.
+ * Requests are answered one at a time, oldest first.
+ */
+export default function McpConfirmationModal() {
+  const { message } = App.useApp();
+  const [resolving, setResolving] = useState(false);
+
+  const confirmation = useMcpStore((state) => state.confirmations[0]);
+  const pendingCount = useMcpStore((state) => state.confirmations.length);
+  const removeConfirmation = useMcpStore((state) => state.removeConfirmation);
+  const connections = useSocketStore((state) => state.connections);
+
+  const connectionName =
+    confirmation?.connectionId != null
+      ? (connections.find((c) => c.id === confirmation.connectionId)?.name ??
+        `#${confirmation.connectionId}`)
+      : null;
+
+  const handleResolve = useCallback(
+    async (approved: boolean) => {
+      if (!confirmation) return;
+      setResolving(true);
+      try {
+        await resolveMcpConfirmation(confirmation.requestId, approved);
+      } catch {
+        // The request was denied after timing out while the dialog was open
+        message.warning(`${confirmation.tool} request expired`);
+      } finally {
+        removeConfirmation(confirmation.requestId);
+        setResolving(false);
+      }
+    },
+    [confirmation, removeConfirmation, message]
+  );
+
+  let argumentsText = confirmation?.arguments ?? '';
+  try {
+    argumentsText = JSON.stringify(JSON.parse(argumentsText), null, 2);
+  } catch {
+    // Show the raw text
+  }
+
+  return (
+    <Modal
+      title="Allow MCP tool call?"
+      open={!!confirmation}
+      onOk={() => handleResolve(true)}
+      onCancel={() => handleResolve(false)}
+      okText="Allow"
+      cancelText="Deny"
+      confirmLoading={resolving}
+      maskClosable={false}
+      closable={false}
+      keyboard={false}
+    >
+      {confirmation && (
+        <Space orientation="vertical" style={{ width: '100%' }}>
+          <Text>
+            An AI assistant wants to run <Text code>{confirmation.tool}</Text>
+            {connectionName && (
+              <>
+                {' '}
+                on <Text strong>{connectionName}</Text>
+              </>
+            )}
+            .
+          </Text>
+          <pre
+            style={{
+              margin: 0,
+              background: 'var(--ant-color-fill-tertiary)',
+              padding: 12,
+              borderRadius: 6,
+              fontSize: 11,
+              maxHeight: 240,
+              overflow: 'auto',
+              whiteSpace: 'pre-wrap',
+              wordBreak: 'break-all',
+            }}
+          >
+            {argumentsText}
+          </pre>
+          {pendingCount > 1 && (
+            <Text type="secondary" style={{ fontSize: 12 }}>
+              {pendingCount - 1} more request(s) waiting
+            </Text>
+          )}
+        </Space>
+      )}
+    </Modal>
+  );
+}
@@ -1,7 +1,7 @@
 'use client';
 
-import { useCallback } from 'react';
-import { Modal, Button, InputNumber, Typography, Space, Divider, App } from 'antd';
+import { useCallback, useEffect, useState } from 'react';
+import { Modal, Button, Input, InputNumber, Typography, Space, Divider, App } from 'antd';
 import {
   PoweroffOutlined,
   CopyOutlined,
@@ -9,23 +9,41 @@ import {
   CloseCircleOutlined,
   ExportOutlined,
   PlayCircleOutlined,
+  ReloadOutlined,
+  SafetyOutlined,
 } from '@ant-design/icons';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { useMcpStore, useMcpStatusText, useMcpStatusColor } from '../stores/mcpStore';
-import { startMcpServer, stopMcpServer, checkClaudeCli, runClaudeMcpAdd } from '../hooks/useTauri';
+import {
+  startMcpServer,
+  stopMcpServer,
+  checkClaudeCli,
+  runClaudeMcpAdd,
+  getMcpAuthToken,
+  rotateMcpAuthToken,
+} from '../hooks/useTauri';
 
 const { Text } = Typography;
 
 const DEFAULT_PORT = 3333;
 
+/**
+ * Server entry for MCP client configs, including the bearer token the server requires
+ */
+function generateServerConfig(port: number, token: string) {
+  return {
+    url: `http://localhost:${port}/mcp`,
+    headers: { Authorization: `Bearer ${token}` },
+  };
+}
+
 /**
  * Generate Cursor MCP deeplink URL for quick installation
  * Format: https://cursor.com/en/install-mcp?name={name}&config={base64_config}
  * @see https://github.com/upstash/context7
  */
-function generateCursorDeeplink(port: number): string {
-  const config = { url: `http://localhost:${port}/sse` };
-  const base64Config = btoa(JSON.stringify(config));
+function generateCursorDeeplink(port: number, token: string): string {
+  const base64Config = btoa(JSON.stringify(generateServerConfig(port, token)));
   return `https://cursor.com/en/install-mcp?name=socket-io-client&config=${base64Config}`;
 }
 
@@ -33,12 +51,23 @@ function generateCursorDeeplink(port: number): string {
  * Generate Claude Code MCP add command
  * @see https://github.com/upstash/context7
  */
-function generateClaudeCommand(port: number): string {
-  return `claude mcp add --transport http socket-io-client http://localhost:${port}/sse`;
+function generateClaudeCommand(port: number, token: string): string {
+  return `claude mcp add --transport http socket-io-client http://localhost:${port}/mcp --header "Authorization: Bearer ${token}"`;
+}
+
+/**
+ * Generate the JSON config for the mcpServers settings of Cursor and similar clients
+ */
+function generateJsonConfig(port: number, token: string): string {
+  return JSON.stringify(
+    { mcpServers: { 'socket-io-client': generateServerConfig(port, token) } },
+    null,
+    2
+  );
 }
 
 export default function McpModal() {
-  const { message } = App.useApp();
+  const { message, modal } = App.useApp();
 
   const isModalOpen = useMcpStore((state) => state.isModalOpen);
   const status = useMcpStore((state) => state.status);
@@ -48,10 +77,21 @@ export default function McpModal() {
   const setPort = useMcpStore((state) => state.setPort);
   const setLoading = useMcpStore((state) => state.setLoading);
   const closeModal = useMcpStore((state) => state.closeModal);
+  const openPolicyModal = useMcpStore((state) => state.openPolicyModal);
+
+  const [authToken, setAuthToken] = useState('');
 
   const statusText = useMcpStatusText();
   const statusColor = useMcpStatusColor();
 
+  // Load the bearer token whenever the modal opens
+  useEffect(() => {
+    if (!isModalOpen) return;
+    getMcpAuthToken()
+      .then(setAuthToken)
+      .catch((err) => message.error(`Failed to load MCP token: ${String(err)}`));
+  }, [isModalOpen, message]);
+
   const handleToggleServer = useCallback(async () => {
     setLoading(true);
     try {
@@ -85,23 +125,42 @@ export default function McpModal() {
   );
 
   const handleCopyConfig = useCallback(() => {
-    const config = JSON.stringify(
-      {
-        mcpServers: {
-          'socket-io-client': {
-            url: `http://localhost:${port ?? DEFAULT_PORT}/sse`,
-          },
-        },
-      },
-      null,
-      2
-    );
-    navigator.clipboard.writeText(config);
+    navigator.clipboard.writeText(generateJsonConfig(port ?? DEFAULT_PORT, authToken));
     message.success('MCP config copied to clipboard');
-  }, [port, message]);
+  }, [port, authToken, message]);
+
+  const handleCopyToken = useCallback(() => {
+    navigator.clipboard.writeText(authToken);
+    message.success('MCP token copied to clipboard');
+  }, [authToken, message]);
+
+  const handleRotateToken = useCallback(() => {
+    modal.confirm({
+      title: 'Rotate MCP token?',
+      content: 'Clients configured with the current token will get 401 until they are updated.',
+      okText: 'Rotate',
+      okButtonProps: { danger: true },
+      onOk: async () => {
+        try {
+          setAuthToken(await rotateMcpAuthToken());
+          // The running server still holds the old token
+          if (status === 'running') {
+            await stopMcpServer();
+            const result = await startMcpServer(port ?? DEFAULT_PORT);
+            setStatus(result.status);
+            setPort(result.port ?? null);
+          }
+          message.success('MCP token rotated');
+        } catch (err) {
+          const errorMessage = err instanceof Error ? err.message : String(err);
+          message.error(`Failed to rotate MCP token: ${errorMessage}`);
+        }
+      },
+    });
+  }, [modal, status, port, setStatus, setPort, message]);
 
   const handleQuickInstall = useCallback(async () => {
-    const deeplink = generateCursorDeeplink(port ?? DEFAULT_PORT);
+    const deeplink = generateCursorDeeplink(port ?? DEFAULT_PORT, authToken);
     try {
       await openUrl(deeplink);
       message.success('Opening Cursor for MCP installation...');
@@ -109,13 +168,13 @@ export default function McpModal() {
       // Fallback to window.open for browser mode
       window.open(deeplink, '_blank');
     }
-  }, [port, message]);
+  }, [port, authToken, message]);
 
   const handleCopyClaudeCommand = useCallback(() => {
-    const cmd = generateClaudeCommand(port ?? DEFAULT_PORT);
+    const cmd = generateClaudeCommand(port ?? DEFAULT_PORT, authToken);
     navigator.clipboard.writeText(cmd);
     message.success('Command copied! Run it in your terminal.');
-  }, [port, message]);
+  }, [port, authToken, message]);
 
   const handleRunClaudeCommand = useCallback(async () => {
     setLoading(true);
@@ -238,6 +297,27 @@ export default function McpModal() {
           {isRunning ? 'Stop MCP Server' : 'Start MCP Server'}
         </Button>
 
+        {/* Bearer token */}
+        <div>
+          <Text type="secondary" style={{ marginBottom: 8, display: 'block' }}>
+            Auth Token
+          </Text>
+          <Space.Compact block>
+            <Input.Password value={authToken} readOnly style={{ fontFamily: 'monospace' }} />
+            <Button icon={<CopyOutlined />} onClick={handleCopyToken} disabled={!authToken} />
+            <Button icon={<ReloadOutlined />} onClick={handleRotateToken} disabled={loading}>
+              Rotate
+            </Button>
+          </Space.Compact>
+          <Text type="secondary" style={{ fontSize: 12, marginTop: 4, display: 'block' }}>
+            Clients send it as <code>Authorization: Bearer &lt;token&gt;</code>
+          </Text>
+        </div>
+
+        <Button icon={<SafetyOutlined />} onClick={openPolicyModal} block>
+          Permissions
+        </Button>
+
         <Divider style={{ margin: '12px 0' }} />
 
         {/* Cursor Configuration */}
@@ -256,17 +336,7 @@ export default function McpModal() {
             }}
           >
             <pre style={{ margin: 0, whiteSpace: 'pre-wrap', wordBreak: 'break-all' }}>
-              {JSON.stringify(
-                {
-                  mcpServers: {
-                    'socket-io-client': {
-                      url: `http://localhost:${port ?? DEFAULT_PORT}/sse`,
-                    },
-                  },
-                },
-                null,
-                2
-              )}
+              {generateJsonConfig(port ?? DEFAULT_PORT, authToken)}
             </pre>
             <Button
               type="text"
@@ -303,7 +373,7 @@ export default function McpModal() {
             }}
           >
             <code style={{ wordBreak: 'break-all' }}>
-              {generateClaudeCommand(port ?? DEFAULT_PORT)}
+              {generateClaudeCommand(port ?? DEFAULT_PORT, authToken)}
             </code>
             <Button
               type="text"
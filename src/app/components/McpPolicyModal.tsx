@@ -0,0 +1,174 @@
+'use client';
+
+import { useCallback, useEffect, useState } from 'react';
+import { Modal, Switch, Select, Typography, Space, Divider, App } from 'antd';
+import { useMcpStore } from '../stores/mcpStore';
+import { useSocketStore } from '../stores/socketStore';
+import { getMcpPolicy, saveMcpPolicy, McpPolicy, McpEventRules } from '../hooks/useTauri';
+
+const { Text } = Typography;
+
+const EMPTY_POLICY: McpPolicy = {
+  readOnly: false,
+  allowedConnections: null,
+  events: {},
+  confirmWrites: false,
+};
+
+const EMPTY_RULES: McpEventRules = { allow: [], deny: [] };
+
+/**
+ * Editor for the MCP policy: read-only mode, write confirmations,
+ * the connection allowlist and per-connection event rules
+ */
+export default function McpPolicyModal() {
+  const { message } = App.useApp();
+  const [policy, setPolicy] = useState<McpPolicy>(EMPTY_POLICY);
+  const [saving, setSaving] = useState(false);
+
+  const isOpen = useMcpStore((state) => state.isPolicyModalOpen);
+  const closeModal = useMcpStore((state) => state.closePolicyModal);
+  const connections = useSocketStore((state) => state.connections);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    getMcpPolicy()
+      .then(setPolicy)
+      .catch((err) => message.error(`Failed to load MCP policy: ${String(err)}`));
+  }, [isOpen, message]);
+
+  const handleSave = useCallback(async () => {
+    setSaving(true);
+    try {
+      // Drop rules that no longer restrict anything
+      const events = Object.fromEntries(
+        Object.entries(policy.events).filter(
+          ([, rules]) => rules.allow.length > 0 || rules.deny.length > 0
+        )
+      );
+      await saveMcpPolicy({ ...policy, events });
+      message.success('MCP permissions saved');
+      closeModal();
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      message.error(`Failed to save MCP permissions: ${errorMessage}`);
+    } finally {
+      setSaving(false);
+    }
+  }, [policy, closeModal, message]);
+
+  const updateRules = (connectionId: number, rules: Partial<McpEventRules>) => {
+    setPolicy((current) => ({
+      ...current,
+      events: {
+        ...current.events,
+        [connectionId]: { ...(current.events[connectionId] ?? EMPTY_RULES), ...rules },
+      },
+    }));
+  };
+
+  const connectionOptions = connections.map((c) => ({ label: c.name, value: c.id }));
+
+  return (
+    <Modal
+      title="MCP Permissions"
+      open={isOpen}
+      onOk={handleSave}
+      onCancel={closeModal}
+      okText="Save"
+      confirmLoading={saving}
+      width={560}
+      destroyOnHidden
+    >
+      <Space orientation="vertical" style={{ width: '100%' }} size="middle">
+        <div style={{ display: 'flex', justifyContent: 'space-between' }}>
+          <div>
+            <Text strong>Read-only</Text>
+            <Text type="secondary" style={{ fontSize: 12, display: 'block' }}>
+              Block connect, disconnect, send_message and listener tools
+            </Text>
+          </div>
+          <Switch
+            checked={policy.readOnly}
+            onChange={(readOnly) => setPolicy({ ...policy, readOnly })}
+          />
+        </div>
+
+        <div style={{ display: 'flex', justifyContent: 'space-between' }}>
+          <div>
+            <Text strong>Confirm writes</Text>
+            <Text type="secondary" style={{ fontSize: 12, display: 'block' }}>
+              Ask here before each write tool runs; unanswered requests are denied after two
+              minutes
+            </Text>
+          </div>
+          <Switch
+            checked={policy.confirmWrites}
+            onChange={(confirmWrites) => setPolicy({ ...policy, confirmWrites })}
+          />
+        </div>
+
+        <div>
+          <div style={{ display: 'flex', justifyContent: 'space-between' }}>
+            <Text strong>Restrict connections</Text>
+            <Switch
+              checked={policy.allowedConnections !== null}
+              onChange={(restrict) =>
+                setPolicy({ ...policy, allowedConnections: restrict ? [] : null })
+              }
+            />
+          </div>
+          {policy.allowedConnections !== null && (
+            <Select
+              mode="multiple"
+              value={policy.allowedConnections}
+              onChange={(allowedConnections) => setPolicy({ ...policy, allowedConnections })}
+              options={connectionOptions}
+              placeholder="Connections the assistant may open"
+              style={{ width: '100%', marginTop: 8 }}
+            />
+          )}
+        </div>
+
+        <Divider style={{ margin: '4px 0' }} />
+
+        <div>
+          <Text strong style={{ display: 'block' }}>
+            Event rules
+          </Text>
+          <Text type="secondary" style={{ fontSize: 12, display: 'block', marginBottom: 8 }}>
+            Events send_message may emit; any event is allowed when the allow list is empty
+          </Text>
+          <Space orientation="vertical" style={{ width: '100%' }}>
+            {connections.map((connection) => {
+              const rules = policy.events[connection.id] ?? EMPTY_RULES;
+              return (
+                <div key={connection.id}>
+                  <Text style={{ fontSize: 12 }}>{connection.name}</Text>
+                  <Space.Compact block>
+                    <Select
+                      mode="tags"
+                      value={rules.allow}
+                      onChange={(allow) => updateRules(connection.id, { allow })}
+                      placeholder="Allow"
+                      open={false}
+                      style={{ width: '50%' }}
+                    />
+                    <Select
+                      mode="tags"
+                      value={rules.deny}
+                      onChange={(deny) => updateRules(connection.id, { deny })}
+                      placeholder="Deny"
+                      open={false}
+                      style={{ width: '50%' }}
+                    />
+                  </Space.Compact>
+                </div>
+              );
+            })}
+          </Space>
+        </div>
+      </Space>
+    </Modal>
+  );
+}
@@ -0,0 +1,103 @@
+'use client';
+
+import { useCallback, useEffect, useState } from 'react';
+import { Modal, Input, Typography, Space, Radio, App } from 'antd';
+import { getSecretStatus, setSecretPassphrase, SecretStatus } from '../hooks/useTauri';
+
+const { Text } = Typography;
+
+interface SecretsModalProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+/**
+ * Choose how stored secrets are encrypted: a local key file or a passphrase
+ * entered on each start. Every secret is re-encrypted when this changes.
+ */
+export default function SecretsModal({ open, onClose }: SecretsModalProps) {
+  const { message } = App.useApp();
+  const [status, setStatus] = useState<SecretStatus | null>(null);
+  const [source, setSource] = useState<SecretStatus['source']>('keyFile');
+  const [passphrase, setPassphrase] = useState('');
+  const [confirmation, setConfirmation] = useState('');
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    setPassphrase('');
+    setConfirmation('');
+    getSecretStatus()
+      .then((current) => {
+        setStatus(current);
+        setSource(current.source);
+      })
+      .catch((err) => message.error(`Failed to load secret settings: ${String(err)}`));
+  }, [open, message]);
+
+  const mismatch = confirmation !== '' && passphrase !== confirmation;
+  const canSave =
+    !!status &&
+    (source === 'passphrase'
+      ? passphrase !== '' && passphrase === confirmation
+      : status.source === 'passphrase');
+
+  const handleSave = useCallback(async () => {
+    setSaving(true);
+    try {
+      await setSecretPassphrase(source === 'passphrase' ? passphrase : null);
+      message.success(
+        source === 'passphrase'
+          ? 'Secrets are now protected by the passphrase'
+          : 'Secrets are now protected by a key file'
+      );
+      onClose();
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      message.error(`Failed to change secret protection: ${errorMessage}`);
+    } finally {
+      setSaving(false);
+    }
+  }, [source, passphrase, message, onClose]);
+
+  return (
+    <Modal
+      title="Secrets"
+      open={open}
+      onOk={handleSave}
+      onCancel={onClose}
+      okText={status?.source === 'passphrase' && source === 'passphrase' ? 'Change' : 'Save'}
+      okButtonProps={{ disabled: !canSave }}
+      confirmLoading={saving}
+      destroyOnHidden
+    >
+      <Space orientation="vertical" style={{ width: '100%' }} size="middle">
+        <Text type="secondary">
+          Auth tokens and credential options are encrypted at rest. A passphrase keeps them
+          unreadable without it, but must be entered each time the app starts.
+        </Text>
+        <Radio.Group value={source} onChange={(e) => setSource(e.target.value)}>
+          <Radio value="keyFile">Key file</Radio>
+          <Radio value="passphrase">Passphrase</Radio>
+        </Radio.Group>
+        {source === 'passphrase' && (
+          <>
+            <Input.Password
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder={status?.source === 'passphrase' ? 'New passphrase' : 'Passphrase'}
+            />
+            <Input.Password
+              value={confirmation}
+              onChange={(e) => setConfirmation(e.target.value)}
+              onPressEnter={() => canSave && handleSave()}
+              status={mismatch ? 'error' : undefined}
+              placeholder="Confirm passphrase"
+            />
+            {mismatch && <Text type="danger">Passphrases do not match</Text>}
+          </>
+        )}
+      </Space>
+    </Modal>
+  );
+}
@@ -0,0 +1,72 @@
+'use client';
+
+import { useCallback, useState } from 'react';
+import { Modal, Input, Typography, Space } from 'antd';
+import { unlockSecrets } from '../hooks/useTauri';
+
+const { Text } = Typography;
+
+interface SecretsUnlockModalProps {
+  open: boolean;
+  onClose: () => void;
+  onUnlocked: () => void;
+}
+
+/**
+ * Asks for the passphrase when secrets are protected by one.
+ * Until it is entered, connections with encrypted secrets cannot connect.
+ */
+export default function SecretsUnlockModal({ open, onClose, onUnlocked }: SecretsUnlockModalProps) {
+  const [passphrase, setPassphrase] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [unlocking, setUnlocking] = useState(false);
+
+  const handleUnlock = useCallback(async () => {
+    if (!passphrase) return;
+    setUnlocking(true);
+    try {
+      await unlockSecrets(passphrase);
+      setPassphrase('');
+      setError(null);
+      onUnlocked();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setUnlocking(false);
+    }
+  }, [passphrase, onUnlocked]);
+
+  return (
+    <Modal
+      title="Unlock Secrets"
+      open={open}
+      onOk={handleUnlock}
+      onCancel={onClose}
+      okText="Unlock"
+      cancelText="Later"
+      okButtonProps={{ disabled: !passphrase }}
+      confirmLoading={unlocking}
+      maskClosable={false}
+      destroyOnHidden
+    >
+      <Space orientation="vertical" style={{ width: '100%' }}>
+        <Text type="secondary">
+          Saved auth tokens and headers are protected by a passphrase. Enter it to use them this
+          session.
+        </Text>
+        <Input.Password
+          autoFocus
+          value={passphrase}
+          onChange={(e) => {
+            setPassphrase(e.target.value);
+            setError(null);
+          }}
+          onPressEnter={handleUnlock}
+          status={error ? 'error' : undefined}
+          placeholder="Passphrase"
+        />
+        {error && <Text type="danger">{error}</Text>}
+      </Space>
+    </Modal>
+  );
+}
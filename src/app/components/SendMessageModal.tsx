@@ -94,7 +94,11 @@ export default function SendMessageModal({ open, onClose }: SendMessageModalProp
   }, [emitLogs, search]);
 
   const handleSendDirect = useCallback(
-    async (eventName: string, payloadStr: string) => {
+    async (eventName: string, payloadStr: string, redacted = false) => {
+      if (redacted) {
+        message.warning('Edit the message to replace its [REDACTED] values before sending');
+        return;
+      }
       let parsed: unknown;
       try {
         parsed = JSON.parse(payloadStr);
@@ -289,8 +293,8 @@ export default function SendMessageModal({ open, onClose }: SendMessageModalProp
               <HistoryPanel
                 items={filteredHistory}
                 isConnected={isConnected}
-                onSend={(item) => handleSendDirect(item.eventName, item.payload)}
-                onEdit={(item) => openComposeModal(item.eventName, item.payload)}
+                onSend={(item) => handleSendDirect(item.eventName, item.payload, item.redacted)}
+                onEdit={(item) => openComposeModal(item.eventName, item.payload, item.redacted)}
                 onPin={(item) => handlePinFromHistory(item.eventName, item.payload)}
                 onClear={handleClearLogs}
               />
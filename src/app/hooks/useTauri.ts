@@ -289,6 +289,72 @@ export async function getMcpStatus(): Promise<McpStatus> {
   return await invoke('get_mcp_status');
 }
 
+/**
+ * Bearer token MCP clients must send in the Authorization header
+ */
+export async function getMcpAuthToken(): Promise<string> {
+  return await invoke('get_mcp_auth_token');
+}
+
+/**
+ * Replace the MCP bearer token; a running server must be restarted to use it
+ */
+export async function rotateMcpAuthToken(): Promise<string> {
+  return await invoke('rotate_mcp_auth_token');
+}
+
+// MCP policy commands
+export interface McpEventRules {
+  allow: string[];
+  deny: string[];
+}
+
+export interface McpPolicy {
+  readOnly: boolean;
+  allowedConnections: number[] | null;
+  events: Record<string, McpEventRules>;
+  confirmWrites: boolean;
+}
+
+export async function getMcpPolicy(): Promise<McpPolicy> {
+  return await invoke('get_mcp_policy');
+}
+
+export async function saveMcpPolicy(policy: McpPolicy): Promise<void> {
+  await invoke('save_mcp_policy', { policy });
+}
+
+/**
+ * Allow or deny an MCP write tool waiting for confirmation
+ */
+export async function resolveMcpConfirmation(requestId: number, approved: boolean): Promise<void> {
+  await invoke('resolve_mcp_confirmation', { requestId, approved });
+}
+
+// Secrets commands
+export interface SecretStatus {
+  source: 'keyFile' | 'passphrase';
+  locked: boolean;
+}
+
+export async function getSecretStatus(): Promise<SecretStatus> {
+  return await invoke('get_secret_status');
+}
+
+/**
+ * Derive the key from the passphrase for this session; fails if the passphrase is wrong
+ */
+export async function unlockSecrets(passphrase: string): Promise<void> {
+  await invoke('unlock_secrets', { passphrase });
+}
+
+/**
+ * Re-encrypt stored secrets with a passphrase, or with a key file again when null
+ */
+export async function setSecretPassphrase(passphrase: string | null): Promise<void> {
+  await invoke('set_secret_passphrase', { passphrase });
+}
+
 // Shell command output
 export interface ShellOutput {
   code: number | null;
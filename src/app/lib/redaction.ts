@@ -0,0 +1,10 @@
+/** Placeholder the backend stores in place of redacted secrets */
+export const REDACTED_MARKER = '[REDACTED]';
+
+/**
+ * Whether a payload still holds placeholders. Only meaningful for rows stored with the
+ * `redacted` flag; other payloads may contain the text legitimately.
+ */
+export function hasRedactedValues(payload: string): boolean {
+  return payload.includes(REDACTED_MARKER);
+}
@@ -3,6 +3,7 @@
 import { listen } from '@tauri-apps/api/event';
 import { isTauri } from '@tauri-apps/api/core';
 import { useSocketStore, ConnectionStatus } from '@/app/stores/socketStore';
+import { useMcpStore, McpConfirmation } from '@/app/stores/mcpStore';
 
 interface SocketStatusPayload {
   connectionId: number;
@@ -76,6 +77,11 @@ export async function initSocketListeners(): Promise<void> {
       }
     });
 
+    // Listen for MCP write tools waiting for confirmation
+    await listen<McpConfirmation>('mcp:confirmation', ({ payload }) => {
+      useMcpStore.getState().addConfirmation(payload);
+    });
+
     console.log('Socket listeners initialized');
   } catch (err) {
     console.error('Failed to initialize socket listeners:', err);
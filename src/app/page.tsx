@@ -13,6 +13,7 @@ import {
   SendOutlined,
   CloudSyncOutlined,
   SettingOutlined,
+  LockOutlined,
 } from '@ant-design/icons';
 import { useTheme } from './providers';
 import { useSocketStore, useCurrentConnection } from './stores/socketStore';
@@ -25,6 +26,7 @@ import {
   listPinnedMessages,
   listEventHistory,
   getMcpStatus,
+  getSecretStatus,
   socketSetActive,
   socketClearActive,
   socketGetAllStatuses,
@@ -37,6 +39,10 @@ import ConnectionModal from './components/ConnectionModal';
 import SendMessageModal from './components/SendMessageModal';
 import ComposeMessageModal from './components/ComposeMessageModal';
 import McpModal from './components/McpModal';
+import McpPolicyModal from './components/McpPolicyModal';
+import McpConfirmationModal from './components/McpConfirmationModal';
+import SecretsModal from './components/SecretsModal';
+import SecretsUnlockModal from './components/SecretsUnlockModal';
 
 export default function Home() {
   const { message, modal } = App.useApp();
@@ -47,6 +53,8 @@ export default function Home() {
     null
   );
   const [checkingUpdate, setCheckingUpdate] = useState(false);
+  const [isSecretsModalOpen, setIsSecretsModalOpen] = useState(false);
+  const [isUnlockModalOpen, setIsUnlockModalOpen] = useState(false);
 
   const currentConnection = useCurrentConnection();
   const connectionStatus = useSocketStore((state) => state.connectionStatus);
@@ -75,6 +83,7 @@ export default function Home() {
   const isComposeModalOpen = useSocketStore((state) => state.isComposeModalOpen);
   const composeModalEventName = useSocketStore((state) => state.composeModalEventName);
   const composeModalPayload = useSocketStore((state) => state.composeModalPayload);
+  const composeModalRedacted = useSocketStore((state) => state.composeModalRedacted);
   const closeComposeModal = useSocketStore((state) => state.closeComposeModal);
 
   const { connect, disconnect } = useSocket();
@@ -113,6 +122,26 @@ export default function Home() {
     }
   }, [setMcpPort, setMcpStatus]);
 
+  // Secrets protected by a passphrase must be unlocked before they can be used or changed
+  const openSecrets = useCallback(async (onlyIfLocked: boolean) => {
+    try {
+      const status = await getSecretStatus();
+      if (status.locked) {
+        setIsUnlockModalOpen(true);
+      } else if (!onlyIfLocked) {
+        setIsSecretsModalOpen(true);
+      }
+    } catch {
+      // Running in browser mode
+    }
+  }, []);
+
+  const handleUnlocked = useCallback(() => {
+    setIsUnlockModalOpen(false);
+    message.success('Secrets unlocked');
+    initializeApp();
+  }, [initializeApp, message]);
+
   const checkForUpdate = useCallback(async () => {
     setCheckingUpdate(true);
     try {
@@ -177,7 +206,8 @@ export default function Home() {
     initializeApp();
     loadVersion();
     loadMcpStatus();
-  }, [initializeApp, loadMcpStatus, loadVersion]);
+    openSecrets(true);
+  }, [initializeApp, loadMcpStatus, loadVersion, openSecrets]);
 
   useEffect(() => {
     if (currentConnection) {
@@ -311,6 +341,13 @@ export default function Home() {
                 <span style={{ fontSize: 12 }}>MCP</span>
               </Button>
             </Tooltip>
+            <Tooltip title="Secrets">
+              <Button
+                type="text"
+                icon={<LockOutlined />}
+                onClick={() => openSecrets(false)}
+              />
+            </Tooltip>
             <Tooltip title={theme === 'dark' ? 'Light mode' : 'Dark mode'}>
               <Button
                 type="text"
@@ -371,10 +408,21 @@ export default function Home() {
         onClose={closeComposeModal}
         initialEventName={composeModalEventName}
         initialPayload={composeModalPayload}
+        initialRedacted={composeModalRedacted}
       />
 
       {/* MCP Modal */}
       <McpModal />
+      <McpPolicyModal />
+      <McpConfirmationModal />
+
+      {/* Secrets */}
+      <SecretsModal open={isSecretsModalOpen} onClose={() => setIsSecretsModalOpen(false)} />
+      <SecretsUnlockModal
+        open={isUnlockModalOpen}
+        onClose={() => setIsUnlockModalOpen(false)}
+        onUnlocked={handleUnlocked}
+      />
     </div>
   );
 }
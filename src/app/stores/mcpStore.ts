@@ -2,12 +2,22 @@ import { create } from 'zustand';
 
 export type McpServerStatus = 'stopped' | 'running' | 'error';
 
+// An MCP write tool call waiting for the user to allow or deny it
+export interface McpConfirmation {
+  requestId: number;
+  tool: string;
+  arguments: string;
+  connectionId: number | null;
+}
+
 interface McpStore {
   // State
   status: McpServerStatus;
   port: number | null;
   loading: boolean;
   isModalOpen: boolean;
+  isPolicyModalOpen: boolean;
+  confirmations: McpConfirmation[];
 
   // Actions
   setStatus: (status: McpServerStatus) => void;
@@ -15,6 +25,10 @@ interface McpStore {
   setLoading: (loading: boolean) => void;
   openModal: () => void;
   closeModal: () => void;
+  openPolicyModal: () => void;
+  closePolicyModal: () => void;
+  addConfirmation: (confirmation: McpConfirmation) => void;
+  removeConfirmation: (requestId: number) => void;
 }
 
 export const useMcpStore = create<McpStore>((set) => ({
@@ -23,6 +37,8 @@ export const useMcpStore = create<McpStore>((set) => ({
   port: null,
   loading: false,
   isModalOpen: false,
+  isPolicyModalOpen: false,
+  confirmations: [],
 
   // Actions
   setStatus: (status) => set({ status }),
@@ -30,6 +46,14 @@ export const useMcpStore = create<McpStore>((set) => ({
   setLoading: (loading) => set({ loading }),
   openModal: () => set({ isModalOpen: true }),
   closeModal: () => set({ isModalOpen: false }),
+  openPolicyModal: () => set({ isPolicyModalOpen: true }),
+  closePolicyModal: () => set({ isPolicyModalOpen: false }),
+  addConfirmation: (confirmation) =>
+    set((state) => ({ confirmations: [...state.confirmations, confirmation] })),
+  removeConfirmation: (requestId) =>
+    set((state) => ({
+      confirmations: state.confirmations.filter((c) => c.requestId !== requestId),
+    })),
 }));
 
 // Helper selectors
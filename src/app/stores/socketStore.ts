@@ -25,6 +25,7 @@ export interface EmitLog {
   eventName: string;
   payload: string;
   sentAt: string;
+  redacted: boolean;
 }
 
 export interface PinnedMessage {
@@ -51,6 +52,7 @@ export interface EventHistoryItem {
   payload: string;
   timestamp: string;
   direction: string;
+  redacted: boolean;
 }
 
 export type ConnectionStatus = 'disconnected' | 'connecting' | 'connected' | 'error';
@@ -87,6 +89,7 @@ interface SocketStore {
   isComposeModalOpen: boolean;
   composeModalEventName: string;
   composeModalPayload: string;
+  composeModalRedacted: boolean;
 
   // Panel visibility
   showEmitLog: boolean;
@@ -121,7 +124,7 @@ interface SocketStore {
   closeSettingsModal: () => void;
   openSendModal: (eventName?: string, payload?: string) => void;
   closeSendModal: () => void;
-  openComposeModal: (eventName?: string, payload?: string) => void;
+  openComposeModal: (eventName?: string, payload?: string, redacted?: boolean) => void;
   closeComposeModal: () => void;
   toggleEmitLog: () => void;
   togglePinnedList: () => void;
@@ -153,6 +156,7 @@ export const useSocketStore = create<SocketStore>((set) => ({
   isComposeModalOpen: false,
   composeModalEventName: '',
   composeModalPayload: '{}',
+  composeModalRedacted: false,
 
   showEmitLog: true,
   showPinnedList: true,
@@ -274,17 +278,19 @@ export const useSocketStore = create<SocketStore>((set) => ({
       sendModalEventName: '',
       sendModalPayload: '{}',
     }),
-  openComposeModal: (eventName, payload) =>
+  openComposeModal: (eventName, payload, redacted) =>
     set({
       isComposeModalOpen: true,
       composeModalEventName: eventName || '',
       composeModalPayload: payload || '{}',
+      composeModalRedacted: redacted ?? false,
     }),
   closeComposeModal: () =>
     set({
       isComposeModalOpen: false,
       composeModalEventName: '',
       composeModalPayload: '{}',
+      composeModalRedacted: false,
     }),
   toggleEmitLog: () => set((state) => ({ showEmitLog: !state.showEmitLog })),
   togglePinnedList: () => set((state) => ({ showPinnedList: !state.showPinnedList })),